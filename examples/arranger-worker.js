@@ -0,0 +1,13 @@
+// Web Worker that arranges tabs off the UI thread. Each message is a `WorkerRequest`, and the
+// `WorkerResponse` goes back with its snapshot buffer transferred rather than copied. See
+// `src/worker.rs` for the protocol and `arranger.js` for the page side.
+import init, { handleWorkerRequest } from "../pkg/wasm_guitar_tab_generator/guitar_tab_generator.js";
+
+const ready = init();
+
+self.onmessage = async (event) => {
+    await ready;
+    const response = handleWorkerRequest(event.data);
+    const transfer = response.kind === "arranged" ? [response.snapshot.buffer] : [];
+    self.postMessage(response, transfer);
+};
@@ -0,0 +1,57 @@
+// Page side of `arranger-worker.js`: `generate` posts a `WorkerRequest` and resolves to the
+// `ArrangementSet` the worker arranged, or rejects with its `TabError`. Call `init()` on the
+// page before the first `generate`, since the set is rebuilt here with `importBinary`.
+import { ArrangementSet } from "../pkg/wasm_guitar_tab_generator/guitar_tab_generator.js";
+
+/**
+ * @param {Worker} [worker] A worker running `arranger-worker.js`.
+ */
+export function createArranger(
+    worker = new Worker(new URL("./arranger-worker.js", import.meta.url), { type: "module" }),
+) {
+    let nextId = 0;
+    /** @type {Map<number, { resolve: (set: ArrangementSet) => void, reject: (error: unknown) => void }>} */
+    const pending = new Map();
+
+    const rejectAll = (error) => {
+        for (const { reject } of pending.values()) reject(error);
+        pending.clear();
+    };
+
+    worker.onmessage = ({ data }) => {
+        const request = pending.get(data.id);
+        if (request === undefined) return;
+        pending.delete(data.id);
+        if (data.kind === "arranged") {
+            request.resolve(ArrangementSet.importBinary(data.snapshot));
+        } else {
+            request.reject(data.error);
+        }
+    };
+    // A worker that fails to load or throws outside `handleWorkerRequest` never answers, so
+    // every request in flight is rejected rather than left pending.
+    worker.onerror = (event) => {
+        event.preventDefault();
+        rejectAll(new Error(`The arranger worker failed: ${event.message}`));
+    };
+    worker.onmessageerror = () => rejectAll(new Error("The arranger worker sent a message that could not be read."));
+
+    return {
+        /**
+         * @param {import("../pkg/wasm_guitar_tab_generator/guitar_tab_generator.js").TabInput} tabInput
+         * @returns {Promise<ArrangementSet>}
+         */
+        generate(tabInput) {
+            const id = nextId++;
+            return new Promise((resolve, reject) => {
+                pending.set(id, { resolve, reject });
+                worker.postMessage({ id, tabInput });
+            });
+        },
+        /** Stops the worker and rejects the requests still in flight. */
+        terminate() {
+            worker.terminate();
+            rejectAll(new Error("The arranger worker was terminated."));
+        },
+    };
+}
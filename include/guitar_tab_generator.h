@@ -0,0 +1,178 @@
+/* guitar-tab-generator C API. See src/ffi.rs. */
+
+#ifndef GUITAR_TAB_GENERATOR_H
+#define GUITAR_TAB_GENERATOR_H
+
+/* Generated by cbindgen from src/ffi.rs. Do not edit by hand. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+// The outcome of a call. The statuses from 10 up are the [`TabError`] variants.
+typedef enum GtgStatus {
+  GTG_STATUS_OK = 0,
+  // A required pointer argument was `NULL`.
+  GTG_STATUS_NULL_ARGUMENT = 1,
+  // A string argument was not valid UTF-8.
+  GTG_STATUS_INVALID_UTF8 = 2,
+  // The caller's buffer cannot hold the output and its terminating NUL.
+  GTG_STATUS_BUFFER_TOO_SMALL = 3,
+  // The library panicked. The message holds the panic's, and is worth reporting as a bug.
+  GTG_STATUS_INTERNAL = 4,
+  GTG_STATUS_PARSE = 10,
+  GTG_STATUS_INPUT_TOO_MANY_LINES = 11,
+  GTG_STATUS_NUM_FRETS_TOO_HIGH = 12,
+  GTG_STATUS_CAPO_TOO_HIGH = 13,
+  GTG_STATUS_CAPO_EXCEEDS_FRETS = 14,
+  GTG_STATUS_STRING_NUMBER_OUT_OF_RANGE = 15,
+  GTG_STATUS_OPEN_PITCH_OUT_OF_RANGE = 16,
+  GTG_STATUS_FRET_RANGE_EXCEEDS_PITCH_RANGE = 17,
+  GTG_STATUS_UNPLAYABLE_PITCHES = 18,
+  GTG_STATUS_NO_ARRANGEMENTS_FOUND = 19,
+  GTG_STATUS_NUM_ARRANGEMENTS_OUT_OF_RANGE = 20,
+  GTG_STATUS_TUNING_NAME_UNKNOWN = 21,
+  GTG_STATUS_INDEX_OUT_OF_BOUNDS = 22,
+  GTG_STATUS_RENDER_WIDTH_TOO_SMALL = 23,
+  GTG_STATUS_DIFFICULTY_WEIGHT_OUT_OF_RANGE = 24,
+  GTG_STATUS_UNPLAYABLE_CHORD = 25,
+  GTG_STATUS_SNAPSHOT_INVALID = 26,
+  GTG_STATUS_SNAPSHOT_VERSION_UNSUPPORTED = 27,
+} GtgStatus;
+
+// A failed call's status and message. Free with [`gtg_error_free`].
+typedef struct GtgError GtgError;
+
+// A generation request: the input and guitar of a [`TabInput`]. Free with
+// [`gtg_request_free`].
+typedef struct GtgRequest GtgRequest;
+
+// The arrangements of one [`gtg_generate`] call. Free with [`gtg_set_free`].
+typedef struct GtgSet GtgSet;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// Creates a request to arrange `input` on a guitar with `tuning_name`, `num_frets` and
+// `capo`, keeping the `num_arrangements` easiest arrangements. The arguments are checked
+// by [`gtg_generate`], as by `generate_arrangements`.
+//
+// # Safety
+//
+// `input` and `tuning_name` must be `NULL` or NUL-terminated strings. `out_request` must be
+// `NULL` or valid for a write, and `out_error` likewise.
+enum GtgStatus gtg_request_new(const char *input,
+                               const char *tuning_name,
+                               uint8_t num_frets,
+                               uint8_t capo,
+                               uint8_t num_arrangements,
+                               struct GtgRequest **out_request,
+                               struct GtgError **out_error);
+
+// Frees a request from [`gtg_request_new`]. `NULL` is a no-op.
+//
+// # Safety
+//
+// `request` must be `NULL` or a live request from [`gtg_request_new`].
+void gtg_request_free(struct GtgRequest *request);
+
+// Arranges `request` like `generate_arrangements` and writes the new set to `out_set`. The
+// request stays usable.
+//
+// # Safety
+//
+// `request` must be `NULL` or a live request. `out_set` must be `NULL` or valid for a
+// write, and `out_error` likewise.
+enum GtgStatus gtg_generate(const struct GtgRequest *request,
+                            struct GtgSet **out_set,
+                            struct GtgError **out_error);
+
+// Frees a set from [`gtg_generate`]. `NULL` is a no-op.
+//
+// # Safety
+//
+// `set` must be `NULL` or a live set from [`gtg_generate`].
+void gtg_set_free(struct GtgSet *set);
+
+// Number of arrangements in `set`, or 0 for `NULL`.
+//
+// # Safety
+//
+// `set` must be `NULL` or a live set.
+size_t gtg_set_len(const struct GtgSet *set);
+
+// Writes the difficulty of the arrangement at `index` to `out_difficulty`. Lower is easier.
+//
+// # Safety
+//
+// `set` must be `NULL` or a live set. `out_difficulty` must be `NULL` or valid for a write,
+// and `out_error` likewise.
+enum GtgStatus gtg_set_difficulty(const struct GtgSet *set,
+                                  size_t index,
+                                  double *out_difficulty,
+                                  struct GtgError **out_error);
+
+// Writes the largest per-beat fret span of the arrangement at `index` to
+// `out_max_fret_span`.
+//
+// # Safety
+//
+// `set` must be `NULL` or a live set. `out_max_fret_span` must be `NULL` or valid for a
+// write, and `out_error` likewise.
+enum GtgStatus gtg_set_max_fret_span(const struct GtgSet *set,
+                                     size_t index,
+                                     uint8_t *out_max_fret_span,
+                                     struct GtgError **out_error);
+
+// Renders the arrangement at `index` like `ArrangementSet::render` into `buffer` as a
+// NUL-terminated string.
+//
+// `out_len` receives the length of the tab in bytes, without the NUL, even when `buffer` is
+// too small. So a caller can pass a `NULL` buffer of length 0 to size it, then render
+// again. A buffer without room for the NUL fails with `GTG_STATUS_BUFFER_TOO_SMALL` and is
+// left untouched.
+//
+// # Safety
+//
+// `set` must be `NULL` or a live set. `buffer` must be valid for `buffer_len` bytes of
+// writes, or `NULL` with `buffer_len` 0. `out_len` must be `NULL` or valid for a write, and
+// `out_error` likewise.
+enum GtgStatus gtg_set_render(const struct GtgSet *set,
+                              size_t index,
+                              uint16_t width,
+                              uint8_t padding,
+                              char *buffer,
+                              size_t buffer_len,
+                              size_t *out_len,
+                              struct GtgError **out_error);
+
+// The status `error` failed with, or `GTG_STATUS_NULL_ARGUMENT` for `NULL`.
+//
+// # Safety
+//
+// `error` must be `NULL` or a live error.
+enum GtgStatus gtg_error_status(const struct GtgError *error);
+
+// The message of `error` as a NUL-terminated string owned by the error, or `NULL` for
+// `NULL`. It is freed with the error.
+//
+// # Safety
+//
+// `error` must be `NULL` or a live error.
+const char *gtg_error_message(const struct GtgError *error);
+
+// Frees an error handed back by a failed call. `NULL` is a no-op.
+//
+// # Safety
+//
+// `error` must be `NULL` or a live error from this library.
+void gtg_error_free(struct GtgError *error);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* GUITAR_TAB_GENERATOR_H */
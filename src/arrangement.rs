@@ -1,27 +1,101 @@
 use crate::{
     DifficultyWeights,
     error::{TabError, UnplayablePitch},
-    guitar::{Guitar, PitchFingering, generate_pitch_fingerings},
+    guitar::{
+        Guitar, Harmonic, PitchFingering, generate_harmonic_fingerings, generate_pitch_fingerings,
+    },
+    parser::ParsedLine,
     pitch::Pitch,
+    string_number::StringNumber,
+    technique::Technique,
+    voice::{Voice, pitch_voices},
 };
 use itertools::Itertools;
 use memoize::memoize;
 use ordered_float::OrderedFloat;
 use pathfinding::prelude::yen;
+use serde::{Deserialize, Serialize};
 use std::{collections::HashSet, rc::Rc};
+#[cfg(feature = "wasm")]
+use tsify::Tsify;
 
 /// One logical line of a parsed or arranged composition.
 ///
 /// `Playable` holds the line's content (pitches during parsing, fingerings after
 /// arrangement). `Rest` is an empty or comment-only line. `MeasureBreak` is a bar
 /// line drawn in the rendered tab.
+///
+/// `RepeatStart` (`|:`), `RepeatEnd` (`:|`), `Ending` (a numbered volta such as `[1.]`)
+/// and `Section` (a label such as `[Verse]`) are structural markers. Like `MeasureBreak`
+/// they carry no beat: pathfinding skips them and the arrangement re-injects them in place.
+///
+/// `Technique` is a marker too, but a linking one (hammer-on, pull-off, slide, bend) also
+/// constrains pathfinding: the beats on either side must be played on the same strings.
+/// `LetRing` lets the beat before it ring for a number of beats, or to the end for `None`,
+/// and pathfinding penalizes any later note that cuts it off.
 #[derive(Debug, Clone, Hash, Eq, PartialEq, Ord, PartialOrd)]
 pub enum Line<T> {
     MeasureBreak,
+    RepeatStart,
+    RepeatEnd,
+    Ending(u8),
+    Section(String),
+    Technique(Technique),
+    LetRing(Option<u8>),
     Rest,
     Playable(T),
 }
-use Line::{MeasureBreak, Playable, Rest};
+use Line::{Ending, MeasureBreak, Playable, RepeatEnd, RepeatStart, Rest, Section};
+
+impl<T> Line<T> {
+    /// Returns true for `Playable` and `Rest`, the lines that occupy a beat in time. Every
+    /// other variant is a structural marker.
+    #[must_use]
+    pub fn is_beat(&self) -> bool {
+        matches!(self, Playable(_) | Rest)
+    }
+
+    /// Re-types a structural marker for another `Playable` payload. `None` for a beat.
+    pub(crate) fn to_marker<U>(&self) -> Option<Line<U>> {
+        match self {
+            MeasureBreak => Some(MeasureBreak),
+            RepeatStart => Some(RepeatStart),
+            RepeatEnd => Some(RepeatEnd),
+            Ending(number) => Some(Ending(*number)),
+            Section(label) => Some(Section(label.clone())),
+            Line::Technique(technique) => Some(Line::Technique(*technique)),
+            Line::LetRing(beats) => Some(Line::LetRing(*beats)),
+            Rest | Playable(_) => None,
+        }
+    }
+}
+#[cfg(test)]
+mod test_line {
+    use super::*;
+
+    #[test]
+    fn beats_are_playable_and_rest() {
+        assert!(Line::Playable(vec![Pitch::E2]).is_beat());
+        assert!(Line::<BeatVec<Pitch>>::Rest.is_beat());
+        assert!(!Line::<BeatVec<Pitch>>::MeasureBreak.is_beat());
+        assert!(!Line::<BeatVec<Pitch>>::RepeatStart.is_beat());
+        assert!(!Line::<BeatVec<Pitch>>::Section("Verse".to_owned()).is_beat());
+    }
+    #[test]
+    fn to_marker_retypes_markers_only() {
+        let section: Line<BeatVec<Pitch>> = Section("Chorus".to_owned());
+        assert_eq!(
+            section.to_marker::<u8>(),
+            Some(Section("Chorus".to_owned()))
+        );
+        assert_eq!(
+            Ending::<BeatVec<Pitch>>(2).to_marker::<u8>(),
+            Some(Ending(2))
+        );
+        assert_eq!(Line::<BeatVec<Pitch>>::Rest.to_marker::<u8>(), None);
+        assert_eq!(Line::Playable(vec![Pitch::E2]).to_marker::<u8>(), None);
+    }
+}
 
 #[derive(Debug, Clone, Hash, Eq, PartialEq, Ord, PartialOrd)]
 enum Node {
@@ -40,24 +114,295 @@ pub(crate) type PitchVec<T> = Vec<T>;
 /// One beat's worth of items (usually `Pitch` or `PitchFingering`).
 pub type BeatVec<T> = Vec<T>;
 
-/// Index of the first `Playable` line in `lines`, or `0` if the sequence has none.
+/// Index of the first line in `lines` that is neither a `Rest` nor a `MeasureBreak`, or `0`
+/// if the sequence has none.
 ///
-/// Both `generate_arrangements` and `create_arrangements` skip leading rests before
-/// shipping the input downstream, so the predicate lives in one place.
-pub(crate) fn first_playable_index<T>(lines: &[Line<T>]) -> usize {
+/// Both `generate_arrangements` and `create_arrangements` skip leading rests and bar lines
+/// before shipping the input downstream, so the predicate lives in one place. A leading
+/// repeat or section marker stops the skip, so an opening `|:` or `[Intro]` is kept.
+pub(crate) fn first_content_index<'a, T: 'a>(
+    lines: impl IntoIterator<Item = &'a Line<T>>,
+) -> usize {
     lines
-        .iter()
-        .position(|line| matches!(line, Playable(_)))
+        .into_iter()
+        .position(|line| !matches!(line, Rest | MeasureBreak))
         .unwrap_or(0)
 }
+#[cfg(test)]
+mod test_first_content_index {
+    use super::*;
+
+    #[test]
+    fn skips_leading_rests_and_measure_breaks() {
+        let lines = vec![Rest, MeasureBreak, Rest, Playable(vec![Pitch::E2])];
+        assert_eq!(first_content_index(&lines), 3);
+    }
+    #[test]
+    fn stops_at_leading_marker() {
+        let lines = vec![Rest, Section("Intro".to_owned()), Playable(vec![Pitch::E2])];
+        assert_eq!(first_content_index(&lines), 1);
+    }
+    #[test]
+    fn falls_back_to_zero() {
+        let lines: Vec<Line<BeatVec<Pitch>>> = vec![Rest, MeasureBreak];
+        assert_eq!(first_content_index(&lines), 0);
+    }
+}
+
+/// Unrolls repeats and volta endings into the linear order the lines are played in.
+///
+/// A `RepeatEnd` jumps back once to the most recent `RepeatStart`, or to the top when there
+/// is none. On the second pass an `Ending(1)` bracket is skipped up to the matching
+/// `Ending(2)`, or past the `RepeatEnd` when there is no second ending. The repeat and
+/// ending markers are consumed. `MeasureBreak`, `Section`, `Technique` and `LetRing` lines
+/// are kept, so the output still renders as a tab. Use it for playback or MIDI export, where
+/// time runs straight.
+#[must_use]
+pub fn expand_repeats<T: Clone>(lines: &[Line<T>]) -> Vec<Line<T>> {
+    expanded_line_indices(lines)
+        .into_iter()
+        .map(|line_index| lines[line_index].clone())
+        .collect()
+}
+
+/// Unrolls the repeats of parsed input like [`expand_repeats`], so each line keeps its
+/// annotations and input line number through the expansion.
+pub(crate) fn expand_parsed_repeats(parsed_lines: &[ParsedLine]) -> Vec<ParsedLine> {
+    // The walk only tells markers from beats, so it runs on the lines' shapes.
+    let shapes: Vec<Line<()>> = parsed_lines
+        .iter()
+        .map(|parsed| parsed.line.to_marker().unwrap_or(Rest))
+        .collect_vec();
+    expanded_line_indices(&shapes)
+        .into_iter()
+        .map(|line_index| parsed_lines[line_index].clone())
+        .collect()
+}
+
+/// The index in `lines` of each line [`expand_repeats`] emits, in play order.
+fn expanded_line_indices<T>(lines: &[Line<T>]) -> Vec<usize> {
+    let mut expanded: Vec<usize> = Vec::with_capacity(lines.len());
+    let mut taken_repeat_ends: HashSet<usize> = HashSet::new();
+    let mut repeat_start_index = 0;
+    let mut pass: u8 = 1;
+    let mut line_index = 0;
+
+    while let Some(line) = lines.get(line_index) {
+        match line {
+            RepeatStart => {
+                repeat_start_index = line_index + 1;
+                pass = 1;
+            }
+            RepeatEnd => {
+                // Each repeat is taken once, so the walk always terminates.
+                if taken_repeat_ends.insert(line_index) {
+                    line_index = repeat_start_index;
+                    pass = 2;
+                    continue;
+                }
+            }
+            Ending(number) if *number != pass => {
+                line_index = skip_ending(lines, line_index, pass);
+                continue;
+            }
+            Ending(_) => {}
+            MeasureBreak
+            | Section(_)
+            | Line::Technique(_)
+            | Line::LetRing(_)
+            | Rest
+            | Playable(_) => expanded.push(line_index),
+        }
+        line_index += 1;
+    }
+
+    expanded
+}
+#[cfg(test)]
+mod test_expanded_line_indices {
+    use super::*;
+
+    #[test]
+    fn repeat_revisits_indices() {
+        let lines: Vec<Line<u8>> = vec![
+            Playable(0),
+            RepeatStart,
+            Playable(1),
+            RepeatEnd,
+            Playable(2),
+        ];
+        assert_eq!(expanded_line_indices(&lines), vec![0, 2, 2, 4]);
+    }
+}
+#[cfg(test)]
+mod test_expand_parsed_repeats {
+    use super::*;
+    use crate::duration::Duration;
+
+    #[test]
+    fn lines_keep_their_input_lines_and_annotations() {
+        let parsed_lines = crate::parser::parse_input("|:\nE2 @q\n:|\nA2".to_owned()).unwrap();
+        let expanded = expand_parsed_repeats(&parsed_lines);
+        assert_eq!(
+            expanded
+                .iter()
+                .map(|parsed| parsed.input_line)
+                .collect_vec(),
+            [2, 2, 4]
+        );
+        assert_eq!(expanded[1].duration, Duration::from_symbol("q"));
+    }
+}
+#[cfg(test)]
+mod test_expand_repeats {
+    use super::*;
+
+    fn beat(pitch: Pitch) -> Line<BeatVec<Pitch>> {
+        Playable(vec![pitch])
+    }
+
+    #[test]
+    fn no_markers_is_unchanged() {
+        let lines = vec![beat(Pitch::E2), MeasureBreak, Rest, beat(Pitch::A2)];
+        assert_eq!(expand_repeats(&lines), lines);
+    }
+    #[test]
+    fn simple_repeat_plays_twice() {
+        let lines = vec![
+            beat(Pitch::E2),
+            RepeatStart,
+            beat(Pitch::A2),
+            RepeatEnd,
+            beat(Pitch::D3),
+        ];
+        assert_eq!(
+            expand_repeats(&lines),
+            vec![
+                beat(Pitch::E2),
+                beat(Pitch::A2),
+                beat(Pitch::A2),
+                beat(Pitch::D3)
+            ]
+        );
+    }
+    #[test]
+    fn repeat_end_without_start_jumps_to_top() {
+        let lines = vec![beat(Pitch::E2), RepeatEnd, beat(Pitch::A2)];
+        assert_eq!(
+            expand_repeats(&lines),
+            vec![beat(Pitch::E2), beat(Pitch::E2), beat(Pitch::A2)]
+        );
+    }
+    #[test]
+    fn volta_endings_alternate() {
+        let lines = vec![
+            RepeatStart,
+            beat(Pitch::E2),
+            Ending(1),
+            beat(Pitch::A2),
+            RepeatEnd,
+            Ending(2),
+            beat(Pitch::D3),
+            beat(Pitch::G3),
+        ];
+        assert_eq!(
+            expand_repeats(&lines),
+            vec![
+                beat(Pitch::E2),
+                beat(Pitch::A2),
+                beat(Pitch::E2),
+                beat(Pitch::D3),
+                beat(Pitch::G3)
+            ]
+        );
+    }
+    #[test]
+    fn first_ending_without_second_skips_past_repeat_end() {
+        let lines = vec![
+            RepeatStart,
+            beat(Pitch::E2),
+            Ending(1),
+            beat(Pitch::A2),
+            RepeatEnd,
+            beat(Pitch::D3),
+        ];
+        assert_eq!(
+            expand_repeats(&lines),
+            vec![
+                beat(Pitch::E2),
+                beat(Pitch::A2),
+                beat(Pitch::E2),
+                beat(Pitch::D3)
+            ]
+        );
+    }
+    #[test]
+    fn keeps_sections_and_measure_breaks() {
+        let lines = vec![
+            Section("Verse".to_owned()),
+            RepeatStart,
+            beat(Pitch::E2),
+            MeasureBreak,
+            RepeatEnd,
+        ];
+        assert_eq!(
+            expand_repeats(&lines),
+            vec![
+                Section("Verse".to_owned()),
+                beat(Pitch::E2),
+                MeasureBreak,
+                beat(Pitch::E2),
+                MeasureBreak,
+            ]
+        );
+    }
+}
+
+/// Index to resume at when `pass` reaches an `Ending` bracket at `ending_index` that belongs
+/// to another pass: the next `Ending(pass)`, else the line after the next `RepeatEnd`, else
+/// the end of `lines`.
+fn skip_ending<T>(lines: &[Line<T>], ending_index: usize, pass: u8) -> usize {
+    let rest = &lines[ending_index + 1..];
+    let next_ending = rest
+        .iter()
+        .position(|line| matches!(line, Ending(number) if *number == pass));
+    let next_repeat_end = rest.iter().position(|line| matches!(line, RepeatEnd));
+    match (next_ending, next_repeat_end) {
+        (Some(offset), _) => ending_index + 1 + offset,
+        (None, Some(offset)) => ending_index + 2 + offset,
+        (None, None) => lines.len(),
+    }
+}
+#[cfg(test)]
+mod test_skip_ending {
+    use super::*;
+
+    #[test]
+    fn jumps_to_matching_ending() {
+        let lines: Vec<Line<u8>> = vec![Ending(1), Playable(0), RepeatEnd, Ending(2)];
+        assert_eq!(skip_ending(&lines, 0, 2), 3);
+    }
+    #[test]
+    fn falls_back_past_repeat_end() {
+        let lines: Vec<Line<u8>> = vec![Ending(1), Playable(0), RepeatEnd, Playable(1)];
+        assert_eq!(skip_ending(&lines, 0, 2), 3);
+    }
+    #[test]
+    fn falls_back_to_end() {
+        let lines: Vec<Line<u8>> = vec![Ending(1), Playable(0)];
+        assert_eq!(skip_ending(&lines, 0, 2), 2);
+    }
+}
 
 /// A single playable assignment of fingerings for one beat, with precomputed difficulty
-/// features (average non-zero fret, non-zero fret span).
+/// features (average non-zero fret, non-zero fret span, harmonic and voice penalties).
 #[derive(Debug, Clone, Hash, Eq, PartialEq, Ord, PartialOrd)]
 pub(crate) struct ScoredBeatFingering {
     beat_fingering: BeatVec<PitchFingering>,
     avg_non_zero_fret: Option<OrderedFloat<f64>>,
     non_zero_fret_span: u8,
+    harmonic_penalty: u8,
+    voice_penalty: u8,
 }
 impl ScoredBeatFingering {
     /// Builds a `ScoredBeatFingering` from a per-beat `PitchFingering` list, precomputing
@@ -65,11 +410,15 @@ impl ScoredBeatFingering {
     pub(crate) fn new(beat_fingering_candidate: BeatVec<PitchFingering>) -> Self {
         let avg_non_zero_fret = calc_avg_non_zero_fret(&beat_fingering_candidate);
         let non_zero_fret_span = calc_fret_span(&beat_fingering_candidate).unwrap_or(0);
+        let harmonic_penalty = calc_harmonic_penalty(&beat_fingering_candidate);
+        let voice_penalty = calc_voice_penalty(&beat_fingering_candidate);
 
         ScoredBeatFingering {
             beat_fingering: beat_fingering_candidate,
             avg_non_zero_fret,
             non_zero_fret_span,
+            harmonic_penalty,
+            voice_penalty,
         }
     }
 }
@@ -84,17 +433,23 @@ mod test_create_scored_beat_fingering {
             pitch: Pitch::A0,
             string_number: StringNumber::new(1).unwrap(),
             fret: 2,
+            harmonic: None,
+            voice: None,
         };
 
         let ScoredBeatFingering {
             beat_fingering,
             avg_non_zero_fret,
             non_zero_fret_span,
+            harmonic_penalty,
+            voice_penalty,
         } = ScoredBeatFingering::new(vec![pitch_fingering_1]);
 
         assert_eq!(beat_fingering, vec![pitch_fingering_1]);
         assert_eq!(avg_non_zero_fret, Some(OrderedFloat(2.0)));
         assert_eq!(non_zero_fret_span, 0);
+        assert_eq!(harmonic_penalty, 0);
+        assert_eq!(voice_penalty, 0);
     }
     #[test]
     fn complex() {
@@ -102,27 +457,37 @@ mod test_create_scored_beat_fingering {
             pitch: Pitch::A0,
             string_number: StringNumber::new(1).unwrap(),
             fret: 2,
+            harmonic: None,
+            voice: None,
         };
         let pitch_fingering_2 = PitchFingering {
             pitch: Pitch::B1,
             string_number: StringNumber::new(2).unwrap(),
             fret: 5,
+            harmonic: None,
+            voice: None,
         };
         let pitch_fingering_3 = PitchFingering {
             pitch: Pitch::C2,
             string_number: StringNumber::new(3).unwrap(),
             fret: 0,
+            harmonic: None,
+            voice: None,
         };
         let pitch_fingering_4 = PitchFingering {
             pitch: Pitch::D3,
             string_number: StringNumber::new(4).unwrap(),
             fret: 1,
+            harmonic: None,
+            voice: None,
         };
 
         let ScoredBeatFingering {
             beat_fingering,
             avg_non_zero_fret,
             non_zero_fret_span,
+            harmonic_penalty,
+            voice_penalty,
         } = ScoredBeatFingering::new(vec![
             pitch_fingering_1,
             pitch_fingering_2,
@@ -141,6 +506,116 @@ mod test_create_scored_beat_fingering {
         );
         assert_eq!(avg_non_zero_fret, Some(OrderedFloat(8.0 / 3.0)));
         assert_eq!(non_zero_fret_span, 4);
+        assert_eq!(harmonic_penalty, 0);
+        assert_eq!(voice_penalty, 0);
+    }
+}
+
+/// Extra cost of a natural harmonic, in frets of stretch. Scaled by the `span` weight.
+const NATURAL_HARMONIC_PENALTY: u8 = 2;
+/// Extra cost of an artificial harmonic, in frets of stretch. Scaled by the `span` weight.
+/// Higher than a natural harmonic because the picking hand frets the touch point too.
+const ARTIFICIAL_HARMONIC_PENALTY: u8 = 5;
+
+/// Sums the harmonic penalties of a beat's fingerings. `0` when every note is fretted.
+fn calc_harmonic_penalty(beat_fingering_candidate: &[PitchFingering]) -> u8 {
+    beat_fingering_candidate
+        .iter()
+        .map(|fingering| match fingering.harmonic {
+            None => 0,
+            Some(Harmonic::Natural) => NATURAL_HARMONIC_PENALTY,
+            Some(Harmonic::Artificial) => ARTIFICIAL_HARMONIC_PENALTY,
+        })
+        .fold(0, u8::saturating_add)
+}
+#[cfg(test)]
+mod test_calc_harmonic_penalty {
+    use super::*;
+    use crate::string_number::StringNumber;
+
+    fn fingering(harmonic: Option<Harmonic>) -> PitchFingering {
+        PitchFingering {
+            pitch: Pitch::E5,
+            string_number: StringNumber::new(1).unwrap(),
+            fret: 12,
+            harmonic,
+            voice: None,
+        }
+    }
+
+    #[test]
+    fn fretted_notes_cost_nothing() {
+        assert_eq!(calc_harmonic_penalty(&[fingering(None)]), 0);
+        assert_eq!(calc_harmonic_penalty(&[]), 0);
+    }
+    #[test]
+    fn harmonics_add_up() {
+        assert_eq!(
+            calc_harmonic_penalty(&[
+                fingering(Some(Harmonic::Natural)),
+                fingering(Some(Harmonic::Artificial)),
+                fingering(None),
+            ]),
+            NATURAL_HARMONIC_PENALTY + ARTIFICIAL_HARMONIC_PENALTY
+        );
+    }
+}
+
+/// Extra cost of a melody note off the treble strings, or a bass note off the bass strings,
+/// in frets of stretch. Scaled by the `span` weight.
+const VOICE_STRING_PENALTY: u8 = 1;
+
+/// Sums the voice penalties of a beat's fingerings. `0` for single-voice input.
+fn calc_voice_penalty(beat_fingering_candidate: &[PitchFingering]) -> u8 {
+    beat_fingering_candidate
+        .iter()
+        .filter(|fingering| {
+            fingering
+                .voice
+                .is_some_and(|voice| voice.is_off_its_strings(fingering.string_number))
+        })
+        .map(|_| VOICE_STRING_PENALTY)
+        .fold(0, u8::saturating_add)
+}
+#[cfg(test)]
+mod test_calc_voice_penalty {
+    use super::*;
+    use crate::string_number::StringNumber;
+
+    fn fingering(string_number: u8, voice: Option<Voice>) -> PitchFingering {
+        PitchFingering {
+            pitch: Pitch::E4,
+            string_number: StringNumber::new(string_number).unwrap(),
+            fret: 0,
+            harmonic: None,
+            voice,
+        }
+    }
+
+    #[test]
+    fn single_voice_costs_nothing() {
+        assert_eq!(calc_voice_penalty(&[fingering(6, None)]), 0);
+    }
+    #[test]
+    fn voices_on_their_strings_cost_nothing() {
+        assert_eq!(
+            calc_voice_penalty(&[
+                fingering(1, Some(Voice::Melody)),
+                fingering(5, Some(Voice::Inner)),
+                fingering(6, Some(Voice::Bass)),
+            ]),
+            0
+        );
+    }
+    #[test]
+    fn voices_off_their_strings_add_up() {
+        assert_eq!(
+            calc_voice_penalty(&[
+                fingering(4, Some(Voice::Melody)),
+                fingering(2, Some(Voice::Bass)),
+            ]),
+            2 * VOICE_STRING_PENALTY
+        );
     }
 }
 
@@ -167,6 +642,8 @@ mod test_calc_avg_non_zero_fret {
             pitch: Pitch::A0,
             string_number: StringNumber::new(1).unwrap(),
             fret: 2,
+            harmonic: None,
+            voice: None,
         };
 
         assert_eq!(
@@ -180,6 +657,8 @@ mod test_calc_avg_non_zero_fret {
             pitch: Pitch::A0,
             string_number: StringNumber::new(1).unwrap(),
             fret: 0,
+            harmonic: None,
+            voice: None,
         };
 
         assert_eq!(calc_avg_non_zero_fret(&[pitch_fingering_1]), None);
@@ -190,11 +669,15 @@ mod test_calc_avg_non_zero_fret {
             pitch: Pitch::A0,
             string_number: StringNumber::new(1).unwrap(),
             fret: 0,
+            harmonic: None,
+            voice: None,
         };
         let pitch_fingering_2 = PitchFingering {
             pitch: Pitch::B2,
             string_number: StringNumber::new(2).unwrap(),
             fret: 0,
+            harmonic: None,
+            voice: None,
         };
 
         assert_eq!(
@@ -208,21 +691,29 @@ mod test_calc_avg_non_zero_fret {
             pitch: Pitch::A0,
             string_number: StringNumber::new(1).unwrap(),
             fret: 2,
+            harmonic: None,
+            voice: None,
         };
         let pitch_fingering_2 = PitchFingering {
             pitch: Pitch::B1,
             string_number: StringNumber::new(2).unwrap(),
             fret: 5,
+            harmonic: None,
+            voice: None,
         };
         let pitch_fingering_3 = PitchFingering {
             pitch: Pitch::C2,
             string_number: StringNumber::new(3).unwrap(),
             fret: 0,
+            harmonic: None,
+            voice: None,
         };
         let pitch_fingering_4 = PitchFingering {
             pitch: Pitch::D3,
             string_number: StringNumber::new(4).unwrap(),
             fret: 1,
+            harmonic: None,
+            voice: None,
         };
 
         assert_eq!(
@@ -237,12 +728,27 @@ mod test_calc_avg_non_zero_fret {
     }
 }
 
+/// A ringing note that a later note on the same string cuts off, with 1-indexed line numbers.
+/// The note rings from a `LetRing` marker or a voice column held with `:N`.
+///
+/// `line` is the ringing note's line and `cut_off_line` the line of the note that silences it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "wasm", derive(Tsify), tsify(into_wasm_abi))]
+#[serde(rename_all = "camelCase")]
+pub struct CutOffNote {
+    pub pitch: String,
+    pub string: u8,
+    pub line: u32,
+    pub cut_off_line: u32,
+}
+
 /// A single ranked guitar arrangement: one fingering choice per beat, ordered by line.
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct Arrangement {
     pub(crate) lines: Vec<Line<BeatVec<PitchFingering>>>,
     difficulty: OrderedFloat<f64>,
     max_fret_span: u8,
+    cut_off_notes: Vec<CutOffNote>,
 }
 impl Arrangement {
     /// Pass directly to [`crate::render_tab`].
@@ -265,6 +771,30 @@ impl Arrangement {
     pub fn difficulty(&self) -> f64 {
         self.difficulty.into_inner()
     }
+
+    /// The ringing notes this arrangement cuts off by replaying their string while they
+    /// should still ring, in input order. Empty when the input has no `LetRing` marker and
+    /// no held voice.
+    #[must_use]
+    pub fn cut_off_notes(&self) -> &[CutOffNote] {
+        &self.cut_off_notes
+    }
+
+    /// Rebuilds an arrangement from the parts an exported set recorded, without
+    /// pathfinding.
+    pub(crate) fn from_parts(
+        lines: Vec<Line<BeatVec<PitchFingering>>>,
+        difficulty: f64,
+        max_fret_span: u8,
+        cut_off_notes: Vec<CutOffNote>,
+    ) -> Self {
+        Arrangement {
+            lines,
+            difficulty: OrderedFloat(difficulty),
+            max_fret_span,
+            cut_off_notes,
+        }
+    }
 }
 #[cfg(test)]
 mod test_max_fret_span {
@@ -276,6 +806,7 @@ mod test_max_fret_span {
             lines: vec![],
             difficulty: OrderedFloat(4.0),
             max_fret_span: 5,
+            cut_off_notes: vec![],
         };
         assert_eq!(arrangement.max_fret_span(), 5);
     }
@@ -294,7 +825,7 @@ mod test_max_fret_span {
 /// # Panics
 ///
 /// Panics only if an internal invariant is violated (a BUG condition, not reachable
-/// under any valid input): a `MeasureBreak` line leaking past the pathfinding filter,
+/// under any valid input): a structural marker line leaking past the pathfinding filter,
 /// or a `Node::Start` appearing as a future node during path traversal.
 #[memoize(Capacity: 10)]
 pub fn create_arrangements(
@@ -303,63 +834,144 @@ pub fn create_arrangements(
     num_arrangements: crate::NumArrangements,
     difficulty_weights: crate::DifficultyWeights,
     max_fret_span_filter: Option<u8>,
+) -> Result<Vec<Arrangement>, TabError> {
+    arrange(
+        guitar,
+        ParsedLine::from_lines(input_lines),
+        num_arrangements,
+        difficulty_weights,
+        max_fret_span_filter,
+    )
+}
+
+/// Like [`create_arrangements`], for the output of [`crate::parse_input`], whose
+/// annotations steer the arrangement.
+///
+/// Each `PitchFingering` of a line with voice columns remembers its voice. The melody is
+/// steered onto the treble strings and the bass onto the bass strings, and a column held with
+/// `:N` rings like a `LetRing` for its voice only.
+///
+/// A chord line's beat is not played as parsed. Its candidates are every voicing of the chord
+/// the guitar can strum, across all positions, so pathfinding picks the easiest voicing and
+/// octave. The chosen voicing is the beat's fingerings in each returned `Arrangement`.
+///
+/// Errors report each line's [`ParsedLine::input_line`], so they point at the input even
+/// after [`expand_repeats`]-style expansion.
+///
+/// # Errors
+///
+/// Fails like [`create_arrangements`], or with [`TabError::UnplayableChord`] when a chord has
+/// no voicing on `guitar`.
+///
+/// # Panics
+///
+/// Panics only on the internal invariant violations [`create_arrangements`] lists.
+#[memoize(Capacity: 10)]
+pub fn create_parsed_arrangements(
+    guitar: Guitar,
+    parsed_lines: Vec<ParsedLine>,
+    num_arrangements: crate::NumArrangements,
+    difficulty_weights: crate::DifficultyWeights,
+    max_fret_span_filter: Option<u8>,
+) -> Result<Vec<Arrangement>, TabError> {
+    arrange(
+        guitar,
+        parsed_lines,
+        num_arrangements,
+        difficulty_weights,
+        max_fret_span_filter,
+    )
+}
+
+/// Shared body of [`create_arrangements`] and [`create_parsed_arrangements`].
+fn arrange(
+    guitar: Guitar,
+    mut parsed_lines: Vec<ParsedLine>,
+    num_arrangements: crate::NumArrangements,
+    difficulty_weights: crate::DifficultyWeights,
+    max_fret_span_filter: Option<u8>,
 ) -> Result<Vec<Arrangement>, TabError> {
     // Reject input past the cap up front: each beat's line index is cast to `u16` below, so a
     // longer sequence would silently wrap. `parse_lines` enforces the same bound, so this only
     // fires for a direct caller that skips it.
-    if input_lines.len() > crate::parser::MAX_INPUT_LINES {
+    if parsed_lines.len() > crate::parser::MAX_INPUT_LINES {
         return Err(TabError::InputTooManyLines {
             max: crate::parser::MAX_INPUT_LINES as u32,
         });
     }
 
-    let input_playable_lines = input_lines
+    if !parsed_lines
         .iter()
-        .filter(|line| matches!(line, Line::Playable(_)))
-        .collect_vec();
-    if input_playable_lines.is_empty() {
+        .any(|parsed| matches!(parsed.line, Line::Playable(_)))
+    {
         let empty_arrangements = vec![
             Arrangement {
                 lines: vec![],
                 difficulty: OrderedFloat(0.0),
                 max_fret_span: 0,
+                cut_off_notes: vec![],
             };
             num_arrangements.get() as usize
         ];
         return Ok(empty_arrangements);
     }
 
-    let first_playable_index = first_playable_index(&input_lines);
-
-    // Validate against the full input so `UnplayablePitch.line` carries the original 1-indexed
-    // input line, then drop the leading rests for pathfinding. Skipping before validation would
-    // report the line relative to the post-skip beat sequence (off by the leading-rest count).
+    // Drop the leading rests for pathfinding. Errors and cut-off notes report each line's
+    // own input line, so skipping first does not shift them.
+    let first_content_index = first_content_index(parsed_lines.iter().map(|parsed| &parsed.line));
+    let parsed_lines = &mut parsed_lines[first_content_index..];
+    let chord_voicings = expand_chords(&guitar, parsed_lines)?;
     let pitch_fingering_candidates: Vec<Line<BeatVec<PitchVec<PitchFingering>>>> =
-        validate_fingerings(&guitar, &input_lines)?
-            .into_iter()
-            .skip(first_playable_index)
-            .collect_vec();
+        validate_fingerings(&guitar, parsed_lines)?;
 
-    let measure_break_indices: Vec<usize> = pitch_fingering_candidates
+    let marker_lines: Vec<(usize, Line<BeatVec<PitchFingering>>)> = pitch_fingering_candidates
         .iter()
         .enumerate()
-        .filter(|(.., line_candidate)| matches!(line_candidate, MeasureBreak))
-        .map(|(line_index, ..)| line_index)
+        .filter_map(|(line_index, line_candidate)| {
+            line_candidate
+                .to_marker()
+                .map(|marker| (line_index, marker))
+        })
+        .collect_vec();
+
+    let linked_beats = linked_beats(&pitch_fingering_candidates);
+    let beat_rings = beat_rings(parsed_lines);
+    let beat_line_numbers: Vec<u32> = parsed_lines
+        .iter()
+        .filter(|parsed| parsed.line.is_beat())
+        .map(|parsed| parsed.input_line)
+        .collect_vec();
+
+    let beat_voicings: Vec<Option<Vec<BeatVec<PitchFingering>>>> = chord_voicings
+        .into_iter()
+        .zip(&pitch_fingering_candidates)
+        .filter(|(_, line_candidate)| line_candidate.is_beat())
+        .map(|(voicings, _)| voicings)
         .collect_vec();
 
     let path_node_groups: Vec<BeatVec<Node>> = pitch_fingering_candidates
         .into_iter()
-        .filter(|line_candidate| !matches!(line_candidate, MeasureBreak))
+        .filter(Line::is_beat)
+        .zip(beat_voicings)
         .enumerate()
-        .map(|(line_index, line_candidate)| match line_candidate {
-            MeasureBreak => unreachable!("Measure breaks should have been filtered out."),
-            // `line_index as u16` cannot truncate: the guard above caps input at
-            // `MAX_INPUT_LINES` (`u16::MAX`), so the beat index always fits.
-            Rest => vec![Node::Rest {
-                line_index: line_index as u16,
-            }],
-            Playable(beat_fingerings_per_pitch) => {
-                generate_beat_fingerings(&beat_fingerings_per_pitch)
+        .map(
+            |(line_index, (line_candidate, voicings))| match line_candidate {
+                MeasureBreak
+                | RepeatStart
+                | RepeatEnd
+                | Ending(_)
+                | Section(_)
+                | Line::Technique(_)
+                | Line::LetRing(_) => {
+                    unreachable!("Structural markers should have been filtered out.")
+                }
+                // `line_index as u16` cannot truncate: the guard above caps input at
+                // `MAX_INPUT_LINES` (`u16::MAX`), so the beat index always fits.
+                Rest => vec![Node::Rest {
+                    line_index: line_index as u16,
+                }],
+                Playable(beat_fingerings_per_pitch) => voicings
+                    .unwrap_or_else(|| generate_beat_fingerings(&beat_fingerings_per_pitch))
                     .into_iter()
                     .map(|pitch_fingering_group| Node::Playable {
                         line_index: line_index as u16,
@@ -367,21 +979,33 @@ pub fn create_arrangements(
                             pitch_fingering_group,
                         )),
                     })
-                    .collect()
-            }
-        })
+                    .collect(),
+            },
+        )
         .collect::<Vec<_>>();
 
     let num_path_node_groups = path_node_groups.len();
 
-    let path_results: Vec<(Vec<Node>, NodeDifficulty)> = yen(
-        &Node::Start,
-        |current_node| calc_next_nodes(current_node, &path_node_groups, difficulty_weights),
-        |current_node| match current_node {
+    let start_state = PathState {
+        node: Node::Start,
+        ringing: RingingStrings::default(),
+    };
+    let path_results: Vec<(Vec<PathState>, NodeDifficulty)> = yen(
+        &start_state,
+        |current_state| {
+            calc_next_states(
+                current_state,
+                &path_node_groups,
+                &linked_beats,
+                &beat_rings,
+                difficulty_weights,
+            )
+        },
+        |current_state| match current_state.node {
             Node::Start => false,
             Node::Rest { line_index } | Node::Playable { line_index, .. } => {
                 // Pathfinding goal is reached when the node is in the last node group
-                *line_index == (num_path_node_groups - 1) as u16
+                line_index == (num_path_node_groups - 1) as u16
             }
         },
         num_arrangements.get() as usize,
@@ -392,7 +1016,17 @@ pub fn create_arrangements(
 
     let mut arrangements = path_results
         .into_iter()
-        .map(|path_result| process_path(path_result.0, path_result.1, &measure_break_indices))
+        .map(|(path_states, path_difficulty)| {
+            let path_nodes = path_states
+                .into_iter()
+                .map(|state| state.node)
+                .collect_vec();
+            let cut_off_notes = collect_cut_off_notes(&path_nodes, &beat_rings, &beat_line_numbers);
+            Arrangement {
+                cut_off_notes,
+                ..process_path(path_nodes, path_difficulty, &marker_lines)
+            }
+        })
         .collect_vec();
 
     if let Some(max_span) = max_fret_span_filter {
@@ -473,6 +1107,36 @@ mod test_create_arrangements {
         assert!(matches!(err, TabError::NoArrangementsFound), "got {err:?}");
     }
 
+    #[test]
+    fn technique_links_beats_to_one_string() {
+        // Unlinked, E4 is the open first string and D4 sits on the second string. A pull-off
+        // needs both on one string, and D4 is below string 1's range, so E4 moves to string 2.
+        let input_pitches = vec![
+            Line::Playable(vec![Pitch::E4]),
+            Line::Technique(Technique::PullOff),
+            Line::Playable(vec![Pitch::D4]),
+        ];
+
+        let arrangements = create_arrangements(
+            Guitar::default(),
+            input_pitches,
+            NumArrangements::try_new(3).unwrap(),
+            DifficultyWeights::standard(),
+            None,
+        )
+        .unwrap();
+
+        for arrangement in arrangements {
+            let lines = arrangement.lines();
+            assert_eq!(lines[1], Line::Technique(Technique::PullOff));
+            let (Line::Playable(from), Line::Playable(to)) = (&lines[0], &lines[2]) else {
+                panic!("expected two playable beats, got {lines:?}");
+            };
+            assert_eq!(from[0].string_number, to[0].string_number);
+            assert_ne!(from[0].string_number.get(), 1);
+        }
+    }
+
     #[test]
     fn single_line_single_pitch() {
         let input_pitches: Vec<Line<BeatVec<Pitch>>> = vec![Line::Playable(vec![Pitch::E4])];
@@ -481,9 +1145,12 @@ mod test_create_arrangements {
                 pitch: Pitch::E4,
                 string_number: StringNumber::new(1).unwrap(),
                 fret: 0,
+                harmonic: None,
+                voice: None,
             }])],
             difficulty: OrderedFloat(0.0),
             max_fret_span: 0,
+            cut_off_notes: vec![],
         }];
 
         let arrangements = create_arrangements(
@@ -506,36 +1173,48 @@ mod test_create_arrangements {
                     pitch: Pitch::E4,
                     string_number: StringNumber::new(1).unwrap(),
                     fret: 0,
+                    harmonic: None,
+                    voice: None,
                 }])],
                 difficulty: OrderedFloat(0.0),
                 max_fret_span: 0,
+                cut_off_notes: vec![],
             },
             Arrangement {
                 lines: vec![Line::Playable(vec![PitchFingering {
                     pitch: Pitch::E4,
                     string_number: StringNumber::new(2).unwrap(),
                     fret: 5,
+                    harmonic: None,
+                    voice: None,
                 }])],
                 difficulty: OrderedFloat(5.0),
                 max_fret_span: 0,
+                cut_off_notes: vec![],
             },
             Arrangement {
                 lines: vec![Line::Playable(vec![PitchFingering {
                     pitch: Pitch::E4,
                     string_number: StringNumber::new(3).unwrap(),
                     fret: 9,
+                    harmonic: None,
+                    voice: None,
                 }])],
                 difficulty: OrderedFloat(9.0),
                 max_fret_span: 0,
+                cut_off_notes: vec![],
             },
             Arrangement {
                 lines: vec![Line::Playable(vec![PitchFingering {
                     pitch: Pitch::E4,
                     string_number: StringNumber::new(4).unwrap(),
                     fret: 14,
+                    harmonic: None,
+                    voice: None,
                 }])],
                 difficulty: OrderedFloat(14.0),
                 max_fret_span: 0,
+                cut_off_notes: vec![],
             },
         ];
 
@@ -563,12 +1242,15 @@ mod test_create_arrangements {
                     pitch: Pitch::E4,
                     string_number: StringNumber::new(1).unwrap(),
                     fret: 0,
+                    harmonic: None,
+                    voice: None,
                 }]),
                 Line::Rest,
                 Line::MeasureBreak,
             ],
             difficulty: OrderedFloat(0.0),
             max_fret_span: 0,
+            cut_off_notes: vec![],
         }];
 
         let arrangements = create_arrangements(
@@ -640,6 +1322,7 @@ mod test_create_arrangements {
                 lines: vec![],
                 difficulty: OrderedFloat(0.0),
                 max_fret_span: 0,
+                cut_off_notes: vec![],
             };
             2
         ];
@@ -671,11 +1354,14 @@ mod test_create_arrangements {
                     pitch: Pitch::E4,
                     string_number: StringNumber::new(1).unwrap(),
                     fret: 0,
+                    harmonic: None,
+                    voice: None,
                 }]),
                 Line::Rest,
             ],
             difficulty: OrderedFloat(0.0),
             max_fret_span: 0,
+            cut_off_notes: vec![],
         }];
 
         assert_eq!(arrangements, expected_arrangements);
@@ -779,166 +1465,923 @@ mod test_create_arrangements {
         assert_eq!(filtered.len(), 2);
         assert!(filtered.iter().all(|a| a.max_fret_span() == 0));
     }
-}
 
-/// Generates the candidate `PitchFingering`s for every pitch in each beat.
-///
-/// Returns the per-beat fingerings on success, or [`TabError::UnplayablePitches`] listing
-/// every pitch that reached no string on the configured guitar (with its 1-indexed input
-/// line). All unplayable pitches are collected before returning, not just the first.
+    /// Frets of a chord beat from string 6 to string 1, with `x` for a muted string.
+    fn chord_frets(line: &Line<BeatVec<PitchFingering>>) -> String {
+        let Playable(fingerings) = line else {
+            panic!("expected a playable line, got {line:?}");
+        };
+        (1..=6)
+            .rev()
+            .map(|string_number| {
+                fingerings
+                    .iter()
+                    .find(|fingering| fingering.string_number.get() == string_number)
+                    .map_or("x".to_owned(), |fingering| fingering.fret.to_string())
+            })
+            .collect()
+    }
+
+    #[test]
+    fn chord_symbols_pick_the_easiest_voicings() {
+        let arrangements = create_parsed_arrangements(
+            Guitar::default(),
+            crate::parser::parse_input("{Am}\n{F}\n{C}\n{G}".to_owned()).unwrap(),
+            NumArrangements::try_new(1).unwrap(),
+            DifficultyWeights::standard(),
+            None,
+        )
+        .unwrap();
+        let frets = arrangements[0].lines.iter().map(chord_frets).collect_vec();
+        assert_eq!(frets, ["x02210", "xx3211", "x32010", "320003"]);
+    }
+    #[test]
+    fn chord_symbols_without_expansion_play_as_parsed() {
+        let arrangements = create_arrangements(
+            Guitar::default(),
+            parse_lines("{Am}".to_owned()).unwrap(),
+            NumArrangements::try_new(1).unwrap(),
+            DifficultyWeights::standard(),
+            None,
+        )
+        .unwrap();
+        let Playable(fingerings) = &arrangements[0].lines[0] else {
+            panic!("expected a playable line");
+        };
+        let pitches = fingerings
+            .iter()
+            .map(|fingering| fingering.pitch)
+            .collect_vec();
+        assert_eq!(pitches, [Pitch::A2, Pitch::C3, Pitch::E3]);
+    }
+}
+
+/// Expands each chord line into the voicings `guitar` can strum, by line, with `None` for a
+/// line that is not a chord. A chord line's pitches are replaced with its first voicing,
+/// which the guitar can always play, so validation does not trip over a close-position chord
+/// below the capo.
+///
+/// Returns [`TabError::UnplayableChord`] for the first chord with no voicing.
+fn expand_chords(
+    guitar: &Guitar,
+    parsed_lines: &mut [ParsedLine],
+) -> Result<Vec<Option<Vec<BeatVec<PitchFingering>>>>, TabError> {
+    parsed_lines
+        .iter_mut()
+        .map(|parsed| {
+            let (Some(chord), Playable(pitches)) = (parsed.chord, &mut parsed.line) else {
+                return Ok(None);
+            };
+            let voicings = chord.voicings(guitar);
+            let first_voicing = voicings.first().ok_or_else(|| TabError::UnplayableChord {
+                value: chord.to_string(),
+                line: parsed.input_line,
+            })?;
+            *pitches = first_voicing
+                .iter()
+                .map(|fingering| fingering.pitch)
+                .collect();
+            Ok(Some(voicings))
+        })
+        .collect()
+}
+#[cfg(test)]
+mod test_expand_chords {
+    use super::*;
+
+    #[test]
+    fn replaces_chord_lines_with_a_voicing() {
+        let mut parsed_lines = crate::parser::parse_input("{Am}\n\nE2".to_owned()).unwrap();
+        let voicings = expand_chords(&Guitar::default(), &mut parsed_lines).unwrap();
+        let first_voicing = voicings[0].as_ref().unwrap()[0].clone();
+        assert_eq!(
+            parsed_lines[0].line,
+            Playable(
+                first_voicing
+                    .iter()
+                    .map(|fingering| fingering.pitch)
+                    .collect()
+            )
+        );
+        assert_eq!(voicings[1..], [None, None]);
+        assert_eq!(parsed_lines[2].line, Playable(vec![Pitch::E2]));
+    }
+    #[test]
+    fn chord_without_voicings_is_an_error() {
+        let tuning =
+            crate::guitar::create_string_tuning(&crate::guitar::STD_6_STRING_TUNING_OPEN_PITCHES)
+                .unwrap();
+        let guitar = Guitar::new(tuning, 0, 0).unwrap();
+        let mut parsed_lines = crate::parser::parse_input("\n{F#m}".to_owned()).unwrap();
+        assert_eq!(
+            expand_chords(&guitar, &mut parsed_lines),
+            Err(TabError::UnplayableChord {
+                value: "F#m".to_owned(),
+                line: 2,
+            })
+        );
+    }
+}
+
+/// Generates the candidate `PitchFingering`s for every pitch in each beat.
+///
+/// Returns the per-beat fingerings on success, or [`TabError::UnplayablePitches`] listing
+/// every pitch that reached no string on the configured guitar (with its 1-indexed input
+/// line). All unplayable pitches are collected before returning, not just the first.
+///
+/// * `guitar`: the configured guitar, supplying per-string ranges.
+/// * `parsed_lines`: the parsed beats to place. The voice columns of a voiced line tag each
+///   of its fingerings with its voice.
+fn validate_fingerings(
+    guitar: &Guitar,
+    parsed_lines: &[ParsedLine],
+) -> Result<Vec<Line<BeatVec<PitchVec<PitchFingering>>>>, TabError> {
+    let mut unplayable_pitches: Vec<UnplayablePitch> = vec![];
+    let fingerings: Vec<Line<BeatVec<PitchVec<PitchFingering>>>> = parsed_lines
+        .iter()
+        .map(|parsed| match &parsed.line {
+            Rest => Rest,
+            Playable(beat_pitches) => {
+                let beat_voices = parsed
+                    .voices
+                    .as_deref()
+                    .map(pitch_voices)
+                    .unwrap_or_default();
+                Playable(
+                    beat_pitches
+                        .iter()
+                        .enumerate()
+                        .map(|(pitch_index, beat_pitch)| {
+                            let mut pitch_fingerings: PitchVec<PitchFingering> =
+                                generate_pitch_fingerings(&guitar.string_ranges, beat_pitch);
+                            pitch_fingerings.extend(generate_harmonic_fingerings(
+                                &guitar.string_ranges,
+                                beat_pitch,
+                                guitar.harmonic_mode,
+                            ));
+                            let voice = beat_voices.get(pitch_index).copied();
+                            for pitch_fingering in &mut pitch_fingerings {
+                                pitch_fingering.voice = voice;
+                            }
+                            if pitch_fingerings.is_empty() {
+                                unplayable_pitches.push(UnplayablePitch {
+                                    value: beat_pitch.plain_text().to_owned(),
+                                    line: parsed.input_line,
+                                })
+                            }
+                            pitch_fingerings
+                        })
+                        .collect(),
+                )
+            }
+            marker => marker
+                .to_marker()
+                .expect("BUG: every non-beat line is a structural marker"),
+        })
+        .collect();
+
+    if !unplayable_pitches.is_empty() {
+        return Err(TabError::UnplayablePitches {
+            pitches: unplayable_pitches,
+        });
+    }
+
+    Ok(fingerings)
+}
+#[cfg(test)]
+mod test_validate_fingerings {
+    use super::*;
+
+    #[test]
+    fn valid_simple() {
+        let guitar = Guitar::default();
+        let input_pitches = vec![Playable(vec![Pitch::G3])];
+        let expected_fingerings = vec![Playable(vec![generate_pitch_fingerings(
+            &guitar.string_ranges,
+            &Pitch::G3,
+        )])];
+
+        assert_eq!(
+            validate_fingerings(&guitar, &ParsedLine::from_lines(input_pitches)).unwrap(),
+            expected_fingerings
+        );
+    }
+    #[test]
+    fn valid_complex() {
+        let guitar = Guitar::default();
+        let input_pitches = vec![
+            Playable(vec![Pitch::G3]),
+            MeasureBreak,
+            Playable(vec![Pitch::B3]),
+            Rest,
+            Playable(vec![Pitch::D4, Pitch::G4]),
+        ];
+        let expected_fingerings = vec![
+            Playable(vec![generate_pitch_fingerings(
+                &guitar.string_ranges,
+                &Pitch::G3,
+            )]),
+            MeasureBreak,
+            Playable(vec![generate_pitch_fingerings(
+                &guitar.string_ranges,
+                &Pitch::B3,
+            )]),
+            Rest,
+            Playable(vec![
+                generate_pitch_fingerings(&guitar.string_ranges, &Pitch::D4),
+                generate_pitch_fingerings(&guitar.string_ranges, &Pitch::G4),
+            ]),
+        ];
+
+        assert_eq!(
+            validate_fingerings(&guitar, &ParsedLine::from_lines(input_pitches)).unwrap(),
+            expected_fingerings
+        );
+    }
+    #[test]
+    fn invalid_simple() {
+        let guitar = Guitar::default();
+        let input_pitches = vec![Playable(vec![Pitch::B9])];
+
+        let err = validate_fingerings(&guitar, &ParsedLine::from_lines(input_pitches)).unwrap_err();
+        match err {
+            TabError::UnplayablePitches { pitches } => {
+                assert_eq!(pitches.len(), 1);
+                assert_eq!(pitches[0].value, "B9");
+                assert_eq!(pitches[0].line, 1);
+            }
+            other => panic!("expected UnplayablePitches, got {other:?}"),
+        }
+    }
+    #[test]
+    fn invalid_accidental_reports_plain_text_spelling() {
+        // An unplayable accidental reports its plain-text spelling ("Db9"), matching the
+        // normalized-input pitch strings, rather than the internal enum name ("CSharpDFlat9").
+        let guitar = Guitar::default();
+        let input_pitches = vec![Playable(vec![Pitch::CSharpDFlat9])];
+
+        let err = validate_fingerings(&guitar, &ParsedLine::from_lines(input_pitches)).unwrap_err();
+        match err {
+            TabError::UnplayablePitches { pitches } => {
+                assert_eq!(pitches.len(), 1);
+                assert_eq!(pitches[0].value, "Db9");
+                assert_eq!(pitches[0].line, 1);
+            }
+            other => panic!("expected UnplayablePitches, got {other:?}"),
+        }
+    }
+    #[test]
+    fn invalid_complex() {
+        let guitar = Guitar::default();
+        let input_pitches = vec![
+            Playable(vec![Pitch::A1]),
+            Playable(vec![Pitch::G3]),
+            Playable(vec![Pitch::B3]),
+            Playable(vec![Pitch::A1, Pitch::B1]),
+            Playable(vec![Pitch::G3, Pitch::D2]),
+            Playable(vec![Pitch::D4, Pitch::G4]),
+        ];
+
+        let err = validate_fingerings(&guitar, &ParsedLine::from_lines(input_pitches)).unwrap_err();
+        match err {
+            TabError::UnplayablePitches { pitches } => {
+                assert_eq!(pitches.len(), 4);
+                assert_eq!(pitches[0].value, "A1");
+                assert_eq!(pitches[0].line, 1);
+                assert_eq!(pitches[1].value, "A1");
+                assert_eq!(pitches[1].line, 4);
+                assert_eq!(pitches[2].value, "B1");
+                assert_eq!(pitches[2].line, 4);
+                assert_eq!(pitches[3].value, "D2");
+                assert_eq!(pitches[3].line, 5);
+            }
+            other => panic!("expected UnplayablePitches, got {other:?}"),
+        }
+    }
+}
+
+/// Flags each beat whose fingering a linking [`Technique`] ties to the beat before it. Indexed
+/// by beat, like the pathfinding node groups, so markers and measure breaks are skipped.
+///
+/// A link only holds between two `Playable` beats. A technique after a rest, or with no
+/// beat after it, constrains nothing.
+fn linked_beats<T>(lines: &[Line<T>]) -> Vec<bool> {
+    let mut linked: Vec<bool> = vec![];
+    let mut previous_is_playable = false;
+    let mut pending_link = false;
+    for line in lines {
+        match line {
+            Line::Technique(technique) => {
+                pending_link |= previous_is_playable && technique.links_beats();
+            }
+            Playable(_) => {
+                linked.push(pending_link);
+                pending_link = false;
+                previous_is_playable = true;
+            }
+            Rest => {
+                linked.push(false);
+                pending_link = false;
+                previous_is_playable = false;
+            }
+            MeasureBreak | RepeatStart | RepeatEnd | Ending(_) | Section(_) | Line::LetRing(_) => {}
+        }
+    }
+    linked
+}
+#[cfg(test)]
+mod test_linked_beats {
+    use super::*;
+
+    fn beat() -> Line<BeatVec<Pitch>> {
+        Playable(vec![Pitch::E4])
+    }
+
+    #[test]
+    fn no_techniques() {
+        assert_eq!(linked_beats(&[beat(), Rest, beat()]), vec![false; 3]);
+    }
+    #[test]
+    fn links_the_beat_after_the_technique() {
+        let lines = vec![
+            beat(),
+            Line::Technique(Technique::HammerOn),
+            MeasureBreak,
+            beat(),
+            beat(),
+        ];
+        assert_eq!(linked_beats(&lines), vec![false, true, false]);
+    }
+    #[test]
+    fn vibrato_does_not_link() {
+        let lines = vec![beat(), Line::Technique(Technique::Vibrato), beat()];
+        assert_eq!(linked_beats(&lines), vec![false, false]);
+    }
+    #[test]
+    fn rests_break_the_link() {
+        let lines = vec![
+            Rest,
+            Line::Technique(Technique::Slide),
+            beat(),
+            Line::Technique(Technique::Slide),
+            Rest,
+        ];
+        assert_eq!(linked_beats(&lines), vec![false, false, false]);
+    }
+}
+
+/// For each beat, the last beat index its notes ring through when a `LetRing` marker follows
+/// it, or `None` when it does not ring. Indexed by beat, like [`linked_beats`].
+///
+/// `LetRing(None)` rings to the end. A `LetRing` after a rest, or before any beat, is ignored.
+fn ring_until<'a, T: 'a>(lines: impl IntoIterator<Item = &'a Line<T>>) -> Vec<Option<u16>> {
+    let mut ring_until: Vec<Option<u16>> = vec![];
+    let mut previous_is_playable = false;
+    for line in lines {
+        match line {
+            Line::LetRing(beats) if previous_is_playable => {
+                let beat_index = (ring_until.len() - 1) as u16;
+                if let Some(last) = ring_until.last_mut() {
+                    *last = Some(
+                        beats.map_or(u16::MAX, |beats| beat_index.saturating_add(beats as u16)),
+                    );
+                }
+            }
+            Playable(_) => {
+                ring_until.push(None);
+                previous_is_playable = true;
+            }
+            Rest => {
+                ring_until.push(None);
+                previous_is_playable = false;
+            }
+            MeasureBreak
+            | RepeatStart
+            | RepeatEnd
+            | Ending(_)
+            | Section(_)
+            | Line::Technique(_)
+            | Line::LetRing(_) => {}
+        }
+    }
+    ring_until
+}
+#[cfg(test)]
+mod test_ring_until {
+    use super::*;
+
+    fn beat() -> Line<BeatVec<Pitch>> {
+        Playable(vec![Pitch::E2])
+    }
+
+    #[test]
+    fn no_let_ring() {
+        assert_eq!(ring_until(&[beat(), Rest, beat()]), vec![None; 3]);
+    }
+    #[test]
+    fn rings_for_a_number_of_beats() {
+        let lines = vec![beat(), Line::LetRing(Some(2)), MeasureBreak, beat(), beat()];
+        assert_eq!(ring_until(&lines), vec![Some(2), None, None]);
+    }
+    #[test]
+    fn rings_to_the_end() {
+        let lines = vec![beat(), beat(), Line::LetRing(None)];
+        assert_eq!(ring_until(&lines), vec![None, Some(u16::MAX)]);
+    }
+    #[test]
+    fn ignored_after_a_rest() {
+        let lines = vec![Line::LetRing(None), beat(), Rest, Line::LetRing(Some(1))];
+        assert_eq!(ring_until(&lines), vec![None, None]);
+    }
+}
+
+/// How long the notes of one beat ring, as the last beat index they ring through.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct BeatRing {
+    /// Set by a `LetRing` marker after the beat, for every note.
+    let_ring: Option<u16>,
+    /// Set by a voice column held with `:N`, for that voice's notes only.
+    held_voices: Vec<(Voice, u16)>,
+}
+impl BeatRing {
+    /// The last beat index `fingering` rings through, or `None` when it does not ring.
+    fn ring_until(&self, fingering: &PitchFingering) -> Option<u16> {
+        let held = self
+            .held_voices
+            .iter()
+            .filter(|(voice, _)| fingering.voice == Some(*voice))
+            .map(|(_, until)| *until)
+            .max();
+        self.let_ring.max(held)
+    }
+}
+
+/// Combines each beat's `LetRing` span from [`ring_until`] with the voice columns held past
+/// it. Indexed by beat.
+fn beat_rings(parsed_lines: &[ParsedLine]) -> Vec<BeatRing> {
+    parsed_lines
+        .iter()
+        .filter(|parsed| parsed.line.is_beat())
+        .zip(ring_until(parsed_lines.iter().map(|parsed| &parsed.line)))
+        .enumerate()
+        .map(|(beat_index, (parsed, let_ring))| {
+            let held_voices = parsed
+                .voices
+                .as_ref()
+                .map(|columns| {
+                    columns
+                        .iter()
+                        .filter(|column| column.beats > 1)
+                        .map(|column| {
+                            let until =
+                                (beat_index as u16).saturating_add(u16::from(column.beats) - 1);
+                            (column.voice, until)
+                        })
+                        .collect_vec()
+                })
+                .unwrap_or_default();
+            BeatRing {
+                let_ring,
+                held_voices,
+            }
+        })
+        .collect_vec()
+}
+#[cfg(test)]
+mod test_beat_rings {
+    use super::*;
+    use crate::string_number::StringNumber;
+    use crate::voice::VoiceColumn;
+
+    fn column(voice: Voice, beats: u8) -> VoiceColumn {
+        VoiceColumn {
+            voice,
+            pitches: vec![Pitch::E2],
+            beats,
+        }
+    }
+
+    #[test]
+    fn held_voice_rings_for_its_beats() {
+        let mut parsed_lines = ParsedLine::from_lines(vec![
+            Section("A".to_owned()),
+            Playable(vec![Pitch::E4, Pitch::E2]),
+            Playable(vec![Pitch::G4]),
+        ]);
+        parsed_lines[1].voices = Some(vec![column(Voice::Melody, 1), column(Voice::Bass, 3)]);
+        let rings = beat_rings(&parsed_lines);
+        assert_eq!(
+            rings,
+            vec![
+                BeatRing {
+                    let_ring: None,
+                    held_voices: vec![(Voice::Bass, 2)],
+                },
+                BeatRing::default(),
+            ]
+        );
+
+        let fingering = |voice| PitchFingering {
+            pitch: Pitch::E2,
+            string_number: StringNumber::new(6).unwrap(),
+            fret: 0,
+            harmonic: None,
+            voice,
+        };
+        assert_eq!(rings[0].ring_until(&fingering(Some(Voice::Bass))), Some(2));
+        assert_eq!(rings[0].ring_until(&fingering(Some(Voice::Melody))), None);
+        assert_eq!(rings[0].ring_until(&fingering(None)), None);
+    }
+    #[test]
+    fn let_ring_covers_every_voice() {
+        let parsed_lines =
+            ParsedLine::from_lines(vec![Playable(vec![Pitch::E2]), Line::LetRing(Some(1))]);
+        let rings = beat_rings(&parsed_lines);
+        assert_eq!(rings[0].let_ring, Some(1));
+    }
+}
+
+/// A let-ring or held note still sounding on its string.
+#[derive(Debug, Clone, Hash, Eq, PartialEq, Ord, PartialOrd)]
+struct RingingNote {
+    beat_index: u16,
+    ring_until: u16,
+    fingering: PitchFingering,
+}
+
+/// The last beat index each string rings through, indexed by string number minus one.
+/// `None` where the string is silent.
+type RingingStrings = [Option<u16>; StringNumber::MAX as usize];
+
+/// A pathfinding node together with the strings still ringing when it is played.
+///
+/// Only the string and how long it rings decide a later cut-off, so the state leaves out
+/// which note is ringing and where it started. Paths that reach a node with the same strings
+/// ringing therefore share one state. Without any `LetRing` marker or held voice every string
+/// stays `None`, so the search space is the plain node graph.
+#[derive(Debug, Clone, Hash, Eq, PartialEq, Ord, PartialOrd)]
+struct PathState {
+    node: Node,
+    ringing: RingingStrings,
+}
+
+/// Cost of cutting off one ringing note, in frets of hand movement. Scaled by the
+/// `movement` weight.
+const CUT_OFF_PENALTY: f64 = 5.0;
+
+/// Steps the ringing notes forward to `next_node`. Returns the notes still ringing after it
+/// is played and the notes it cuts off by replaying their string.
 ///
-/// * `guitar`: the configured guitar, supplying per-string ranges.
-/// * `input_pitches`: the parsed beats to place.
-fn validate_fingerings(
-    guitar: &Guitar,
-    input_pitches: &[Line<BeatVec<Pitch>>],
-) -> Result<Vec<Line<BeatVec<PitchVec<PitchFingering>>>>, TabError> {
-    let mut unplayable_pitches: Vec<UnplayablePitch> = vec![];
-    let fingerings: Vec<Line<BeatVec<PitchVec<PitchFingering>>>> = input_pitches
-        .iter()
-        .enumerate()
-        .map(|(beat_index, beat_input)| match beat_input {
-            MeasureBreak => MeasureBreak,
-            Rest => Rest,
-            Playable(beat_pitches) => Playable(
-                beat_pitches
-                    .iter()
-                    .map(|beat_pitch| {
-                        let pitch_fingerings: PitchVec<PitchFingering> =
-                            generate_pitch_fingerings(&guitar.string_ranges, beat_pitch);
-                        if pitch_fingerings.is_empty() {
-                            unplayable_pitches.push(UnplayablePitch {
-                                value: beat_pitch.plain_text().to_owned(),
-                                line: (beat_index as u32) + 1,
-                            })
-                        }
-                        pitch_fingerings
-                    })
-                    .collect(),
-            ),
-        })
-        .collect();
+/// Expired notes drop out silently. A `Playable` node adds those of its own notes that its
+/// beat rings.
+fn advance_ringing(
+    ringing: &[RingingNote],
+    next_node: &Node,
+    beat_rings: &[BeatRing],
+) -> (Vec<RingingNote>, Vec<RingingNote>) {
+    let (next_beat_index, next_fingering): (u16, &[PitchFingering]) = match next_node {
+        Node::Start => unreachable!("Start should never be a future node."),
+        Node::Rest { line_index } => (*line_index, &[]),
+        Node::Playable {
+            line_index,
+            scored_beat_fingering,
+        } => (*line_index, &scored_beat_fingering.beat_fingering),
+    };
 
-    if !unplayable_pitches.is_empty() {
-        return Err(TabError::UnplayablePitches {
-            pitches: unplayable_pitches,
+    let (cut_off, still_ringing): (Vec<RingingNote>, Vec<RingingNote>) = ringing
+        .iter()
+        .filter(|note| note.ring_until >= next_beat_index)
+        .cloned()
+        .partition(|note| {
+            next_fingering
+                .iter()
+                .any(|fingering| fingering.string_number == note.fingering.string_number)
         });
+
+    let mut still_ringing = still_ringing;
+    if let Some(beat_ring) = beat_rings.get(next_beat_index as usize) {
+        still_ringing.extend(next_fingering.iter().filter_map(|fingering| {
+            Some(RingingNote {
+                beat_index: next_beat_index,
+                ring_until: beat_ring.ring_until(fingering)?,
+                fingering: *fingering,
+            })
+        }));
     }
 
-    Ok(fingerings)
+    (still_ringing, cut_off)
 }
 #[cfg(test)]
-mod test_validate_fingerings {
+mod test_advance_ringing {
     use super::*;
+    use crate::string_number::StringNumber;
 
-    #[test]
-    fn valid_simple() {
-        let guitar = Guitar::default();
-        let input_pitches = vec![Playable(vec![Pitch::G3])];
-        let expected_fingerings = vec![Playable(vec![generate_pitch_fingerings(
-            &guitar.string_ranges,
-            &Pitch::G3,
-        )])];
+    fn fingering(string_number: u8) -> PitchFingering {
+        PitchFingering {
+            pitch: Pitch::E2,
+            string_number: StringNumber::new(string_number).unwrap(),
+            fret: 0,
+            harmonic: None,
+            voice: None,
+        }
+    }
+    fn playable(line_index: u16, string_numbers: &[u8]) -> Node {
+        Node::Playable {
+            line_index,
+            scored_beat_fingering: Rc::new(ScoredBeatFingering::new(
+                string_numbers.iter().map(|&n| fingering(n)).collect(),
+            )),
+        }
+    }
+    fn ringing_on(string_number: u8, ring_until: u16) -> RingingNote {
+        RingingNote {
+            beat_index: 0,
+            ring_until,
+            fingering: fingering(string_number),
+        }
+    }
+    fn let_rings(let_rings: &[Option<u16>]) -> Vec<BeatRing> {
+        let_rings
+            .iter()
+            .map(|&let_ring| BeatRing {
+                let_ring,
+                held_voices: vec![],
+            })
+            .collect()
+    }
 
-        assert_eq!(
-            validate_fingerings(&guitar, &input_pitches).unwrap(),
-            expected_fingerings
+    #[test]
+    fn starts_ringing() {
+        let (ringing, cut_off) = advance_ringing(&[], &playable(0, &[6]), &let_rings(&[Some(3)]));
+        assert_eq!(ringing, vec![ringing_on(6, 3)]);
+        assert!(cut_off.is_empty());
+    }
+    #[test]
+    fn other_strings_keep_ringing() {
+        let (ringing, cut_off) = advance_ringing(
+            &[ringing_on(6, 3)],
+            &playable(1, &[1]),
+            &let_rings(&[Some(3), None]),
         );
+        assert_eq!(ringing, vec![ringing_on(6, 3)]);
+        assert!(cut_off.is_empty());
     }
     #[test]
-    fn valid_complex() {
-        let guitar = Guitar::default();
-        let input_pitches = vec![
-            Playable(vec![Pitch::G3]),
-            MeasureBreak,
-            Playable(vec![Pitch::B3]),
-            Rest,
-            Playable(vec![Pitch::D4, Pitch::G4]),
-        ];
-        let expected_fingerings = vec![
-            Playable(vec![generate_pitch_fingerings(
-                &guitar.string_ranges,
-                &Pitch::G3,
-            )]),
-            MeasureBreak,
-            Playable(vec![generate_pitch_fingerings(
-                &guitar.string_ranges,
-                &Pitch::B3,
-            )]),
-            Rest,
-            Playable(vec![
-                generate_pitch_fingerings(&guitar.string_ranges, &Pitch::D4),
-                generate_pitch_fingerings(&guitar.string_ranges, &Pitch::G4),
-            ]),
+    fn same_string_cuts_off() {
+        let (ringing, cut_off) = advance_ringing(
+            &[ringing_on(6, 3)],
+            &playable(1, &[6]),
+            &let_rings(&[Some(3), None]),
+        );
+        assert!(ringing.is_empty());
+        assert_eq!(cut_off, vec![ringing_on(6, 3)]);
+    }
+    #[test]
+    fn expired_notes_drop_out() {
+        let (ringing, cut_off) = advance_ringing(
+            &[ringing_on(6, 1)],
+            &playable(2, &[6]),
+            &let_rings(&[Some(1), None, None]),
+        );
+        assert!(ringing.is_empty());
+        assert!(cut_off.is_empty());
+    }
+    #[test]
+    fn rests_keep_notes_ringing() {
+        let (ringing, _) = advance_ringing(
+            &[ringing_on(6, 3)],
+            &Node::Rest { line_index: 1 },
+            &let_rings(&[Some(3), None]),
+        );
+        assert_eq!(ringing, vec![ringing_on(6, 3)]);
+    }
+    #[test]
+    fn held_voice_rings_alone() {
+        let beat_fingering = vec![
+            PitchFingering {
+                voice: Some(Voice::Melody),
+                ..fingering(1)
+            },
+            PitchFingering {
+                voice: Some(Voice::Bass),
+                ..fingering(6)
+            },
         ];
-
+        let node = Node::Playable {
+            line_index: 0,
+            scored_beat_fingering: Rc::new(ScoredBeatFingering::new(beat_fingering.clone())),
+        };
+        let beat_rings = vec![BeatRing {
+            let_ring: None,
+            held_voices: vec![(Voice::Bass, 1)],
+        }];
+        let (ringing, _) = advance_ringing(&[], &node, &beat_rings);
         assert_eq!(
-            validate_fingerings(&guitar, &input_pitches).unwrap(),
-            expected_fingerings
+            ringing,
+            vec![RingingNote {
+                beat_index: 0,
+                ring_until: 1,
+                fingering: beat_fingering[1],
+            }]
         );
     }
-    #[test]
-    fn invalid_simple() {
-        let guitar = Guitar::default();
-        let input_pitches = vec![Playable(vec![Pitch::B9])];
+}
 
-        let err = validate_fingerings(&guitar, &input_pitches).unwrap_err();
-        match err {
-            TabError::UnplayablePitches { pitches } => {
-                assert_eq!(pitches.len(), 1);
-                assert_eq!(pitches[0].value, "B9");
-                assert_eq!(pitches[0].line, 1);
-            }
-            other => panic!("expected UnplayablePitches, got {other:?}"),
+/// [`advance_ringing`] for the search state. Returns the strings still ringing after
+/// `next_node` is played and the number of ringing strings it cuts off.
+fn advance_ringing_strings(
+    ringing: &RingingStrings,
+    next_node: &Node,
+    beat_rings: &[BeatRing],
+) -> (RingingStrings, usize) {
+    let (next_beat_index, next_fingering): (u16, &[PitchFingering]) = match next_node {
+        Node::Start => unreachable!("Start should never be a future node."),
+        Node::Rest { line_index } => (*line_index, &[]),
+        Node::Playable {
+            line_index,
+            scored_beat_fingering,
+        } => (*line_index, &scored_beat_fingering.beat_fingering),
+    };
+
+    let mut still_ringing = ringing.map(|until| until.filter(|&until| until >= next_beat_index));
+    let mut num_cut_off = 0;
+    for fingering in next_fingering {
+        let string = &mut still_ringing[usize::from(fingering.string_number.get() - 1)];
+        if string.is_some() {
+            num_cut_off += 1;
+        }
+        *string = beat_rings
+            .get(next_beat_index as usize)
+            .and_then(|beat_ring| beat_ring.ring_until(fingering));
+    }
+
+    (still_ringing, num_cut_off)
+}
+#[cfg(test)]
+mod test_advance_ringing_strings {
+    use super::*;
+
+    fn playable(line_index: u16, string_numbers: &[u8]) -> Node {
+        Node::Playable {
+            line_index,
+            scored_beat_fingering: Rc::new(ScoredBeatFingering::new(
+                string_numbers
+                    .iter()
+                    .map(|&n| PitchFingering {
+                        pitch: Pitch::E2,
+                        string_number: StringNumber::new(n).unwrap(),
+                        fret: 0,
+                        harmonic: None,
+                        voice: None,
+                    })
+                    .collect(),
+            )),
         }
     }
+    fn ringing_on(strings: &[(u8, u16)]) -> RingingStrings {
+        let mut ringing = RingingStrings::default();
+        for &(string_number, until) in strings {
+            ringing[usize::from(string_number - 1)] = Some(until);
+        }
+        ringing
+    }
+    fn let_rings(let_rings: &[Option<u16>]) -> Vec<BeatRing> {
+        let_rings
+            .iter()
+            .map(|&let_ring| BeatRing {
+                let_ring,
+                held_voices: vec![],
+            })
+            .collect()
+    }
+
     #[test]
-    fn invalid_accidental_reports_plain_text_spelling() {
-        // An unplayable accidental reports its plain-text spelling ("Db9"), matching the
-        // normalized-input pitch strings, rather than the internal enum name ("CSharpDFlat9").
-        let guitar = Guitar::default();
-        let input_pitches = vec![Playable(vec![Pitch::CSharpDFlat9])];
+    fn tracks_each_string() {
+        let beat_rings = let_rings(&[Some(3), Some(2), None, None]);
+        let (ringing, num_cut_off) =
+            advance_ringing_strings(&ringing_on(&[]), &playable(0, &[6, 1]), &beat_rings);
+        assert_eq!(ringing, ringing_on(&[(6, 3), (1, 3)]));
+        assert_eq!(num_cut_off, 0);
+
+        let (ringing, num_cut_off) =
+            advance_ringing_strings(&ringing, &playable(1, &[6, 5]), &beat_rings);
+        assert_eq!(ringing, ringing_on(&[(6, 2), (5, 2), (1, 3)]));
+        assert_eq!(num_cut_off, 1);
+
+        let (ringing, num_cut_off) =
+            advance_ringing_strings(&ringing, &playable(3, &[5]), &beat_rings);
+        assert_eq!(ringing, ringing_on(&[(1, 3)]));
+        assert_eq!(num_cut_off, 0);
+    }
+    #[test]
+    fn notes_of_different_beats_share_a_state() {
+        let beat_rings = let_rings(&[Some(u16::MAX), Some(u16::MAX), None]);
+        let from_first = advance_ringing_strings(&ringing_on(&[]), &playable(0, &[6]), &beat_rings);
+        let from_second =
+            advance_ringing_strings(&ringing_on(&[]), &playable(1, &[6]), &beat_rings);
+        assert_eq!(from_first, from_second);
+    }
+}
 
-        let err = validate_fingerings(&guitar, &input_pitches).unwrap_err();
-        match err {
-            TabError::UnplayablePitches { pitches } => {
-                assert_eq!(pitches.len(), 1);
-                assert_eq!(pitches[0].value, "Db9");
-                assert_eq!(pitches[0].line, 1);
-            }
-            other => panic!("expected UnplayablePitches, got {other:?}"),
+/// Like [`calc_next_nodes`], but threads the ringing strings through and adds the cut-off
+/// penalty to each transition.
+fn calc_next_states(
+    current_state: &PathState,
+    path_node_groups: &[BeatVec<Node>],
+    linked_beats: &[bool],
+    beat_rings: &[BeatRing],
+    weights: DifficultyWeights,
+) -> Vec<(PathState, NodeDifficulty)> {
+    calc_next_nodes(&current_state.node, path_node_groups, linked_beats, weights)
+        .into_iter()
+        .map(|(next_node, difficulty)| {
+            let (ringing, num_cut_off) =
+                advance_ringing_strings(&current_state.ringing, &next_node, beat_rings);
+            let penalty = num_cut_off as f64 * CUT_OFF_PENALTY * weights.movement();
+            (
+                PathState {
+                    node: next_node,
+                    ringing,
+                },
+                difficulty + penalty,
+            )
+        })
+        .collect_vec()
+}
+#[cfg(test)]
+mod test_calc_next_states {
+    use super::*;
+    use crate::string_number::StringNumber;
+
+    fn playable(line_index: u16, string_number: u8) -> Node {
+        Node::Playable {
+            line_index,
+            scored_beat_fingering: Rc::new(ScoredBeatFingering::new(vec![PitchFingering {
+                pitch: Pitch::E2,
+                string_number: StringNumber::new(string_number).unwrap(),
+                fret: 0,
+                harmonic: None,
+                voice: None,
+            }])),
         }
     }
+
     #[test]
-    fn invalid_complex() {
-        let guitar = Guitar::default();
-        let input_pitches = vec![
-            Playable(vec![Pitch::A1]),
-            Playable(vec![Pitch::G3]),
-            Playable(vec![Pitch::B3]),
-            Playable(vec![Pitch::A1, Pitch::B1]),
-            Playable(vec![Pitch::G3, Pitch::D2]),
-            Playable(vec![Pitch::D4, Pitch::G4]),
+    fn cut_off_adds_penalty() {
+        let path_node_groups = vec![vec![playable(0, 6)], vec![playable(1, 6), playable(1, 5)]];
+        let beat_rings = [
+            BeatRing {
+                let_ring: Some(1),
+                held_voices: vec![],
+            },
+            BeatRing::default(),
         ];
+        let weights = DifficultyWeights::standard();
 
-        let err = validate_fingerings(&guitar, &input_pitches).unwrap_err();
-        match err {
-            TabError::UnplayablePitches { pitches } => {
-                assert_eq!(pitches.len(), 4);
-                assert_eq!(pitches[0].value, "A1");
-                assert_eq!(pitches[0].line, 1);
-                assert_eq!(pitches[1].value, "A1");
-                assert_eq!(pitches[1].line, 4);
-                assert_eq!(pitches[2].value, "B1");
-                assert_eq!(pitches[2].line, 4);
-                assert_eq!(pitches[3].value, "D2");
-                assert_eq!(pitches[3].line, 5);
-            }
-            other => panic!("expected UnplayablePitches, got {other:?}"),
-        }
+        let start = PathState {
+            node: Node::Start,
+            ringing: RingingStrings::default(),
+        };
+        let first = calc_next_states(&start, &path_node_groups, &[], &beat_rings, weights)
+            .remove(0)
+            .0;
+        assert_eq!(first.ringing.iter().flatten().count(), 1);
+
+        let next = calc_next_states(&first, &path_node_groups, &[], &beat_rings, weights);
+        let plain = calc_next_nodes(&first.node, &path_node_groups, &[], weights);
+        assert_eq!(next[0].1, plain[0].1 + CUT_OFF_PENALTY * weights.movement());
+        assert_eq!(next[1].1, plain[1].1);
+        assert_eq!(next[1].0.ringing.iter().flatten().count(), 1);
     }
 }
 
+/// Lists the ringing notes a path cuts off, with their 1-indexed input lines.
+///
+/// The search state only keeps which strings ring, so the notes are replayed along the path.
+fn collect_cut_off_notes(
+    path_nodes: &[Node],
+    beat_rings: &[BeatRing],
+    beat_line_numbers: &[u32],
+) -> Vec<CutOffNote> {
+    let mut ringing = vec![];
+    let mut cut_off_notes = vec![];
+    for node in path_nodes {
+        let cut_off_beat_index = match node {
+            Node::Start => continue,
+            Node::Rest { line_index } | Node::Playable { line_index, .. } => *line_index,
+        };
+        let (still_ringing, cut_off) = advance_ringing(&ringing, node, beat_rings);
+        ringing = still_ringing;
+        cut_off_notes.extend(cut_off.into_iter().map(|note| CutOffNote {
+            pitch: note.fingering.pitch.plain_text().to_owned(),
+            string: note.fingering.string_number.get(),
+            line: beat_line_numbers[note.beat_index as usize],
+            cut_off_line: beat_line_numbers[cut_off_beat_index as usize],
+        }));
+    }
+    cut_off_notes
+}
+
 /// Generates all playable combinations of fingerings for all the pitches in a beat.
 /// An empty beat yields no combinations.
 fn generate_beat_fingerings(
@@ -970,6 +2413,8 @@ mod test_generate_beat_fingerings {
             pitch: Pitch::B6,
             string_number: StringNumber::new(2).unwrap(),
             fret: 3,
+            harmonic: None,
+            voice: None,
         };
 
         let beat_fingerings_per_pitch = &[vec![pitch_fingering]];
@@ -985,26 +2430,36 @@ mod test_generate_beat_fingerings {
             pitch: Pitch::B6,
             string_number: StringNumber::new(2).unwrap(),
             fret: 3,
+            harmonic: None,
+            voice: None,
         };
         let pitch_fingering_a_string_3 = PitchFingering {
             pitch: Pitch::B6,
             string_number: StringNumber::new(3).unwrap(),
             fret: 8,
+            harmonic: None,
+            voice: None,
         };
         let pitch_fingering_b_string_2 = PitchFingering {
             pitch: Pitch::C7,
             string_number: StringNumber::new(2).unwrap(),
             fret: 4,
+            harmonic: None,
+            voice: None,
         };
         let pitch_fingering_b_string_3 = PitchFingering {
             pitch: Pitch::C7,
             string_number: StringNumber::new(3).unwrap(),
             fret: 9,
+            harmonic: None,
+            voice: None,
         };
         let pitch_fingering_b_string_4 = PitchFingering {
             pitch: Pitch::C7,
             string_number: StringNumber::new(4).unwrap(),
             fret: 14,
+            harmonic: None,
+            voice: None,
         };
 
         let beat_fingerings_per_pitch = vec![
@@ -1056,6 +2511,8 @@ mod test_no_duplicate_strings {
             pitch: Pitch::B6,
             string_number: StringNumber::new(2).unwrap(),
             fret: 3,
+            harmonic: None,
+            voice: None,
         };
 
         assert!(no_duplicate_strings(&[fingering_1]));
@@ -1066,21 +2523,29 @@ mod test_no_duplicate_strings {
             pitch: Pitch::CSharpDFlat2,
             string_number: StringNumber::new(1).unwrap(),
             fret: 1,
+            harmonic: None,
+            voice: None,
         };
         let fingering_2 = PitchFingering {
             pitch: Pitch::F4,
             string_number: StringNumber::new(2).unwrap(),
             fret: 3,
+            harmonic: None,
+            voice: None,
         };
         let fingering_3 = PitchFingering {
             pitch: Pitch::A5,
             string_number: StringNumber::new(4).unwrap(),
             fret: 4,
+            harmonic: None,
+            voice: None,
         };
         let fingering_4 = PitchFingering {
             pitch: Pitch::DSharpEFlat6,
             string_number: StringNumber::new(11).unwrap(),
             fret: 0,
+            harmonic: None,
+            voice: None,
         };
 
         assert!(no_duplicate_strings(&[
@@ -1096,11 +2561,15 @@ mod test_no_duplicate_strings {
             pitch: Pitch::CSharpDFlat2,
             string_number: StringNumber::new(4).unwrap(),
             fret: 1,
+            harmonic: None,
+            voice: None,
         };
         let fingering_2 = PitchFingering {
             pitch: Pitch::F4,
             string_number: StringNumber::new(4).unwrap(),
             fret: 3,
+            harmonic: None,
+            voice: None,
         };
 
         assert!(!no_duplicate_strings(&[fingering_1, fingering_2]));
@@ -1111,21 +2580,29 @@ mod test_no_duplicate_strings {
             pitch: Pitch::CSharpDFlat2,
             string_number: StringNumber::new(1).unwrap(),
             fret: 1,
+            harmonic: None,
+            voice: None,
         };
         let fingering_2 = PitchFingering {
             pitch: Pitch::F4,
             string_number: StringNumber::new(3).unwrap(),
             fret: 3,
+            harmonic: None,
+            voice: None,
         };
         let fingering_3 = PitchFingering {
             pitch: Pitch::A5,
             string_number: StringNumber::new(6).unwrap(),
             fret: 4,
+            harmonic: None,
+            voice: None,
         };
         let fingering_4 = PitchFingering {
             pitch: Pitch::DSharpEFlat6,
             string_number: StringNumber::new(3).unwrap(),
             fret: 0,
+            harmonic: None,
+            voice: None,
         };
 
         assert!(!no_duplicate_strings(&[
@@ -1169,6 +2646,8 @@ mod test_calc_fret_span {
             pitch: Pitch::B6,
             string_number: StringNumber::new(2).unwrap(),
             fret: 3,
+            harmonic: None,
+            voice: None,
         };
 
         assert_eq!(calc_fret_span(&[fingering_1]).unwrap(), 0);
@@ -1179,21 +2658,29 @@ mod test_calc_fret_span {
             pitch: Pitch::CSharpDFlat2,
             string_number: StringNumber::new(1).unwrap(),
             fret: 1,
+            harmonic: None,
+            voice: None,
         };
         let fingering_2 = PitchFingering {
             pitch: Pitch::F4,
             string_number: StringNumber::new(2).unwrap(),
             fret: 3,
+            harmonic: None,
+            voice: None,
         };
         let fingering_3 = PitchFingering {
             pitch: Pitch::A5,
             string_number: StringNumber::new(4).unwrap(),
             fret: 4,
+            harmonic: None,
+            voice: None,
         };
         let fingering_4 = PitchFingering {
             pitch: Pitch::DSharpEFlat6,
             string_number: StringNumber::new(11).unwrap(),
             fret: 0,
+            harmonic: None,
+            voice: None,
         };
         let beat_fingering_option = &[fingering_1, fingering_2, fingering_3, fingering_4];
 
@@ -1211,10 +2698,12 @@ type NodeDifficulty = OrderedFloat<f64>;
 /// and the per-beat node groups.
 ///
 /// Returns a vector of tuples, where each tuple contains a `Node` and the `NodeDifficulty`
-/// of moving to that node.
+/// of moving to that node. When `linked_beats` marks the next beat as technique-linked, only
+/// next nodes on the same strings as `current_node` are returned.
 fn calc_next_nodes(
     current_node: &Node,
     path_node_groups: &[BeatVec<Node>],
+    linked_beats: &[bool],
     weights: DifficultyWeights,
 ) -> Vec<(Node, NodeDifficulty)> {
     let next_node_index = match current_node {
@@ -1228,11 +2717,17 @@ fn calc_next_nodes(
     // The graph is layered and a group's position equals its `line_index`, so every successor
     // is exactly one contiguous group. `.get()` returns `None` past the last layer, where the
     // old full-list filter naturally returned empty.
+    let is_linked = linked_beats
+        .get(next_node_index as usize)
+        .copied()
+        .unwrap_or(false);
+
     path_node_groups
         .get(next_node_index as usize)
         .map(|group| {
             group
                 .iter()
+                .filter(|next_node| !is_linked || same_strings(current_node, next_node))
                 .map(|next_node| {
                     (
                         next_node.clone(),
@@ -1256,6 +2751,8 @@ mod test_calc_next_nodes {
                         beat_fingering: vec![],
                         avg_non_zero_fret: Some(OrderedFloat(0.1)),
                         non_zero_fret_span: 0,
+                        harmonic_penalty: 0,
+                        voice_penalty: 0,
                     }),
                 },
                 Node::Playable {
@@ -1264,6 +2761,8 @@ mod test_calc_next_nodes {
                         beat_fingering: vec![],
                         avg_non_zero_fret: Some(OrderedFloat(0.2)),
                         non_zero_fret_span: 0,
+                        harmonic_penalty: 0,
+                        voice_penalty: 0,
                     }),
                 },
             ],
@@ -1273,6 +2772,8 @@ mod test_calc_next_nodes {
                     beat_fingering: vec![],
                     avg_non_zero_fret: Some(OrderedFloat(1.1)),
                     non_zero_fret_span: 1,
+                    harmonic_penalty: 0,
+                    voice_penalty: 0,
                 }),
             }],
             vec![Node::Rest { line_index: 2 }],
@@ -1284,6 +2785,8 @@ mod test_calc_next_nodes {
                         beat_fingering: vec![],
                         avg_non_zero_fret: Some(OrderedFloat(4.1)),
                         non_zero_fret_span: 4,
+                        harmonic_penalty: 0,
+                        voice_penalty: 0,
                     }),
                 },
                 Node::Playable {
@@ -1292,6 +2795,8 @@ mod test_calc_next_nodes {
                         beat_fingering: vec![],
                         avg_non_zero_fret: Some(OrderedFloat(4.1)),
                         non_zero_fret_span: 4,
+                        harmonic_penalty: 0,
+                        voice_penalty: 0,
                     }),
                 },
             ],
@@ -1309,6 +2814,8 @@ mod test_calc_next_nodes {
                     beat_fingering: vec![],
                     avg_non_zero_fret: Some(OrderedFloat(0.1)),
                     non_zero_fret_span: 0,
+                    harmonic_penalty: 0,
+                    voice_penalty: 0,
                 }),
             },
             Node::Playable {
@@ -1317,6 +2824,8 @@ mod test_calc_next_nodes {
                     beat_fingering: vec![],
                     avg_non_zero_fret: Some(OrderedFloat(0.2)),
                     non_zero_fret_span: 0,
+                    harmonic_penalty: 0,
+                    voice_penalty: 0,
                 }),
             },
         ]
@@ -1333,6 +2842,7 @@ mod test_calc_next_nodes {
             calc_next_nodes(
                 &current_node,
                 &create_test_path_node_groups(),
+                &[],
                 DifficultyWeights::standard()
             ),
             expected_nodes_and_costs
@@ -1346,6 +2856,8 @@ mod test_calc_next_nodes {
                 beat_fingering: vec![],
                 avg_non_zero_fret: Some(OrderedFloat(0.1)),
                 non_zero_fret_span: 0,
+                harmonic_penalty: 0,
+                voice_penalty: 0,
             }),
         };
 
@@ -1355,6 +2867,8 @@ mod test_calc_next_nodes {
                 beat_fingering: vec![],
                 avg_non_zero_fret: Some(OrderedFloat(1.1)),
                 non_zero_fret_span: 1,
+                harmonic_penalty: 0,
+                voice_penalty: 0,
             }),
         }]
         .iter()
@@ -1370,6 +2884,7 @@ mod test_calc_next_nodes {
             calc_next_nodes(
                 &current_node,
                 &create_test_path_node_groups(),
+                &[],
                 DifficultyWeights::standard()
             ),
             expected_nodes_and_costs
@@ -1383,6 +2898,8 @@ mod test_calc_next_nodes {
                 beat_fingering: vec![],
                 avg_non_zero_fret: Some(OrderedFloat(1.1)),
                 non_zero_fret_span: 1,
+                harmonic_penalty: 0,
+                voice_penalty: 0,
             }),
         };
 
@@ -1400,6 +2917,7 @@ mod test_calc_next_nodes {
             calc_next_nodes(
                 &current_node,
                 &create_test_path_node_groups(),
+                &[],
                 DifficultyWeights::standard()
             ),
             expected_nodes_and_costs
@@ -1423,6 +2941,7 @@ mod test_calc_next_nodes {
             calc_next_nodes(
                 &current_node,
                 &create_test_path_node_groups(),
+                &[],
                 DifficultyWeights::standard()
             ),
             expected_nodes_and_costs
@@ -1439,6 +2958,8 @@ mod test_calc_next_nodes {
                     beat_fingering: vec![],
                     avg_non_zero_fret: Some(OrderedFloat(4.1)),
                     non_zero_fret_span: 4,
+                    harmonic_penalty: 0,
+                    voice_penalty: 0,
                 }),
             },
             Node::Playable {
@@ -1447,6 +2968,8 @@ mod test_calc_next_nodes {
                     beat_fingering: vec![],
                     avg_non_zero_fret: Some(OrderedFloat(4.1)),
                     non_zero_fret_span: 4,
+                    harmonic_penalty: 0,
+                    voice_penalty: 0,
                 }),
             },
         ]
@@ -1463,6 +2986,7 @@ mod test_calc_next_nodes {
             calc_next_nodes(
                 &current_node,
                 &create_test_path_node_groups(),
+                &[],
                 DifficultyWeights::standard()
             ),
             expected_nodes_and_costs
@@ -1470,6 +2994,72 @@ mod test_calc_next_nodes {
     }
 }
 
+/// Returns true when both nodes are `Playable` on the same set of strings, or when either is
+/// not `Playable`.
+fn same_strings(current_node: &Node, next_node: &Node) -> bool {
+    match (current_node, next_node) {
+        (
+            Node::Playable {
+                scored_beat_fingering: current,
+                ..
+            },
+            Node::Playable {
+                scored_beat_fingering: next,
+                ..
+            },
+        ) => {
+            let strings = |beat_fingering: &[PitchFingering]| {
+                beat_fingering
+                    .iter()
+                    .map(|fingering| fingering.string_number)
+                    .sorted()
+                    .collect_vec()
+            };
+            strings(&current.beat_fingering) == strings(&next.beat_fingering)
+        }
+        _ => true,
+    }
+}
+#[cfg(test)]
+mod test_same_strings {
+    use super::*;
+    use crate::string_number::StringNumber;
+
+    fn playable(string_numbers: &[u8]) -> Node {
+        Node::Playable {
+            line_index: 0,
+            scored_beat_fingering: Rc::new(ScoredBeatFingering::new(
+                string_numbers
+                    .iter()
+                    .map(|&string_number| PitchFingering {
+                        pitch: Pitch::E4,
+                        string_number: StringNumber::new(string_number).unwrap(),
+                        fret: 0,
+                        harmonic: None,
+                        voice: None,
+                    })
+                    .collect(),
+            )),
+        }
+    }
+
+    #[test]
+    fn matching_strings() {
+        assert!(same_strings(&playable(&[1]), &playable(&[1])));
+        assert!(same_strings(&playable(&[2, 3]), &playable(&[3, 2])));
+    }
+    #[test]
+    fn different_strings() {
+        assert!(!same_strings(&playable(&[1]), &playable(&[2])));
+        assert!(!same_strings(&playable(&[1]), &playable(&[1, 2])));
+    }
+    #[test]
+    fn non_playable_nodes_always_match() {
+        assert!(same_strings(&Node::Start, &playable(&[1])));
+        assert!(same_strings(&playable(&[1]), &Node::Rest { line_index: 1 }));
+    }
+}
+
 /// Calculates the transition difficulty from one node to another based on the
 /// average fret difference and fret span.
 fn calculate_node_difficulty(
@@ -1488,12 +3078,16 @@ fn calculate_node_difficulty(
     let (next_avg_fret, next_fret_span) = match next_node {
         Node::Start => unreachable!("Start should never be a future node."),
         Node::Rest { .. } => (None, 0.0),
+        // A harmonic, or a voice off its preferred strings, costs like extra fret span, so it
+        // rides the `span` weight.
         Node::Playable {
             scored_beat_fingering,
             ..
         } => (
             scored_beat_fingering.avg_non_zero_fret,
-            scored_beat_fingering.non_zero_fret_span as f64,
+            (scored_beat_fingering.non_zero_fret_span
+                + scored_beat_fingering.harmonic_penalty
+                + scored_beat_fingering.voice_penalty) as f64,
         ),
     };
 
@@ -1531,6 +3125,8 @@ mod test_calculate_node_difficulty {
                 beat_fingering: vec![],
                 avg_non_zero_fret: Some(OrderedFloat(3.5)),
                 non_zero_fret_span: 0,
+                harmonic_penalty: 0,
+                voice_penalty: 0,
             }),
         };
         let next_node = Node::Playable {
@@ -1539,6 +3135,8 @@ mod test_calculate_node_difficulty {
                 beat_fingering: vec![],
                 avg_non_zero_fret: Some(OrderedFloat(3.5)),
                 non_zero_fret_span: 0,
+                harmonic_penalty: 0,
+                voice_penalty: 0,
             }),
         };
 
@@ -1555,6 +3153,8 @@ mod test_calculate_node_difficulty {
                 beat_fingering: vec![],
                 avg_non_zero_fret: Some(OrderedFloat(3.5)),
                 non_zero_fret_span: 0,
+                harmonic_penalty: 0,
+                voice_penalty: 0,
             }),
         };
 
@@ -1571,6 +3171,8 @@ mod test_calculate_node_difficulty {
                 beat_fingering: vec![],
                 avg_non_zero_fret: Some(OrderedFloat(3.5)),
                 non_zero_fret_span: 0,
+                harmonic_penalty: 0,
+                voice_penalty: 0,
             }),
         };
 
@@ -1591,6 +3193,8 @@ mod test_calculate_node_difficulty {
                 beat_fingering: vec![],
                 avg_non_zero_fret: Some(OrderedFloat(3.5)),
                 non_zero_fret_span: 0,
+                harmonic_penalty: 0,
+                voice_penalty: 0,
             }),
         };
 
@@ -1611,6 +3215,8 @@ mod test_calculate_node_difficulty {
                 beat_fingering: vec![],
                 avg_non_zero_fret: Some(OrderedFloat(3.0)),
                 non_zero_fret_span: 0,
+                harmonic_penalty: 0,
+                voice_penalty: 0,
             }),
         };
         let next_node = Node::Playable {
@@ -1619,6 +3225,8 @@ mod test_calculate_node_difficulty {
                 beat_fingering: vec![],
                 avg_non_zero_fret: Some(OrderedFloat(1.6)),
                 non_zero_fret_span: 0,
+                harmonic_penalty: 0,
+                voice_penalty: 0,
             }),
         };
 
@@ -1635,6 +3243,8 @@ mod test_calculate_node_difficulty {
                 beat_fingering: vec![],
                 avg_non_zero_fret: Some(OrderedFloat(4.133333)),
                 non_zero_fret_span: 0,
+                harmonic_penalty: 0,
+                voice_penalty: 0,
             }),
         };
         let next_node = Node::Playable {
@@ -1643,6 +3253,8 @@ mod test_calculate_node_difficulty {
                 beat_fingering: vec![],
                 avg_non_zero_fret: Some(OrderedFloat(4.133333)),
                 non_zero_fret_span: 3,
+                harmonic_penalty: 0,
+                voice_penalty: 0,
             }),
         };
 
@@ -1659,6 +3271,8 @@ mod test_calculate_node_difficulty {
                 beat_fingering: vec![],
                 avg_non_zero_fret: Some(OrderedFloat(5.0)),
                 non_zero_fret_span: 0,
+                harmonic_penalty: 0,
+                voice_penalty: 0,
             }),
         };
         let next_node = Node::Playable {
@@ -1667,6 +3281,8 @@ mod test_calculate_node_difficulty {
                 beat_fingering: vec![],
                 avg_non_zero_fret: Some(OrderedFloat(2.0)),
                 non_zero_fret_span: 5,
+                harmonic_penalty: 0,
+                voice_penalty: 0,
             }),
         };
 
@@ -1683,6 +3299,8 @@ mod test_calculate_node_difficulty {
                 beat_fingering: vec![],
                 avg_non_zero_fret: Some(OrderedFloat(7.3333333)),
                 non_zero_fret_span: 0,
+                harmonic_penalty: 0,
+                voice_penalty: 0,
             }),
         };
         let next_node = Node::Playable {
@@ -1691,6 +3309,8 @@ mod test_calculate_node_difficulty {
                 beat_fingering: vec![],
                 avg_non_zero_fret: Some(OrderedFloat(3.6666666)),
                 non_zero_fret_span: 4,
+                harmonic_penalty: 0,
+                voice_penalty: 0,
             }),
         };
 
@@ -1722,6 +3342,8 @@ mod test_calculate_node_difficulty {
                 beat_fingering: vec![],
                 avg_non_zero_fret: Some(OrderedFloat(3.0)),
                 non_zero_fret_span: 0,
+                harmonic_penalty: 0,
+                voice_penalty: 0,
             }),
         };
         let next = Node::Playable {
@@ -1730,6 +3352,8 @@ mod test_calculate_node_difficulty {
                 beat_fingering: vec![],
                 avg_non_zero_fret: Some(OrderedFloat(5.0)),
                 non_zero_fret_span: 2,
+                harmonic_penalty: 0,
+                voice_penalty: 0,
             }),
         };
         // avg_fret_difference = 2, next_fret_span = 2, next_avg_fret = 5
@@ -1754,6 +3378,8 @@ mod test_calculate_node_difficulty {
                 beat_fingering: vec![],
                 avg_non_zero_fret: Some(OrderedFloat(3.0)),
                 non_zero_fret_span: 0,
+                harmonic_penalty: 0,
+                voice_penalty: 0,
             }),
         };
         let next = Node::Playable {
@@ -1762,6 +3388,8 @@ mod test_calculate_node_difficulty {
                 beat_fingering: vec![],
                 avg_non_zero_fret: Some(OrderedFloat(3.5)),
                 non_zero_fret_span: 0,
+                harmonic_penalty: 0,
+                voice_penalty: 0,
             }),
         };
         // movement only: avg_fret_difference = 0.5, weighted = 0.5 (0 under the old cast).
@@ -1776,7 +3404,7 @@ mod test_calculate_node_difficulty {
 fn process_path(
     path_nodes: Vec<Node>,
     path_difficulty: NodeDifficulty,
-    measure_break_indices: &[usize],
+    marker_lines: &[(usize, Line<BeatVec<PitchFingering>>)],
 ) -> Arrangement {
     let mut lines: Vec<Line<BeatVec<PitchFingering>>> = path_nodes
         .iter()
@@ -1790,11 +3418,11 @@ fn process_path(
             } => Line::Playable(scored_beat_fingering.beat_fingering.clone()),
         })
         .collect_vec();
-    // Re-inject measure breaks. `measure_break_indices` is built by `enumerate().filter()`
-    // upstream, so it is already ascending. Inserting low to high lands each break at its
+    // Re-inject structural markers. `marker_lines` is built by `enumerate().filter_map()`
+    // upstream, so it is already ascending. Inserting low to high lands each marker at its
     // original post-skip slot without shifting an earlier one.
-    for &measure_break_index in measure_break_indices {
-        lines.insert(measure_break_index, Line::MeasureBreak);
+    for (marker_index, marker) in marker_lines {
+        lines.insert(*marker_index, marker.clone());
     }
 
     let max_fret_span: u8 = path_nodes
@@ -1815,6 +3443,7 @@ fn process_path(
         lines,
         difficulty: path_difficulty,
         max_fret_span,
+        cut_off_notes: vec![],
     }
 }
 #[cfg(test)]
@@ -1829,9 +3458,13 @@ mod test_process_path {
                 pitch: Pitch::C4,
                 string_number: StringNumber::new(1).unwrap(),
                 fret: 3,
+                harmonic: None,
+                voice: None,
             }],
             avg_non_zero_fret: Some(OrderedFloat(3.0)),
             non_zero_fret_span: 0,
+            harmonic_penalty: 0,
+            voice_penalty: 0,
         };
 
         let path_nodes = vec![
@@ -1848,6 +3481,7 @@ mod test_process_path {
             lines: vec![Playable(placeholder_scored_beat_fingering.beat_fingering)],
             difficulty: OrderedFloat(123.0),
             max_fret_span: 0,
+            cut_off_notes: vec![],
         };
 
         assert_eq!(arrangement, expected_arrangement);
@@ -1859,9 +3493,13 @@ mod test_process_path {
                 pitch: Pitch::C4,
                 string_number: StringNumber::new(1).unwrap(),
                 fret: 3,
+                harmonic: None,
+                voice: None,
             }],
             avg_non_zero_fret: Some(OrderedFloat(3.0)),
             non_zero_fret_span: 4,
+            harmonic_penalty: 0,
+            voice_penalty: 0,
         };
 
         let path_nodes = vec![
@@ -1885,7 +3523,16 @@ mod test_process_path {
             },
         ];
 
-        let arrangement = process_path(path_nodes, OrderedFloat(321.0), &[0, 2, 5, 7]);
+        let arrangement = process_path(
+            path_nodes,
+            OrderedFloat(321.0),
+            &[
+                (0, MeasureBreak),
+                (2, MeasureBreak),
+                (5, MeasureBreak),
+                (7, MeasureBreak),
+            ],
+        );
 
         let expected_arrangement = Arrangement {
             lines: vec![
@@ -1901,10 +3548,59 @@ mod test_process_path {
             ],
             difficulty: OrderedFloat(321.0),
             max_fret_span: 4,
+            cut_off_notes: vec![],
         };
 
         assert_eq!(arrangement, expected_arrangement);
     }
+    #[test]
+    fn reinjects_repeat_and_section_markers() {
+        let placeholder_scored_beat_fingering = ScoredBeatFingering {
+            beat_fingering: vec![PitchFingering {
+                pitch: Pitch::C4,
+                string_number: StringNumber::new(1).unwrap(),
+                fret: 3,
+                harmonic: None,
+                voice: None,
+            }],
+            avg_non_zero_fret: Some(OrderedFloat(3.0)),
+            non_zero_fret_span: 0,
+            harmonic_penalty: 0,
+            voice_penalty: 0,
+        };
+
+        let path_nodes = vec![
+            Node::Start,
+            Node::Playable {
+                line_index: 0,
+                scored_beat_fingering: Rc::new(placeholder_scored_beat_fingering.clone()),
+            },
+            Node::Rest { line_index: 1 },
+        ];
+
+        let arrangement = process_path(
+            path_nodes,
+            OrderedFloat(0.0),
+            &[
+                (0, Section("Verse".to_owned())),
+                (1, RepeatStart),
+                (3, Ending(1)),
+                (5, RepeatEnd),
+            ],
+        );
+
+        assert_eq!(
+            arrangement.lines,
+            vec![
+                Section("Verse".to_owned()),
+                RepeatStart,
+                Playable(placeholder_scored_beat_fingering.beat_fingering),
+                Ending(1),
+                Rest,
+                RepeatEnd,
+            ]
+        );
+    }
 }
 
 // `proptest` is a non-wasm dev-dependency (it does not compile for `wasm32`), so this module
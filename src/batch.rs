@@ -0,0 +1,217 @@
+//! Arranging many inputs against one guitar and difficulty configuration.
+//!
+//! [`arrange_batch`] checks the shared configuration once, then arranges each input on the
+//! uncached path. The memoize caches on `parse_input` and `create_parsed_arrangements` hold ten
+//! entries, so a songbook of a few hundred pieces would only evict what interactive callers
+//! keep there without ever hitting it.
+
+use crate::{
+    ArrangementSet, DifficultyWeights, Guitar, NumArrangements, TabError, TabInput,
+    UnplayablePitch, generate, parser,
+};
+use serde::Serialize;
+
+/// One named input of a batch, such as a file of a songbook.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BatchInput {
+    pub name: String,
+    pub input: String,
+}
+
+impl BatchInput {
+    pub fn new(name: impl Into<String>, input: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            input: input.into(),
+        }
+    }
+}
+
+/// The outcome of one input of a batch.
+#[derive(Debug)]
+pub struct BatchEntry {
+    pub name: String,
+    pub result: Result<ArrangementSet, TabError>,
+}
+
+/// The per-input outcomes of [`arrange_batch`], in input order.
+#[derive(Debug)]
+pub struct BatchReport {
+    entries: Vec<BatchEntry>,
+}
+
+impl BatchReport {
+    pub fn entries(&self) -> &[BatchEntry] {
+        &self.entries
+    }
+
+    /// Number of inputs that failed to arrange.
+    pub fn num_failed(&self) -> usize {
+        self.entries
+            .iter()
+            .filter(|entry| entry.result.is_err())
+            .count()
+    }
+
+    /// One summary row per input, in input order.
+    pub fn summary(&self) -> Vec<BatchSummaryRow> {
+        self.entries
+            .iter()
+            .map(BatchSummaryRow::from_entry)
+            .collect()
+    }
+}
+
+/// The report line of one input. `difficulty` and `max_fret_span` describe the easiest
+/// arrangement and are absent on failure. `unplayable_pitches` lifts the payload of a
+/// [`TabError::UnplayablePitches`] failure so a report can list them without matching on
+/// `error`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchSummaryRow {
+    pub name: String,
+    pub difficulty: Option<f64>,
+    pub max_fret_span: Option<u8>,
+    pub unplayable_pitches: Vec<UnplayablePitch>,
+    pub error: Option<TabError>,
+}
+
+impl BatchSummaryRow {
+    fn from_entry(entry: &BatchEntry) -> Self {
+        let mut row = Self {
+            name: entry.name.clone(),
+            difficulty: None,
+            max_fret_span: None,
+            unplayable_pitches: Vec::new(),
+            error: None,
+        };
+        match &entry.result {
+            Ok(set) => {
+                row.difficulty = set.difficulty(0).ok();
+                row.max_fret_span = set.max_fret_span(0).ok();
+            }
+            Err(error) => {
+                if let TabError::UnplayablePitches { pitches } = error {
+                    row.unplayable_pitches = pitches.clone();
+                }
+                row.error = Some(error.clone());
+            }
+        }
+        row
+    }
+}
+
+/// Arranges each of `inputs` with the guitar, weights and options of `config`, whose own
+/// `input` is ignored.
+///
+/// Fails before arranging anything when the shared configuration is invalid: an unknown
+/// tuning, a bad fret or capo, an out-of-range arrangement count or difficulty weight. Every
+/// other failure is recorded on its input's [`BatchEntry`] and the batch carries on.
+pub fn arrange_batch(
+    config: &TabInput,
+    inputs: impl IntoIterator<Item = BatchInput>,
+) -> Result<BatchReport, TabError> {
+    NumArrangements::try_new(config.num_arrangements)?;
+    if let Some(raw) = config.difficulty_weights {
+        DifficultyWeights::try_new(raw.movement, raw.span, raw.position)?;
+    }
+    let tuning = parser::create_string_tuning_offset(parser::parse_tuning(&config.tuning_name)?);
+    Guitar::new(tuning, config.guitar_num_frets, config.guitar_capo)?;
+
+    let entries = inputs
+        .into_iter()
+        .map(|BatchInput { name, input }| {
+            let tab_input = TabInput {
+                input,
+                ..config.clone()
+            };
+            BatchEntry {
+                name,
+                result: generate(tab_input, false),
+            }
+        })
+        .collect();
+    Ok(BatchReport { entries })
+}
+#[cfg(test)]
+mod test_arrange_batch {
+    use super::*;
+    use crate::generate_arrangements;
+
+    fn config() -> TabInput {
+        TabInput::new("", "standard", 18, 0, 2)
+    }
+
+    #[test]
+    fn matches_single_arrangement() {
+        let report = arrange_batch(
+            &config(),
+            [
+                BatchInput::new("a", "E2\nA2"),
+                BatchInput::new("b", "G3 B3"),
+            ],
+        )
+        .unwrap();
+        assert_eq!(report.num_failed(), 0);
+        for (entry, input) in report.entries().iter().zip(["E2\nA2", "G3 B3"]) {
+            let expected = generate_arrangements(TabInput {
+                input: input.to_owned(),
+                ..config()
+            })
+            .unwrap();
+            assert_eq!(
+                entry.result.as_ref().unwrap().export_json(),
+                expected.export_json()
+            );
+        }
+    }
+    #[test]
+    fn records_failures_and_carries_on() {
+        let report = arrange_batch(
+            &config(),
+            [
+                BatchInput::new("bad", "H2"),
+                BatchInput::new("low", "E2\nC2"),
+                BatchInput::new("good", "E2"),
+            ],
+        )
+        .unwrap();
+        assert_eq!(report.num_failed(), 2);
+
+        let summary = report.summary();
+        assert!(matches!(summary[0].error, Some(TabError::Parse { .. })));
+        assert!(summary[0].unplayable_pitches.is_empty());
+        assert_eq!(
+            summary[1].unplayable_pitches,
+            [UnplayablePitch {
+                value: "C2".to_owned(),
+                line: 2
+            }]
+        );
+        assert!(matches!(
+            summary[1].error,
+            Some(TabError::UnplayablePitches { .. })
+        ));
+        assert_eq!(summary[2].name, "good");
+        assert_eq!(summary[2].max_fret_span, Some(0));
+        assert!(summary[2].difficulty.is_some() && summary[2].error.is_none());
+    }
+    #[test]
+    fn rejects_shared_config_up_front() {
+        let config = TabInput {
+            tuning_name: "openZ".to_owned(),
+            ..config()
+        };
+        let error = arrange_batch(&config, [BatchInput::new("a", "H2")]).unwrap_err();
+        assert!(matches!(error, TabError::TuningNameUnknown { .. }));
+
+        let config = TabInput {
+            num_arrangements: 0,
+            ..TabInput::new("", "standard", 18, 0, 1)
+        };
+        assert!(matches!(
+            arrange_batch(&config, []).unwrap_err(),
+            TabError::NumArrangementsOutOfRange { .. }
+        ));
+    }
+}
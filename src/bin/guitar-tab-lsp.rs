@@ -0,0 +1,14 @@
+//! `guitar-tab-lsp`: serves the language server protocol for the pitch input format on stdin
+//! and stdout. See [`guitar_tab_generator::lsp`].
+
+use std::process::ExitCode;
+
+fn main() -> ExitCode {
+    match guitar_tab_generator::lsp::serve_stdio() {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("error: {error}");
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,14 @@
+//! `guitar-tab-rpc`: serves JSON-RPC requests, one per line, on stdin and stdout. See
+//! [`guitar_tab_generator::rpc`].
+
+use std::process::ExitCode;
+
+fn main() -> ExitCode {
+    match guitar_tab_generator::rpc::run(std::io::stdin().lock(), &mut std::io::stdout().lock()) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("error: {error}");
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,14 @@
+//! `guitar-tab`: arranges pitches from a file or stdin and prints the tabs. See
+//! [`guitar_tab_generator::cli`].
+
+use std::process::ExitCode;
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    ExitCode::from(guitar_tab_generator::cli::run(
+        &args,
+        std::io::stdin().lock(),
+        &mut std::io::stdout().lock(),
+        &mut std::io::stderr().lock(),
+    ))
+}
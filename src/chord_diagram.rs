@@ -0,0 +1,512 @@
+use crate::{
+    arrangement::{BeatVec, Line},
+    guitar::{Guitar, PitchFingering},
+};
+use itertools::Itertools;
+use serde::Serialize;
+use std::fmt::Write;
+#[cfg(feature = "wasm")]
+use tsify::Tsify;
+
+/// Fewest notes a beat needs to count as a chord.
+const MIN_CHORD_NOTES: usize = 3;
+/// Fewest fret rows a diagram shows, so small shapes keep a familiar chord-box height.
+const MIN_FRET_ROWS: u8 = 4;
+
+/// A barre across the strings from `from_string` down to `to_string` at `fret`.
+/// `from_string` is the higher string number, the lower-pitched end of the barre.
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "wasm", derive(Tsify))]
+#[serde(rename_all = "camelCase")]
+pub struct Barre {
+    pub fret: u8,
+    pub from_string: u8,
+    pub to_string: u8,
+}
+
+/// The fretboard shape of one chord beat, as drawn in a chord diagram.
+#[derive(Debug, Clone, Hash, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "wasm", derive(Tsify), tsify(into_wasm_abi))]
+#[serde(rename_all = "camelCase")]
+pub struct ChordShape {
+    /// Fret on each string from string 1 down. `None` marks a muted string and `Some(0)` an
+    /// open one.
+    pub frets: Vec<Option<u8>>,
+    /// Top fret of the diagram window. `1` when the window starts at the nut.
+    pub base_fret: u8,
+    /// Number of fret rows in the window.
+    pub num_fret_rows: u8,
+    pub barre: Option<Barre>,
+}
+impl ChordShape {
+    /// The shape of `beat` on `guitar`. Returns `None` for a beat of fewer than three notes,
+    /// or one holding a harmonic, which a chord box cannot show.
+    #[must_use]
+    pub fn from_beat(beat: &[PitchFingering], guitar: &Guitar) -> Option<Self> {
+        if beat.len() < MIN_CHORD_NOTES || beat.iter().any(|f| f.harmonic().is_some()) {
+            return None;
+        }
+        let mut frets = vec![None; guitar.string_ranges.len()];
+        for fingering in beat {
+            let string_index = fingering.string_number.get() as usize - 1;
+            if let Some(slot) = frets.get_mut(string_index) {
+                *slot = Some(fingering.fret);
+            }
+        }
+
+        let fretted = frets.iter().flatten().copied().filter(|&fret| fret > 0);
+        let (lowest_fret, highest_fret) = fretted.minmax().into_option().unwrap_or((1, 1));
+        let base_fret = if highest_fret <= MIN_FRET_ROWS {
+            1
+        } else {
+            lowest_fret
+        };
+        let num_fret_rows = (highest_fret - base_fret + 1).max(MIN_FRET_ROWS);
+        let barre = find_barre(&frets);
+        Some(ChordShape {
+            frets,
+            base_fret,
+            num_fret_rows,
+            barre,
+        })
+    }
+}
+#[cfg(test)]
+mod test_chord_shape {
+    use super::*;
+    use crate::{pitch::Pitch, string_number::StringNumber};
+
+    pub(super) fn beat(frets: &[(u8, u8)]) -> BeatVec<PitchFingering> {
+        frets
+            .iter()
+            .map(|&(string_number, fret)| PitchFingering {
+                pitch: Pitch::E4,
+                string_number: StringNumber::new(string_number).unwrap(),
+                fret,
+                harmonic: None,
+                voice: None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn open_c_major() {
+        let shape = ChordShape::from_beat(
+            &beat(&[(5, 3), (4, 2), (3, 0), (2, 1), (1, 0)]),
+            &Guitar::default(),
+        )
+        .unwrap();
+        assert_eq!(
+            shape.frets,
+            vec![Some(0), Some(1), Some(0), Some(2), Some(3), None]
+        );
+        assert_eq!(shape.base_fret, 1);
+        assert_eq!(shape.num_fret_rows, 4);
+        assert_eq!(shape.barre, None);
+    }
+    #[test]
+    fn window_moves_up_the_neck() {
+        let shape =
+            ChordShape::from_beat(&beat(&[(6, 5), (5, 7), (4, 7), (3, 6)]), &Guitar::default())
+                .unwrap();
+        assert_eq!(shape.base_fret, 5);
+        assert_eq!(shape.num_fret_rows, 4);
+    }
+    #[test]
+    fn wide_shape_grows_the_window() {
+        let shape =
+            ChordShape::from_beat(&beat(&[(6, 3), (3, 5), (1, 8)]), &Guitar::default()).unwrap();
+        assert_eq!(shape.base_fret, 3);
+        assert_eq!(shape.num_fret_rows, 6);
+    }
+    #[test]
+    fn too_few_notes() {
+        assert!(ChordShape::from_beat(&beat(&[(6, 3), (1, 3)]), &Guitar::default()).is_none());
+    }
+    #[test]
+    fn harmonics_are_not_chords() {
+        let mut harmonic_beat = beat(&[(3, 12), (2, 12), (1, 12)]);
+        harmonic_beat[0].harmonic = Some(crate::guitar::Harmonic::Natural);
+        assert!(ChordShape::from_beat(&harmonic_beat, &Guitar::default()).is_none());
+    }
+}
+
+/// A barre at the lowest fretted fret, when two or more strings hold it and every string
+/// between them is fretted at or above it.
+fn find_barre(frets: &[Option<u8>]) -> Option<Barre> {
+    let fret = frets
+        .iter()
+        .flatten()
+        .copied()
+        .filter(|&fret| fret > 0)
+        .min()?;
+    let (first_index, last_index) = frets
+        .iter()
+        .positions(|&string_fret| string_fret == Some(fret))
+        .minmax()
+        .into_option()?;
+    if first_index == last_index
+        || frets[first_index..=last_index]
+            .iter()
+            .any(|string_fret| string_fret.is_none_or(|string_fret| string_fret < fret))
+    {
+        return None;
+    }
+    Some(Barre {
+        fret,
+        from_string: last_index as u8 + 1,
+        to_string: first_index as u8 + 1,
+    })
+}
+#[cfg(test)]
+mod test_find_barre {
+    use super::*;
+
+    #[test]
+    fn full_barre() {
+        // F major: 133211
+        let frets = [Some(1), Some(1), Some(2), Some(3), Some(3), Some(1)];
+        assert_eq!(
+            find_barre(&frets),
+            Some(Barre {
+                fret: 1,
+                from_string: 6,
+                to_string: 1,
+            })
+        );
+    }
+    #[test]
+    fn partial_barre() {
+        // A major: x02220
+        let frets = [Some(0), Some(2), Some(2), Some(2), Some(0), None];
+        assert_eq!(
+            find_barre(&frets),
+            Some(Barre {
+                fret: 2,
+                from_string: 4,
+                to_string: 2,
+            })
+        );
+    }
+    #[test]
+    fn open_string_breaks_the_barre() {
+        let frets = [Some(2), Some(0), Some(2), None, None, None];
+        assert_eq!(find_barre(&frets), None);
+    }
+    #[test]
+    fn single_string_at_the_lowest_fret() {
+        // C major: x32010
+        let frets = [Some(0), Some(1), Some(0), Some(2), Some(3), None];
+        assert_eq!(find_barre(&frets), None);
+    }
+}
+
+/// The distinct chord shapes in `lines`, in order of first appearance.
+pub(crate) fn chord_shapes(
+    lines: &[Line<BeatVec<PitchFingering>>],
+    guitar: &Guitar,
+) -> Vec<ChordShape> {
+    lines
+        .iter()
+        .filter_map(|line| match line {
+            Line::Playable(beat) => ChordShape::from_beat(beat, guitar),
+            _ => None,
+        })
+        .unique()
+        .collect()
+}
+#[cfg(test)]
+mod test_chord_shapes {
+    use super::{test_chord_shape::beat, *};
+
+    #[test]
+    fn distinct_in_order() {
+        let g_major = beat(&[(6, 3), (5, 2), (1, 3)]);
+        let e_minor = beat(&[(5, 2), (4, 2), (3, 0)]);
+        let lines = vec![
+            Line::Playable(g_major.clone()),
+            Line::Playable(beat(&[(1, 0)])),
+            Line::MeasureBreak,
+            Line::Playable(e_minor.clone()),
+            Line::Playable(g_major.clone()),
+        ];
+        let shapes = chord_shapes(&lines, &Guitar::default());
+        assert_eq!(
+            shapes,
+            vec![
+                ChordShape::from_beat(&g_major, &Guitar::default()).unwrap(),
+                ChordShape::from_beat(&e_minor, &Guitar::default()).unwrap(),
+            ]
+        );
+    }
+}
+
+/// Renders `shape` as an ASCII chord diagram, lowest-pitched string on the left.
+///
+/// The top row marks open strings `o` and muted strings `x`. Below the nut (`=`), or a plain
+/// line and a `Nfr` label when the window starts higher, each fret row shows fingered
+/// strings as `*`, with a barre joining its strings.
+#[must_use]
+pub fn render_chord_diagram(shape: &ChordShape) -> String {
+    let columns = shape.frets.iter().rev().collect_vec();
+    let row_width = (columns.len() * 2).saturating_sub(1);
+    let mut out = String::new();
+
+    out.push_str(
+        &columns
+            .iter()
+            .map(|fret| match fret {
+                None => 'x',
+                Some(0) => 'o',
+                Some(_) => ' ',
+            })
+            .join(" "),
+    );
+    out.push('\n');
+    let top = if shape.base_fret == 1 { "=" } else { "-" };
+    out.push_str(&top.repeat(row_width));
+    out.push('\n');
+
+    for row in 0..shape.num_fret_rows {
+        let fret = shape.base_fret + row;
+        let barre_columns = shape.barre.filter(|barre| barre.fret == fret).map(|barre| {
+            let num_strings = columns.len();
+            (
+                num_strings - barre.from_string as usize,
+                num_strings - barre.to_string as usize,
+            )
+        });
+        for (column, string_fret) in columns.iter().enumerate() {
+            let in_barre = |column| {
+                barre_columns.is_some_and(|(first, last)| (first..=last).contains(&column))
+            };
+            if column > 0 {
+                out.push(if in_barre(column - 1) && in_barre(column) {
+                    '*'
+                } else {
+                    ' '
+                });
+            }
+            out.push(if **string_fret == Some(fret) || in_barre(column) {
+                '*'
+            } else {
+                '|'
+            });
+        }
+        if row == 0 && shape.base_fret > 1 {
+            let _ = write!(out, " {}fr", shape.base_fret);
+        }
+        out.push('\n');
+        out.push_str(&"-".repeat(row_width));
+        out.push('\n');
+    }
+    out.pop();
+    out
+}
+#[cfg(test)]
+mod test_render_chord_diagram {
+    use super::{test_chord_shape::beat, *};
+
+    #[test]
+    fn open_chord() {
+        let shape = ChordShape::from_beat(
+            &beat(&[(5, 3), (4, 2), (3, 0), (2, 1), (1, 0)]),
+            &Guitar::default(),
+        )
+        .unwrap();
+        assert_eq!(
+            render_chord_diagram(&shape),
+            "x     o   o\n\
+             ===========\n\
+             | | | | * |\n\
+             -----------\n\
+             | | * | | |\n\
+             -----------\n\
+             | * | | | |\n\
+             -----------\n\
+             | | | | | |\n\
+             -----------"
+        );
+    }
+    #[test]
+    fn barre_chord_up_the_neck() {
+        // B minor at the second fret: x24432
+        let shape = ChordShape::from_beat(
+            &beat(&[(5, 2), (4, 4), (3, 4), (2, 3), (1, 2)]),
+            &Guitar::default(),
+        )
+        .unwrap();
+        assert_eq!(
+            render_chord_diagram(&shape),
+            "x          \n\
+             ===========\n\
+             | | | | | |\n\
+             -----------\n\
+             | *********\n\
+             -----------\n\
+             | | | | * |\n\
+             -----------\n\
+             | | * * | |\n\
+             -----------"
+        );
+    }
+    #[test]
+    fn window_label() {
+        let shape =
+            ChordShape::from_beat(&beat(&[(6, 5), (5, 7), (4, 7), (3, 6)]), &Guitar::default())
+                .unwrap();
+        let diagram = render_chord_diagram(&shape);
+        let rows = diagram.lines().collect_vec();
+        assert_eq!(rows[0], "        x x");
+        assert_eq!(rows[1], "-----------");
+        assert_eq!(rows[2], "* | | | | | 5fr");
+    }
+}
+
+/// Horizontal gap between strings in an SVG chord diagram.
+const STRING_SPACING: f64 = 20.0;
+/// Vertical gap between frets in an SVG chord diagram.
+const FRET_SPACING: f64 = 24.0;
+/// Space left of the lowest string and right of the highest, in SVG user units.
+const MARGIN: f64 = 20.0;
+/// Height of the open and muted marker row above the nut.
+const MARKER_ROW_HEIGHT: f64 = 24.0;
+/// Radius of a fingered-note dot.
+const DOT_RADIUS: f64 = 7.0;
+
+/// Default look for a standalone chord diagram. Every rule targets a class, so page CSS can
+/// override it.
+const STYLE: &str = ".string,.fret{stroke:currentColor;stroke-width:1px}\
+.nut{stroke:currentColor;stroke-width:4px}\
+.finger,.barre{fill:currentColor}\
+.open{fill:none;stroke:currentColor;stroke-width:1px}\
+.muted,.base-fret{font-family:monospace;font-size:12px;fill:currentColor}";
+
+/// Renders `shape` as an SVG chord diagram laid out like [`render_chord_diagram`].
+#[must_use]
+pub fn render_chord_diagram_svg(shape: &ChordShape) -> String {
+    let num_strings = shape.frets.len();
+    let grid_width = num_strings.saturating_sub(1) as f64 * STRING_SPACING;
+    let grid_bottom = MARKER_ROW_HEIGHT + f64::from(shape.num_fret_rows) * FRET_SPACING;
+    // Leave room on the right for the `Nfr` label.
+    let width = grid_width + 3.0 * MARGIN;
+    let height = grid_bottom + MARGIN / 2.0;
+    let string_x =
+        |string_index: usize| MARGIN + (num_strings - 1 - string_index) as f64 * STRING_SPACING;
+    let fret_y =
+        |fret: u8| MARKER_ROW_HEIGHT + (f64::from(fret - shape.base_fret) + 0.5) * FRET_SPACING;
+
+    let mut out = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" class=\"chord-diagram\" width=\"{width}\" \
+         height=\"{height}\" viewBox=\"0 0 {width} {height}\"><style>{STYLE}</style>"
+    );
+    for row in 0..=shape.num_fret_rows {
+        let y = MARKER_ROW_HEIGHT + f64::from(row) * FRET_SPACING;
+        let class = if row == 0 && shape.base_fret == 1 {
+            "nut"
+        } else {
+            "fret"
+        };
+        let _ = write!(
+            out,
+            "<line class=\"{class}\" x1=\"{MARGIN}\" y1=\"{y}\" x2=\"{}\" y2=\"{y}\"/>",
+            MARGIN + grid_width
+        );
+    }
+    for string_index in 0..num_strings {
+        let x = string_x(string_index);
+        let _ = write!(
+            out,
+            "<line class=\"string\" x1=\"{x}\" y1=\"{MARKER_ROW_HEIGHT}\" x2=\"{x}\" \
+             y2=\"{grid_bottom}\"/>"
+        );
+    }
+    if shape.base_fret > 1 {
+        let _ = write!(
+            out,
+            "<text class=\"base-fret\" x=\"{}\" y=\"{}\" dominant-baseline=\"central\">{}fr</text>",
+            MARGIN + grid_width + DOT_RADIUS + 4.0,
+            fret_y(shape.base_fret),
+            shape.base_fret
+        );
+    }
+    if let Some(barre) = shape.barre {
+        let left = string_x(barre.from_string as usize - 1) - DOT_RADIUS;
+        let right = string_x(barre.to_string as usize - 1) + DOT_RADIUS;
+        let _ = write!(
+            out,
+            "<rect class=\"barre\" x=\"{left}\" y=\"{}\" width=\"{}\" height=\"{}\" rx=\"{DOT_RADIUS}\"/>",
+            fret_y(barre.fret) - DOT_RADIUS,
+            right - left,
+            2.0 * DOT_RADIUS
+        );
+    }
+    let marker_y = MARKER_ROW_HEIGHT / 2.0;
+    for (string_index, fret) in shape.frets.iter().enumerate() {
+        let x = string_x(string_index);
+        match fret {
+            None => {
+                let _ = write!(
+                    out,
+                    "<text class=\"muted\" x=\"{x}\" y=\"{marker_y}\" text-anchor=\"middle\" \
+                     dominant-baseline=\"central\">x</text>"
+                );
+            }
+            Some(0) => {
+                let _ = write!(
+                    out,
+                    "<circle class=\"open\" cx=\"{x}\" cy=\"{marker_y}\" r=\"5\"/>"
+                );
+            }
+            Some(fret) => {
+                let _ = write!(
+                    out,
+                    "<circle class=\"finger\" cx=\"{x}\" cy=\"{}\" r=\"{DOT_RADIUS}\"/>",
+                    fret_y(*fret)
+                );
+            }
+        }
+    }
+    out.push_str("</svg>");
+    out
+}
+#[cfg(test)]
+mod test_render_chord_diagram_svg {
+    use super::{test_chord_shape::beat, *};
+
+    #[test]
+    fn open_chord() {
+        let shape = ChordShape::from_beat(
+            &beat(&[(5, 3), (4, 2), (3, 0), (2, 1), (1, 0)]),
+            &Guitar::default(),
+        )
+        .unwrap();
+        let svg = render_chord_diagram_svg(&shape);
+        assert!(
+            svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\" class=\"chord-diagram\"")
+        );
+        assert_eq!(svg.matches("<line class=\"nut\"").count(), 1);
+        assert_eq!(svg.matches("<line class=\"fret\"").count(), 4);
+        assert_eq!(svg.matches("<line class=\"string\"").count(), 6);
+        assert_eq!(svg.matches("<circle class=\"finger\"").count(), 3);
+        assert_eq!(svg.matches("<circle class=\"open\"").count(), 2);
+        assert_eq!(svg.matches(">x</text>").count(), 1);
+        assert!(!svg.contains("class=\"barre\""));
+        assert!(!svg.contains("fr</text>"));
+    }
+    #[test]
+    fn barre_chord_up_the_neck() {
+        // A minor shape at the fifth fret: 577555
+        let shape = ChordShape::from_beat(
+            &beat(&[(6, 5), (5, 7), (4, 7), (3, 5), (2, 5), (1, 5)]),
+            &Guitar::default(),
+        )
+        .unwrap();
+        let svg = render_chord_diagram_svg(&shape);
+        assert_eq!(svg.matches("<rect class=\"barre\"").count(), 1);
+        assert_eq!(svg.matches("<line class=\"nut\"").count(), 0);
+        assert!(svg.contains(">5fr</text>"));
+        // The barre spans every string: from the leftmost dot edge to the rightmost.
+        assert!(svg.contains("<rect class=\"barre\" x=\"13\" y=\"29\" width=\"114\""));
+    }
+}
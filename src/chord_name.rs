@@ -0,0 +1,172 @@
+use crate::pitch::Pitch;
+
+/// Fewest pitches a beat needs before it is named as a chord.
+const MIN_CHORD_PITCHES: usize = 3;
+
+/// Root names by pitch class, starting at C. Accidentals take the spelling guitarists
+/// usually read in chord symbols.
+pub(crate) const ROOT_NAMES: [&str; 12] = [
+    "C", "C#", "D", "Eb", "E", "F", "F#", "G", "Ab", "A", "Bb", "B",
+];
+
+/// Chord qualities as pitch-class sets above the root, with the suffix each is written with.
+/// Earlier entries win when one set of pitches spells several chords (`C6` and `Am7`) and
+/// the bass does not decide between them.
+pub(crate) const CHORD_QUALITIES: [(&[u8], &str); 27] = [
+    (&[0, 4, 7], ""),
+    (&[0, 3, 7], "m"),
+    (&[0, 4, 7, 10], "7"),
+    (&[0, 4, 7, 11], "maj7"),
+    (&[0, 3, 7, 10], "m7"),
+    (&[0, 5, 7], "sus4"),
+    (&[0, 2, 7], "sus2"),
+    (&[0, 7], "5"),
+    (&[0, 3, 6], "dim"),
+    (&[0, 4, 8], "aug"),
+    (&[0, 3, 6, 10], "m7b5"),
+    (&[0, 3, 6, 9], "dim7"),
+    (&[0, 3, 7, 11], "mMaj7"),
+    (&[0, 4, 7, 9], "6"),
+    (&[0, 3, 7, 9], "m6"),
+    (&[0, 5, 7, 10], "7sus4"),
+    (&[0, 2, 4, 7], "add9"),
+    (&[0, 2, 3, 7], "madd9"),
+    (&[0, 2, 4, 7, 10], "9"),
+    (&[0, 2, 4, 7, 11], "maj9"),
+    (&[0, 2, 3, 7, 10], "m9"),
+    (&[0, 4, 8, 10], "7#5"),
+    (&[0, 4, 7, 10, 1], "7b9"),
+    // Sevenths often drop the fifth on guitar.
+    (&[0, 4, 10], "7"),
+    (&[0, 4, 11], "maj7"),
+    (&[0, 3, 10], "m7"),
+    (&[0, 2, 4, 10], "9"),
+];
+
+/// Names the chord `pitches` spell, such as `Am7`, `G/B` or `Dsus4`. Returns `None` for
+/// fewer than three pitches or a set no known chord spells.
+///
+/// Any pitch can be the root. A root matching the lowest pitch wins, so an inversion
+/// names its root with the bass after a slash (`C/E`). When the full set spells nothing but
+/// the pitches above the bass do, the bass is a slash bass outside the chord (`C/F#`).
+#[must_use]
+pub fn name_chord(pitches: &[Pitch]) -> Option<String> {
+    if pitches.len() < MIN_CHORD_PITCHES {
+        return None;
+    }
+    let bass = pitches.iter().min()?.index() % 12;
+    let pitch_classes = pitch_class_set(pitches.iter().map(|pitch| pitch.index() % 12));
+
+    let (root, suffix) = match name_pitch_classes(pitch_classes, bass) {
+        Some(name) => name,
+        None => {
+            let upper = pitch_class_set(
+                pitches
+                    .iter()
+                    .map(|pitch| pitch.index() % 12)
+                    .filter(|&pitch_class| pitch_class != bass),
+            );
+            name_pitch_classes(upper, bass)?
+        }
+    };
+    let root_name = ROOT_NAMES[root as usize];
+    if root == bass {
+        Some(format!("{root_name}{suffix}"))
+    } else {
+        Some(format!("{root_name}{suffix}/{}", ROOT_NAMES[bass as usize]))
+    }
+}
+#[cfg(test)]
+mod test_name_chord {
+    use super::*;
+    use std::str::FromStr;
+
+    fn name(pitches: &str) -> Option<String> {
+        let pitches = pitches
+            .split_whitespace()
+            .map(|pitch| Pitch::from_str(pitch).unwrap())
+            .collect::<Vec<_>>();
+        name_chord(&pitches)
+    }
+
+    #[test]
+    fn triads() {
+        assert_eq!(name("C3 E3 G3 C4 E4").as_deref(), Some("C"));
+        assert_eq!(name("A2 E3 A3 C4 E4").as_deref(), Some("Am"));
+        assert_eq!(name("D3 A3 D4 G4").as_deref(), Some("Dsus4"));
+        assert_eq!(name("B2 F3 D4").as_deref(), Some("Bdim"));
+        assert_eq!(name("E2 B2 E3").as_deref(), Some("E5"));
+    }
+    #[test]
+    fn sevenths() {
+        assert_eq!(name("A2 E3 G3 C4 E4").as_deref(), Some("Am7"));
+        assert_eq!(name("C3 E3 G3 B3").as_deref(), Some("Cmaj7"));
+        assert_eq!(name("G2 B2 D3 F3").as_deref(), Some("G7"));
+        // The fifth is left out.
+        assert_eq!(name("E2 D3 G#3").as_deref(), Some("E7"));
+    }
+    #[test]
+    fn inversions_name_the_bass() {
+        assert_eq!(name("B2 D3 G3 B3 G4").as_deref(), Some("G/B"));
+        assert_eq!(name("E2 G2 C3 E3").as_deref(), Some("C/E"));
+    }
+    #[test]
+    fn bass_decides_between_spellings() {
+        assert_eq!(name("C3 E3 G3 A3").as_deref(), Some("C6"));
+        assert_eq!(name("A2 C3 E3 G3").as_deref(), Some("Am7"));
+    }
+    #[test]
+    fn slash_bass_outside_the_chord() {
+        assert_eq!(name("F#2 C3 E3 G3").as_deref(), Some("C/F#"));
+        // A seventh in the bass is an inversion, not a slash bass.
+        assert_eq!(name("C3 D3 F#3 A3").as_deref(), Some("D7/C"));
+    }
+    #[test]
+    fn too_few_pitches() {
+        assert_eq!(name("C3 E3"), None);
+        assert_eq!(name(""), None);
+    }
+    #[test]
+    fn unnamed_cluster() {
+        assert_eq!(name("C3 C#3 D3"), None);
+    }
+}
+
+/// Bit set of pitch classes, bit `n` for pitch class `n`.
+pub(crate) fn pitch_class_set(pitch_classes: impl Iterator<Item = u8>) -> u16 {
+    pitch_classes.fold(0, |set, pitch_class| set | 1 << pitch_class)
+}
+
+/// The root and suffix of the chord that `pitch_classes` spell, preferring `bass` as the
+/// root, then the earlier chord quality.
+fn name_pitch_classes(pitch_classes: u16, bass: u8) -> Option<(u8, &'static str)> {
+    let roots = (0..12).filter(|root| pitch_classes & 1 << root != 0);
+    roots
+        .filter_map(|root| {
+            let quality = CHORD_QUALITIES.iter().position(|(intervals, _)| {
+                pitch_class_set(intervals.iter().map(|interval| (root + interval) % 12))
+                    == pitch_classes
+            })?;
+            Some((root != bass, quality, root))
+        })
+        .min()
+        .map(|(_, quality, root)| (root, CHORD_QUALITIES[quality].1))
+}
+#[cfg(test)]
+mod test_name_pitch_classes {
+    use super::*;
+
+    #[test]
+    fn prefers_the_bass_as_root() {
+        // C E G A, with A in the bass.
+        let set = pitch_class_set([0, 4, 7, 9].into_iter());
+        assert_eq!(name_pitch_classes(set, 9), Some((9, "m7")));
+        assert_eq!(name_pitch_classes(set, 0), Some((0, "6")));
+    }
+    #[test]
+    fn falls_back_to_quality_order() {
+        // C E G A, with E in the bass: Am7 is listed before C6.
+        let set = pitch_class_set([0, 4, 7, 9].into_iter());
+        assert_eq!(name_pitch_classes(set, 4), Some((9, "m7")));
+    }
+}
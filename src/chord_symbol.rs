@@ -0,0 +1,343 @@
+use crate::{
+    arrangement::BeatVec,
+    chord_name::{CHORD_QUALITIES, ROOT_NAMES, pitch_class_set},
+    guitar::{Guitar, PitchFingering},
+    pitch::Pitch,
+};
+use itertools::Itertools;
+use std::fmt;
+
+/// Other ways of writing a chord suffix, with the suffix each stands for.
+const SUFFIX_ALIASES: [(&str, &str); 8] = [
+    ("maj", ""),
+    ("M", ""),
+    ("min", "m"),
+    ("-", "m"),
+    ("M7", "maj7"),
+    ("min7", "m7"),
+    ("sus", "sus4"),
+    ("+", "aug"),
+];
+
+/// Widest stretch, in frets, from a voicing's lowest fretted note to its highest.
+const MAX_VOICING_SPAN: u8 = 3;
+
+/// Fewest strings a voicing sounds. Every voicing strums from string 1 down to its bass.
+const MIN_VOICING_STRINGS: usize = 4;
+
+/// Most fretted notes a voicing holds above its lowest fret, which the index finger barres.
+const MAX_FINGERS_ABOVE_BARRE: usize = 3;
+
+/// Highest fret a voicing with open strings reaches. Open strings ring against a hand near the
+/// nut, not one up the neck.
+const MAX_OPEN_POSITION_FRET: u8 = 5;
+
+/// A chord symbol such as `Am`, `F#m7b5` or `Cmaj7/E`, written in place of a pitch list.
+///
+/// The arranger expands a chord symbol to every voicing the guitar can strum and keeps the
+/// easiest, so the input fixes neither the octave nor the strings.
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+pub struct ChordSymbol {
+    /// Pitch class of the root, 0 for C.
+    root: u8,
+    /// Index into `CHORD_QUALITIES`.
+    quality: usize,
+    /// Pitch class of the lowest note, the root unless a slash names another.
+    bass: u8,
+}
+impl ChordSymbol {
+    /// Parses a chord symbol: a root from `A` to `G` with an optional `#` or `b`, a suffix such
+    /// as `m`, `7`, `maj7` or `sus4`, and an optional slash bass (`/E`). Returns `None` for
+    /// anything else, so `Verse` or `Chorus` is not a chord.
+    #[must_use]
+    pub fn from_symbol(symbol: &str) -> Option<Self> {
+        let (chord, bass) = match symbol.split_once('/') {
+            Some((chord, bass)) => (chord, Some(bass)),
+            None => (symbol, None),
+        };
+        let (root, suffix) = parse_root(chord)?;
+        let suffix = SUFFIX_ALIASES
+            .iter()
+            .find(|(alias, _)| *alias == suffix)
+            .map_or(suffix, |(_, suffix)| suffix);
+        let quality = CHORD_QUALITIES
+            .iter()
+            .position(|(_, quality_suffix)| *quality_suffix == suffix)?;
+        let bass = match bass {
+            Some(bass) => match parse_root(bass)? {
+                (bass, "") => bass,
+                _ => return None,
+            },
+            None => root,
+        };
+        Some(ChordSymbol {
+            root,
+            quality,
+            bass,
+        })
+    }
+
+    /// Pitch classes of the chord tones, root first.
+    fn tones(self) -> impl Iterator<Item = u8> {
+        CHORD_QUALITIES[self.quality]
+            .0
+            .iter()
+            .map(move |interval| (self.root + interval) % 12)
+    }
+
+    /// The chord in close position above the lowest bass note from `E2`. The parser stores
+    /// these pitches so a caller that skips chord expansion still gets a playable beat.
+    pub(crate) fn default_pitches(self) -> BeatVec<Pitch> {
+        let bass = Pitch::E2
+            .plus_offset(i16::from((self.bass + 8) % 12))
+            .expect("BUG: E2 plus less than an octave is a valid pitch");
+        let offsets = self
+            .tones()
+            .map(|tone| (tone + 12 - self.bass) % 12)
+            .filter(|&offset| offset != 0)
+            .sorted()
+            .dedup();
+        std::iter::once(bass)
+            .chain(offsets.map(|offset| {
+                bass.plus_offset(i16::from(offset))
+                    .expect("BUG: less than an octave above the bass is a valid pitch")
+            }))
+            .collect()
+    }
+
+    /// Every voicing of the chord that `guitar` can strum, lowest position first.
+    ///
+    /// A voicing sounds string 1 down to the string holding the bass, at least four strings,
+    /// with every chord tone present. Chords of four or more tones may leave out the fifth.
+    /// Fretted notes stay within a four-fret hand position, and at most three sit above the
+    /// lowest fret, which the index finger can barre. Open strings only join a voicing held
+    /// within the first five frets.
+    pub(crate) fn voicings(self, guitar: &Guitar) -> Vec<BeatVec<PitchFingering>> {
+        let tones = pitch_class_set(self.tones());
+        let allowed = tones | 1 << self.bass;
+        let required = if CHORD_QUALITIES[self.quality].0.len() >= 4 {
+            tones & !(1 << ((self.root + 7) % 12))
+        } else {
+            tones
+        };
+
+        let mut voicings = Vec::new();
+        for lowest_fret in 1..=guitar.playable_frets.max(1) {
+            let position = lowest_fret..=lowest_fret.saturating_add(MAX_VOICING_SPAN);
+            let options_by_string = guitar
+                .string_ranges
+                .iter()
+                .map(|(&string_number, range)| {
+                    range
+                        .iter()
+                        .enumerate()
+                        .map(|(fret, &pitch)| (fret as u8, pitch))
+                        .filter(|&(fret, pitch)| {
+                            (fret == 0 || position.contains(&fret))
+                                && allowed & 1 << (pitch.index() % 12) != 0
+                        })
+                        .map(|(fret, pitch)| PitchFingering {
+                            pitch,
+                            string_number,
+                            fret,
+                            harmonic: None,
+                            voice: None,
+                        })
+                        .collect_vec()
+                })
+                .collect_vec();
+            for num_strings in MIN_VOICING_STRINGS..=options_by_string.len() {
+                voicings.extend(
+                    options_by_string[..num_strings]
+                        .iter()
+                        .multi_cartesian_product()
+                        .map(|shape| shape.into_iter().copied().collect_vec())
+                        .filter(|shape| self.is_voicing(shape, required, lowest_fret)),
+                );
+            }
+        }
+        voicings.sort();
+        voicings.dedup();
+        voicings
+    }
+
+    /// Returns true when `shape` sounds the `required` tones over the bass and can be held
+    /// with its lowest fretted note at `lowest_fret`.
+    fn is_voicing(self, shape: &[PitchFingering], required: u16, lowest_fret: u8) -> bool {
+        let Some(lowest_pitch) = shape.iter().map(|fingering| fingering.pitch).min() else {
+            return false;
+        };
+        let sounded = pitch_class_set(shape.iter().map(|fingering| fingering.pitch.index() % 12));
+        let fretted = shape
+            .iter()
+            .map(|fingering| fingering.fret)
+            .filter(|&fret| fret > 0);
+        let min_fret = fretted.clone().min().unwrap_or(1);
+        let max_fret = fretted.clone().max().unwrap_or(0);
+        let has_open_string = shape.iter().any(|fingering| fingering.fret == 0);
+        lowest_pitch.index() % 12 == self.bass
+            && required & !sounded == 0
+            && min_fret == lowest_fret
+            && (!has_open_string || max_fret <= MAX_OPEN_POSITION_FRET)
+            && fretted.filter(|&fret| fret > lowest_fret).count() <= MAX_FINGERS_ABOVE_BARRE
+    }
+}
+#[cfg(test)]
+mod test_chord_symbol {
+    use super::*;
+
+    fn symbol(text: &str) -> ChordSymbol {
+        ChordSymbol::from_symbol(text).unwrap()
+    }
+
+    /// The voicing as frets from string 6 to string 1, with `x` for a muted string.
+    fn frets(voicing: &[PitchFingering]) -> String {
+        (1..=6)
+            .rev()
+            .map(|string_number| {
+                voicing
+                    .iter()
+                    .find(|fingering| fingering.string_number.get() == string_number)
+                    .map_or("x".to_owned(), |fingering| fingering.fret.to_string())
+            })
+            .collect()
+    }
+
+    #[test]
+    fn parses_symbols() {
+        assert_eq!(symbol("Am").to_string(), "Am");
+        assert_eq!(symbol("F#m7b5").to_string(), "F#m7b5");
+        assert_eq!(symbol("Cmaj7/E").to_string(), "Cmaj7/E");
+        assert_eq!(symbol("Bb").to_string(), "Bb");
+        // Aliases and sharps are written the usual way back.
+        assert_eq!(symbol("Amin").to_string(), "Am");
+        assert_eq!(symbol("A#M7").to_string(), "Bbmaj7");
+        assert_eq!(symbol("Dsus").to_string(), "Dsus4");
+    }
+    #[test]
+    fn rejects_other_text() {
+        for text in [
+            "", "Verse", "Chorus", "H", "am", "Cx", "C/", "C/Em", "Bridge 2", "1",
+        ] {
+            assert_eq!(ChordSymbol::from_symbol(text), None, "{text}");
+        }
+    }
+    #[test]
+    fn default_pitches_stack_above_the_bass() {
+        assert_eq!(
+            symbol("Am").default_pitches(),
+            vec![Pitch::A2, Pitch::C3, Pitch::E3]
+        );
+        assert_eq!(
+            symbol("C/E").default_pitches(),
+            vec![Pitch::E2, Pitch::G2, Pitch::C3]
+        );
+        assert_eq!(
+            symbol("D7").default_pitches(),
+            vec![Pitch::D3, Pitch::FSharpGFlat3, Pitch::A3, Pitch::C4]
+        );
+    }
+    #[test]
+    fn voicings_include_the_open_shapes() {
+        let guitar = Guitar::default();
+        let shapes = |text: &str| {
+            symbol(text)
+                .voicings(&guitar)
+                .iter()
+                .map(|voicing| frets(voicing))
+                .collect_vec()
+        };
+        assert!(shapes("Am").contains(&"x02210".to_owned()));
+        assert!(shapes("Am").contains(&"577555".to_owned()));
+        assert!(shapes("C").contains(&"x32010".to_owned()));
+        assert!(shapes("G").contains(&"320003".to_owned()));
+        assert!(shapes("D").contains(&"xx0232".to_owned()));
+        assert!(shapes("Cmaj7/E").contains(&"032000".to_owned()));
+        // The bass is always the lowest note.
+        assert!(!shapes("Am").contains(&"002210".to_owned()));
+        // Open strings stay near the nut.
+        // E2 under frets 10, 10 on strings 5 and 4.
+        assert!(!shapes("Cmaj7/E").contains(&"01010000".to_owned()));
+    }
+    #[test]
+    fn voicings_follow_the_capo() {
+        let tuning =
+            crate::guitar::create_string_tuning(&crate::guitar::STD_6_STRING_TUNING_OPEN_PITCHES)
+                .unwrap();
+        let guitar = Guitar::new(tuning, 18, 2).unwrap();
+        let shapes = symbol("Bm")
+            .voicings(&guitar)
+            .iter()
+            .map(|voicing| frets(voicing))
+            .collect_vec();
+        // The Am shape, two frets up.
+        assert!(shapes.contains(&"x02210".to_owned()));
+    }
+    #[test]
+    fn no_voicings_on_a_short_neck() {
+        let tuning =
+            crate::guitar::create_string_tuning(&crate::guitar::STD_6_STRING_TUNING_OPEN_PITCHES)
+                .unwrap();
+        let guitar = Guitar::new(tuning, 0, 0).unwrap();
+        assert!(symbol("F#m").voicings(&guitar).is_empty());
+    }
+}
+
+impl fmt::Display for ChordSymbol {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}{}",
+            ROOT_NAMES[self.root as usize], CHORD_QUALITIES[self.quality].1
+        )?;
+        if self.bass != self.root {
+            write!(f, "/{}", ROOT_NAMES[self.bass as usize])?;
+        }
+        Ok(())
+    }
+}
+
+/// Splits a chord's root note off the front of `text`, returning its pitch class and the
+/// rest of the text.
+fn parse_root(text: &str) -> Option<(u8, &str)> {
+    let mut chars = text.chars();
+    let letter: u8 = match chars.next()? {
+        'C' => 0,
+        'D' => 2,
+        'E' => 4,
+        'F' => 5,
+        'G' => 7,
+        'A' => 9,
+        'B' => 11,
+        _ => return None,
+    };
+    let rest = chars.as_str();
+    match rest.chars().next() {
+        Some(accidental @ ('#' | '♯')) => {
+            Some(((letter + 1) % 12, &rest[accidental.len_utf8()..]))
+        }
+        Some(accidental @ ('b' | '♭')) => {
+            Some(((letter + 11) % 12, &rest[accidental.len_utf8()..]))
+        }
+        _ => Some((letter, rest)),
+    }
+}
+#[cfg(test)]
+mod test_parse_root {
+    use super::*;
+
+    #[test]
+    fn naturals_and_accidentals() {
+        assert_eq!(parse_root("Am7"), Some((9, "m7")));
+        assert_eq!(parse_root("F#"), Some((6, "")));
+        assert_eq!(parse_root("Bbmaj7"), Some((10, "maj7")));
+        assert_eq!(parse_root("C♯m"), Some((1, "m")));
+        assert_eq!(parse_root("Cb"), Some((11, "")));
+    }
+    #[test]
+    fn rejects_other_letters() {
+        assert_eq!(parse_root(""), None);
+        assert_eq!(parse_root("H"), None);
+        assert_eq!(parse_root("am"), None);
+    }
+}
@@ -0,0 +1,817 @@
+//! The `guitar-tab` command line, built with the `cli` feature.
+//!
+//! Reads pitch input from a file or stdin, arranges it with [`generate_arrangements`] and
+//! writes the rendered tabs, or JSON with `--json`, to stdout. The flags mirror [`TabInput`]
+//! field for field, plus `--width` and `--padding` for the render. The `batch` subcommand
+//! arranges many files with the same flags through [`arrange_batch`] and prints a summary.
+//! [`run`] is the whole program, so tests drive it without spawning a process.
+
+use crate::{
+    ArrangementLine, ArrangementSet, BatchInput, BatchSummaryRow, HarmonicMode, NormalizedBeat,
+    ParseError, TabError, TabInput, arrange_batch, generate_arrangements,
+};
+use serde::Serialize;
+use std::io::{Read, Write};
+
+/// The `--help` text.
+pub const USAGE: &str = "\
+Usage: guitar-tab [OPTIONS] [FILE]
+       guitar-tab batch [OPTIONS] [--out <DIR>] <FILE|DIR>...
+
+Arranges the pitches in FILE, or stdin when FILE is missing or `-`, and prints the tabs.
+
+`batch` arranges every FILE, and every file directly inside each DIR, with the same options.
+It writes each tab to DIR given by `--out`, named after the input with a .txt or .json
+extension, and prints a summary of the difficulty, max fret span and failure of each input.
+Two inputs that would write the same tab, such as a/song.txt and b/song.txt, are refused
+before anything is written.
+
+Options:
+  --tuning <NAME>            Tuning preset, such as standard, dropD or openG [default: standard]
+  --frets <N>                Number of frets on the guitar [default: 18]
+  --capo <FRET>              Fret the capo sits at, 0 for none [default: 0]
+  --arrangements <N>         Number of arrangements, easiest first [default: 1]
+  --max-span <FRETS>         Drop arrangements with a wider fret span on any beat
+  --weights <M,S,P>          Movement, span and position difficulty weights
+  --lenient                  Drop unparseable text instead of failing
+  --expand-repeats           Arrange repeats and endings in playing order
+  --harmonics <MODE>         off, natural or naturalAndArtificial [default: off]
+  --width <COLUMNS>          Width of the rendered tab [default: 80]
+  --padding <N>              Dashes on each side of a fret number [default: 2]
+  --json                     Print JSON instead of rendered tabs
+  --out <DIR>                Directory for the tabs of `batch`
+  -h, --help                 Print this help
+";
+
+/// Exit code for a command line that could not be parsed.
+pub const EXIT_USAGE: u8 = 2;
+/// Exit code for input that could not be read or output that could not be written.
+pub const EXIT_IO: u8 = 3;
+/// Exit code for a `batch` run in which at least one input failed to arrange.
+pub const EXIT_BATCH_FAILED: u8 = 4;
+
+/// The exit code for `error`. Each variant has its own code, from 10 up, so a script can
+/// tell the failures apart without reading stderr.
+#[must_use]
+pub fn exit_code(error: &TabError) -> u8 {
+    match error {
+        TabError::Parse { .. } => 10,
+        TabError::InputTooManyLines { .. } => 11,
+        TabError::NumFretsTooHigh { .. } => 12,
+        TabError::CapoTooHigh { .. } => 13,
+        TabError::CapoExceedsFrets { .. } => 14,
+        TabError::StringNumberOutOfRange { .. } => 15,
+        TabError::OpenPitchOutOfRange { .. } => 16,
+        TabError::FretRangeExceedsPitchRange { .. } => 17,
+        TabError::UnplayablePitches { .. } => 18,
+        TabError::NoArrangementsFound => 19,
+        TabError::NumArrangementsOutOfRange { .. } => 20,
+        TabError::TuningNameUnknown { .. } => 21,
+        TabError::IndexOutOfBounds { .. } => 22,
+        TabError::RenderWidthTooSmall { .. } => 23,
+        TabError::DifficultyWeightOutOfRange { .. } => 24,
+        TabError::UnplayableChord { .. } => 25,
+        TabError::SnapshotInvalid { .. } => 26,
+        TabError::SnapshotVersionUnsupported { .. } => 27,
+    }
+}
+#[cfg(test)]
+mod test_exit_code {
+    use super::*;
+
+    #[test]
+    fn codes_are_distinct_and_clear_of_the_fixed_ones() {
+        let errors = [
+            TabError::Parse { errors: vec![] },
+            TabError::NoArrangementsFound,
+            TabError::TuningNameUnknown {
+                value: String::new(),
+            },
+            TabError::SnapshotVersionUnsupported {
+                version: 2,
+                supported: 1,
+            },
+        ];
+        let codes: Vec<u8> = errors.iter().map(exit_code).collect();
+        assert_eq!(codes, [10, 19, 21, 27]);
+        assert!(codes.iter().all(|&code| code > EXIT_BATCH_FAILED));
+    }
+}
+
+/// Whether to print rendered tabs or JSON.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Format {
+    Text,
+    Json,
+}
+
+/// A parsed command line. `tab_input.input` stays empty until the input is read.
+#[derive(Debug)]
+struct Invocation {
+    path: Option<String>,
+    tab_input: TabInput,
+    width: u16,
+    padding: u8,
+    format: Format,
+    batch: Option<BatchArgs>,
+}
+
+/// The arguments only the `batch` subcommand takes.
+#[derive(Debug, Default, PartialEq, Eq)]
+struct BatchArgs {
+    paths: Vec<String>,
+    out: Option<String>,
+}
+
+/// Parses the arguments after the program name. `Ok(None)` asks for the help text.
+fn parse_args(args: &[String]) -> Result<Option<Invocation>, String> {
+    let mut invocation = Invocation {
+        path: None,
+        tab_input: TabInput::new("", "standard", 18, 0, 1),
+        width: 80,
+        padding: 2,
+        format: Format::Text,
+        batch: None,
+    };
+    let mut args = args.iter().peekable();
+    if args.next_if(|arg| *arg == "batch").is_some() {
+        invocation.batch = Some(BatchArgs::default());
+    }
+    while let Some(arg) = args.next() {
+        let (flag, inline_value) = match arg.split_once('=') {
+            Some((flag, value)) if flag.starts_with("--") => (flag, Some(value.to_owned())),
+            _ => (arg.as_str(), None),
+        };
+        let mut value = || {
+            inline_value
+                .clone()
+                .or_else(|| args.next().cloned())
+                .ok_or_else(|| format!("{flag} needs a value"))
+        };
+        let tab_input = &mut invocation.tab_input;
+        match flag {
+            "-h" | "--help" => return Ok(None),
+            "--tuning" => tab_input.tuning_name = value()?,
+            "--frets" => tab_input.guitar_num_frets = parse_number(flag, &value()?)?,
+            "--capo" => tab_input.guitar_capo = parse_number(flag, &value()?)?,
+            "--arrangements" => tab_input.num_arrangements = parse_number(flag, &value()?)?,
+            "--max-span" => tab_input.max_fret_span_filter = Some(parse_number(flag, &value()?)?),
+            "--weights" => {
+                let value = value()?;
+                let weights: Vec<f64> = value
+                    .split(',')
+                    .map(|weight| parse_number(flag, weight.trim()))
+                    .collect::<Result<_, _>>()?;
+                let [movement, span, position] = weights[..] else {
+                    return Err(format!(
+                        "{flag} takes three comma-separated weights, not {value:?}"
+                    ));
+                };
+                tab_input.difficulty_weights = Some(crate::DifficultyWeightsInput {
+                    movement,
+                    span,
+                    position,
+                });
+            }
+            "--lenient" => tab_input.lenient_parse = true,
+            "--expand-repeats" => tab_input.expand_repeats = true,
+            "--harmonics" => {
+                tab_input.harmonic_mode = match value()?.as_str() {
+                    "off" => HarmonicMode::Off,
+                    "natural" => HarmonicMode::Natural,
+                    "naturalAndArtificial" => HarmonicMode::NaturalAndArtificial,
+                    other => return Err(format!("{flag} does not accept {other:?}")),
+                }
+            }
+            "--width" => invocation.width = parse_number(flag, &value()?)?,
+            "--padding" => invocation.padding = parse_number(flag, &value()?)?,
+            "--json" => invocation.format = Format::Json,
+            "--out" if invocation.batch.is_some() => {
+                let out = value()?;
+                if let Some(batch) = &mut invocation.batch {
+                    batch.out = Some(out);
+                }
+            }
+            "-" if invocation.batch.is_some() => {
+                return Err("batch reads files, not stdin".to_owned());
+            }
+            _ if flag.starts_with('-') && flag != "-" => {
+                return Err(format!("unknown option {flag}"));
+            }
+            _ if invocation.batch.is_some() => {
+                if let Some(batch) = &mut invocation.batch {
+                    batch.paths.push(arg.clone());
+                }
+            }
+            "-" => invocation.path = None,
+            _ if invocation.path.is_some() => {
+                return Err(format!(
+                    "unexpected argument {arg:?}, only one FILE is read"
+                ));
+            }
+            _ => invocation.path = Some(arg.clone()),
+        }
+    }
+    if invocation
+        .batch
+        .as_ref()
+        .is_some_and(|batch| batch.paths.is_empty())
+    {
+        return Err("batch needs at least one FILE or DIR".to_owned());
+    }
+    Ok(Some(invocation))
+}
+#[cfg(test)]
+mod test_parse_args {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Option<Invocation>, String> {
+        parse_args(&args.iter().map(|&arg| arg.to_owned()).collect::<Vec<_>>())
+    }
+
+    #[test]
+    fn defaults() {
+        let invocation = parse(&[]).unwrap().unwrap();
+        assert_eq!(invocation.path, None);
+        assert_eq!(invocation.tab_input.tuning_name, "standard");
+        assert_eq!(invocation.tab_input.guitar_num_frets, 18);
+        assert_eq!(invocation.tab_input.num_arrangements, 1);
+        assert_eq!((invocation.width, invocation.padding), (80, 2));
+        assert_eq!(invocation.format, Format::Text);
+    }
+    #[test]
+    fn every_flag() {
+        let invocation = parse(&[
+            "--tuning",
+            "dropD",
+            "--frets=20",
+            "--capo",
+            "2",
+            "--arrangements",
+            "3",
+            "--max-span",
+            "4",
+            "--weights",
+            "1, 2.5,0",
+            "--lenient",
+            "--expand-repeats",
+            "--harmonics",
+            "natural",
+            "--width",
+            "60",
+            "--padding=1",
+            "--json",
+            "song.txt",
+        ])
+        .unwrap()
+        .unwrap();
+        let tab_input = &invocation.tab_input;
+        assert_eq!(invocation.path.as_deref(), Some("song.txt"));
+        assert_eq!(tab_input.tuning_name, "dropD");
+        assert_eq!((tab_input.guitar_num_frets, tab_input.guitar_capo), (20, 2));
+        assert_eq!(tab_input.num_arrangements, 3);
+        assert_eq!(tab_input.max_fret_span_filter, Some(4));
+        let weights = tab_input.difficulty_weights.unwrap();
+        assert_eq!(
+            (weights.movement, weights.span, weights.position),
+            (1.0, 2.5, 0.0)
+        );
+        assert!(tab_input.lenient_parse && tab_input.expand_repeats);
+        assert_eq!(tab_input.harmonic_mode, HarmonicMode::Natural);
+        assert_eq!((invocation.width, invocation.padding), (60, 1));
+        assert_eq!(invocation.format, Format::Json);
+    }
+    #[test]
+    fn batch() {
+        let invocation = parse(&["batch", "--capo", "2", "a.txt", "--out=tabs", "songs"])
+            .unwrap()
+            .unwrap();
+        assert_eq!(invocation.tab_input.guitar_capo, 2);
+        assert_eq!(
+            invocation.batch,
+            Some(BatchArgs {
+                paths: vec!["a.txt".to_owned(), "songs".to_owned()],
+                out: Some("tabs".to_owned()),
+            })
+        );
+        assert_eq!(invocation.path, None);
+
+        assert_eq!(
+            parse(&["batch", "--json"]).unwrap_err(),
+            "batch needs at least one FILE or DIR"
+        );
+        assert_eq!(
+            parse(&["batch", "-"]).unwrap_err(),
+            "batch reads files, not stdin"
+        );
+        assert_eq!(
+            parse(&["--out", "tabs"]).unwrap_err(),
+            "unknown option --out"
+        );
+        // Only the first argument names the subcommand.
+        assert_eq!(parse(&["batch.txt"]).unwrap().unwrap().batch, None);
+    }
+    #[test]
+    fn help() {
+        assert!(parse(&["--width", "9", "-h"]).unwrap().is_none());
+    }
+    #[test]
+    fn rejects_bad_command_lines() {
+        assert_eq!(parse(&["--capo"]).unwrap_err(), "--capo needs a value");
+        assert_eq!(
+            parse(&["--capo", "x"]).unwrap_err(),
+            "--capo expects a number, not \"x\""
+        );
+        assert_eq!(
+            parse(&["--weights", "1,2"]).unwrap_err(),
+            "--weights takes three comma-separated weights, not \"1,2\""
+        );
+        assert_eq!(
+            parse(&["--harmonics", "all"]).unwrap_err(),
+            "--harmonics does not accept \"all\""
+        );
+        assert_eq!(parse(&["--bpm"]).unwrap_err(), "unknown option --bpm");
+        assert_eq!(
+            parse(&["a.txt", "b.txt"]).unwrap_err(),
+            "unexpected argument \"b.txt\", only one FILE is read"
+        );
+    }
+}
+
+fn parse_number<T: std::str::FromStr>(flag: &str, value: &str) -> Result<T, String> {
+    value
+        .parse()
+        .map_err(|_| format!("{flag} expects a number, not {value:?}"))
+}
+
+/// The `--json` output: the normalized input and, per arrangement, its scores, rendered tab
+/// and lines.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct JsonOutput {
+    normalized_input: Vec<NormalizedBeat>,
+    parse_warnings: Vec<ParseError>,
+    arrangements: Vec<JsonArrangement>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct JsonArrangement {
+    difficulty: f64,
+    max_fret_span: u8,
+    tab: String,
+    lines: Vec<ArrangementLine>,
+}
+
+/// Arranges and renders the input of `invocation`. Returns the stdout text and the
+/// lenient-parse warnings.
+fn render(invocation: &Invocation) -> Result<(String, Vec<ParseError>), TabError> {
+    let set = generate_arrangements(invocation.tab_input.clone())?;
+    Ok((render_set(invocation, &set)?, set.parse_warnings()))
+}
+
+/// The tabs, or JSON, that `invocation` prints for `set`.
+fn render_set(invocation: &Invocation, set: &ArrangementSet) -> Result<String, TabError> {
+    let (width, padding) = (invocation.width, invocation.padding);
+    let output = match invocation.format {
+        Format::Text => {
+            let mut output = String::new();
+            for index in 0..set.len() {
+                if set.len() > 1 {
+                    output.push_str(&format!(
+                        "Arrangement {} of {}, difficulty {}\n",
+                        index + 1,
+                        set.len(),
+                        set.difficulty(index)?
+                    ));
+                }
+                output.push_str(&set.render(index, width, padding, None)?);
+            }
+            output
+        }
+        Format::Json => {
+            let arrangements = (0..set.len())
+                .map(|index| {
+                    Ok(JsonArrangement {
+                        difficulty: set.difficulty(index)?,
+                        max_fret_span: set.max_fret_span(index)?,
+                        tab: set.render(index, width, padding, None)?,
+                        lines: set.lines(index)?,
+                    })
+                })
+                .collect::<Result<_, TabError>>()?;
+            let output = JsonOutput {
+                normalized_input: set.normalized_input(),
+                parse_warnings: set.parse_warnings(),
+                arrangements,
+            };
+            let mut json = serde_json::to_string_pretty(&output)
+                .expect("BUG: the CLI output always serializes to JSON");
+            json.push('\n');
+            json
+        }
+    };
+    Ok(output)
+}
+
+/// The FILE and DIR arguments of `batch` as named inputs, each DIR replaced by the files
+/// directly inside it in name order.
+fn read_batch_inputs(paths: &[String]) -> Result<Vec<BatchInput>, String> {
+    let mut files = Vec::new();
+    for path in paths {
+        let metadata = std::fs::metadata(path).map_err(|error| format!("{path}: {error}"))?;
+        if !metadata.is_dir() {
+            files.push(std::path::PathBuf::from(path));
+            continue;
+        }
+        let mut dir_files = std::fs::read_dir(path)
+            .and_then(|entries| {
+                entries
+                    .map(|entry| entry.map(|entry| entry.path()))
+                    .collect::<Result<Vec<_>, _>>()
+            })
+            .map_err(|error| format!("{path}: {error}"))?;
+        dir_files.retain(|file| file.is_file());
+        dir_files.sort();
+        files.extend(dir_files);
+    }
+    files
+        .into_iter()
+        .map(|file| {
+            let name = file.display().to_string();
+            std::fs::read_to_string(&file)
+                .map(|input| BatchInput::new(name.clone(), input))
+                .map_err(|error| format!("{name}: {error}"))
+        })
+        .collect()
+}
+
+/// The `batch` summary: one line per input, then the totals.
+fn render_summary(rows: &[BatchSummaryRow]) -> String {
+    let mut output = String::new();
+    for row in rows {
+        match (&row.error, row.difficulty, row.max_fret_span) {
+            (Some(error), _, _) => output.push_str(&format!("{}: error: {error}\n", row.name)),
+            (None, Some(difficulty), Some(max_fret_span)) => output.push_str(&format!(
+                "{}: difficulty {difficulty}, max fret span {max_fret_span}\n",
+                row.name
+            )),
+            (None, _, _) => output.push_str(&format!("{}: no arrangements\n", row.name)),
+        }
+    }
+    let num_failed = rows.iter().filter(|row| row.error.is_some()).count();
+    output.push_str(&format!(
+        "{} arranged, {num_failed} failed\n",
+        rows.len() - num_failed
+    ));
+    output
+}
+
+/// The file under `out` that `batch` writes the tab of each named input to: the input's file
+/// stem with `extension`. Fails when two inputs would write the same file.
+fn batch_output_paths(
+    out: &str,
+    names: &[&str],
+    extension: &str,
+) -> Result<Vec<std::path::PathBuf>, String> {
+    let mut paths: Vec<std::path::PathBuf> = Vec::with_capacity(names.len());
+    for name in names {
+        let stem = std::path::Path::new(name).file_stem().unwrap_or_default();
+        let path = std::path::Path::new(out)
+            .join(stem)
+            .with_extension(extension);
+        if let Some(earlier) = paths.iter().position(|earlier| *earlier == path) {
+            return Err(format!(
+                "{} and {name} would both write {}; rename one of them",
+                names[earlier],
+                path.display()
+            ));
+        }
+        paths.push(path);
+    }
+    Ok(paths)
+}
+#[cfg(test)]
+mod test_batch_output_paths {
+    use super::*;
+
+    #[test]
+    fn names_each_tab_after_its_input() {
+        assert_eq!(
+            batch_output_paths("tabs", &["songs/a.txt", "b.tab", "c"], "json").unwrap(),
+            [
+                std::path::Path::new("tabs/a.json"),
+                std::path::Path::new("tabs/b.json"),
+                std::path::Path::new("tabs/c.json"),
+            ]
+        );
+    }
+    #[test]
+    fn refuses_inputs_that_share_a_tab() {
+        let message = batch_output_paths("tabs", &["a/song.txt", "b/song.txt"], "txt").unwrap_err();
+        assert!(
+            message.starts_with("a/song.txt and b/song.txt would both write tabs"),
+            "{message}"
+        );
+        assert!(batch_output_paths("tabs", &["song.txt", "song.tab"], "txt").is_err());
+    }
+}
+
+/// Runs the `batch` subcommand. Returns the exit code, or a message and code to exit with.
+fn run_batch(
+    invocation: &Invocation,
+    batch: &BatchArgs,
+    stdout: &mut impl Write,
+    stderr: &mut impl Write,
+) -> Result<u8, (String, u8)> {
+    let inputs = read_batch_inputs(&batch.paths).map_err(|message| (message, EXIT_IO))?;
+    let output_paths = match &batch.out {
+        Some(out) => {
+            let extension = match invocation.format {
+                Format::Text => "txt",
+                Format::Json => "json",
+            };
+            let names: Vec<&str> = inputs.iter().map(|input| input.name.as_str()).collect();
+            batch_output_paths(out, &names, extension).map_err(|message| (message, EXIT_USAGE))?
+        }
+        None => Vec::new(),
+    };
+    let report = arrange_batch(&invocation.tab_input, inputs)
+        .map_err(|error| (error.to_string(), exit_code(&error)))?;
+
+    if let Some(out) = &batch.out {
+        std::fs::create_dir_all(out).map_err(|error| (format!("{out}: {error}"), EXIT_IO))?;
+    }
+    for (index, entry) in report.entries().iter().enumerate() {
+        let Ok(set) = &entry.result else { continue };
+        for warning in set.parse_warnings() {
+            let _ = writeln!(stderr, "warning: {}: {warning}", entry.name);
+        }
+        let Some(path) = output_paths.get(index) else {
+            continue;
+        };
+        let output = render_set(invocation, set)
+            .map_err(|error| (format!("{}: {error}", entry.name), exit_code(&error)))?;
+        std::fs::write(path, output)
+            .map_err(|error| (format!("{}: {error}", path.display()), EXIT_IO))?;
+    }
+
+    let summary = report.summary();
+    let output = match invocation.format {
+        Format::Text => render_summary(&summary),
+        Format::Json => {
+            let mut json = serde_json::to_string_pretty(&summary)
+                .expect("BUG: the batch summary always serializes to JSON");
+            json.push('\n');
+            json
+        }
+    };
+    stdout
+        .write_all(output.as_bytes())
+        .map_err(|error| (format!("stdout: {error}"), EXIT_IO))?;
+    Ok(if report.num_failed() > 0 {
+        EXIT_BATCH_FAILED
+    } else {
+        0
+    })
+}
+
+/// Runs `guitar-tab` with the arguments after the program name and returns its exit code.
+///
+/// Reads the input from the FILE argument, or from `stdin` without one. Tabs or JSON go to
+/// `stdout`, and errors, lenient-parse warnings and the help text to `stderr`. A
+/// [`TabError`] exits with its [`exit_code`].
+///
+/// `batch` prints its summary to `stdout` and exits with [`EXIT_BATCH_FAILED`] when any input
+/// failed. A shared option that no input could arrange with, such as an unknown tuning,
+/// exits with the [`exit_code`] of its error instead.
+pub fn run(
+    args: &[String],
+    mut stdin: impl Read,
+    stdout: &mut impl Write,
+    stderr: &mut impl Write,
+) -> u8 {
+    let mut invocation = match parse_args(args) {
+        Ok(Some(invocation)) => invocation,
+        Ok(None) => {
+            let _ = write!(stderr, "{USAGE}");
+            return 0;
+        }
+        Err(message) => {
+            let _ = write!(stderr, "error: {message}\n\n{USAGE}");
+            return EXIT_USAGE;
+        }
+    };
+    if let Some(batch) = &invocation.batch {
+        return match run_batch(&invocation, batch, stdout, stderr) {
+            Ok(code) => code,
+            Err((message, code)) => {
+                let _ = writeln!(stderr, "error: {message}");
+                code
+            }
+        };
+    }
+    let read = match &invocation.path {
+        Some(path) => std::fs::read_to_string(path).map_err(|error| format!("{path}: {error}")),
+        None => {
+            let mut input = String::new();
+            stdin
+                .read_to_string(&mut input)
+                .map(|_| input)
+                .map_err(|error| format!("stdin: {error}"))
+        }
+    };
+    invocation.tab_input.input = match read {
+        Ok(input) => input,
+        Err(message) => {
+            let _ = writeln!(stderr, "error: {message}");
+            return EXIT_IO;
+        }
+    };
+
+    match render(&invocation) {
+        Ok((output, warnings)) => {
+            for warning in warnings {
+                let _ = writeln!(stderr, "warning: {warning}");
+            }
+            match stdout.write_all(output.as_bytes()) {
+                Ok(()) => 0,
+                Err(_) => EXIT_IO,
+            }
+        }
+        Err(error) => {
+            let _ = writeln!(stderr, "error: {error}");
+            exit_code(&error)
+        }
+    }
+}
+#[cfg(test)]
+mod test_run {
+    use super::*;
+
+    fn run_with(args: &[&str], stdin: &str) -> (u8, String, String) {
+        let args: Vec<String> = args.iter().map(|&arg| arg.to_owned()).collect();
+        let (mut stdout, mut stderr) = (Vec::new(), Vec::new());
+        let code = run(&args, stdin.as_bytes(), &mut stdout, &mut stderr);
+        (
+            code,
+            String::from_utf8(stdout).unwrap(),
+            String::from_utf8(stderr).unwrap(),
+        )
+    }
+
+    #[test]
+    fn renders_stdin() {
+        let (code, stdout, stderr) = run_with(&["--width", "20"], "E2\nA2");
+        assert_eq!(code, 0, "{stderr}");
+        assert_eq!(
+            stdout,
+            "\n--------------------\n--------------------\n--------------------\n--------------------\n-----0--------------\n--0-----------------\n\n"
+        );
+    }
+    #[test]
+    fn heads_each_of_several_arrangements() {
+        let (code, stdout, _) = run_with(&["--arrangements", "2"], "E4");
+        assert_eq!(code, 0);
+        assert!(stdout.starts_with("Arrangement 1 of 2, difficulty "));
+        assert!(stdout.contains("\nArrangement 2 of 2, difficulty "));
+    }
+    #[test]
+    fn prints_json() {
+        let (code, stdout, _) = run_with(&["--json", "-"], "E2");
+        assert_eq!(code, 0);
+        let json: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+        assert_eq!(json["normalizedInput"][0]["pitches"][0], "E2");
+        let arrangement = &json["arrangements"][0];
+        assert_eq!(arrangement["lines"][0]["fingerings"][0]["string"], 6);
+        assert!(arrangement["tab"].as_str().unwrap().contains("0"));
+    }
+    #[test]
+    fn warns_on_lenient_input() {
+        let (code, _, stderr) = run_with(&["--lenient"], "E2 xyz");
+        assert_eq!(code, 0);
+        assert_eq!(
+            stderr,
+            "warning: Input 'xyz' on line 1 could not be parsed into a pitch.\n"
+        );
+    }
+    #[test]
+    fn exits_with_the_error_code() {
+        let (code, stdout, stderr) = run_with(&["--tuning", "openZ"], "E2");
+        assert_eq!(code, 21);
+        assert_eq!(stdout, "");
+        assert!(stderr.starts_with("error: The tuning name (\"openZ\")"));
+
+        assert_eq!(run_with(&[], "H2").0, 10);
+        assert_eq!(run_with(&["--width", "3"], "E2").0, 23);
+    }
+    #[test]
+    fn exits_on_usage_and_io_errors() {
+        let (code, _, stderr) = run_with(&["--frets"], "");
+        assert_eq!(code, EXIT_USAGE);
+        assert!(stderr.starts_with("error: --frets needs a value\n\nUsage: guitar-tab"));
+
+        let (code, _, stderr) = run_with(&["/nonexistent/song.txt"], "");
+        assert_eq!(code, EXIT_IO);
+        assert!(stderr.starts_with("error: /nonexistent/song.txt: "));
+
+        assert_eq!(run_with(&["--help"], "").0, 0);
+    }
+
+    /// A fresh directory under the temp dir holding `files`.
+    fn temp_dir_with(name: &str, files: &[(&str, &str)]) -> std::path::PathBuf {
+        let dir = std::env::temp_dir().join(format!("guitar-tab-{name}-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        for (file, input) in files {
+            std::fs::write(dir.join(file), input).unwrap();
+        }
+        dir
+    }
+
+    #[test]
+    fn batch_writes_tabs_and_summary() {
+        let songs = temp_dir_with("batch-songs", &[("a.txt", "E2\nA2"), ("b.txt", "E4")]);
+        let out = songs.with_file_name(format!("guitar-tab-batch-out-{}", std::process::id()));
+        let (code, stdout, stderr) = run_with(
+            &[
+                "batch",
+                "--width",
+                "20",
+                "--out",
+                out.to_str().unwrap(),
+                songs.to_str().unwrap(),
+            ],
+            "",
+        );
+        assert_eq!(code, 0, "{stderr}");
+        let a = songs.join("a.txt").display().to_string();
+        assert!(stdout.starts_with(&format!("{a}: difficulty ")));
+        assert!(stdout.ends_with(", max fret span 0\n2 arranged, 0 failed\n"));
+        assert_eq!(
+            std::fs::read_to_string(out.join("a.txt")).unwrap(),
+            run_with(&["--width", "20"], "E2\nA2").1
+        );
+        assert!(out.join("b.txt").is_file());
+        std::fs::remove_dir_all(songs).unwrap();
+        std::fs::remove_dir_all(out).unwrap();
+    }
+    #[test]
+    fn batch_refuses_inputs_that_share_a_tab() {
+        let first = temp_dir_with("batch-collision-a", &[("song.txt", "E2")]);
+        let second = temp_dir_with("batch-collision-b", &[("song.txt", "A2")]);
+        let out = first.with_file_name(format!(
+            "guitar-tab-batch-collision-out-{}",
+            std::process::id()
+        ));
+        let (code, stdout, stderr) = run_with(
+            &[
+                "batch",
+                "--out",
+                out.to_str().unwrap(),
+                first.to_str().unwrap(),
+                second.to_str().unwrap(),
+            ],
+            "",
+        );
+        assert_eq!(code, EXIT_USAGE);
+        assert_eq!(stdout, "");
+        assert!(stderr.contains("would both write"), "{stderr}");
+        assert!(!out.exists());
+        std::fs::remove_dir_all(first).unwrap();
+        std::fs::remove_dir_all(second).unwrap();
+    }
+    #[test]
+    fn batch_reports_failures() {
+        let songs = temp_dir_with(
+            "batch-failures",
+            &[("bad.txt", "H2"), ("good.txt", "E2"), ("low.txt", "C2")],
+        );
+        let files: Vec<String> = ["bad.txt", "good.txt", "low.txt"]
+            .iter()
+            .map(|file| songs.join(file).display().to_string())
+            .collect();
+        let mut args = vec!["batch", "--json"];
+        args.extend(files.iter().map(String::as_str));
+        let (code, stdout, _) = run_with(&args, "");
+        assert_eq!(code, EXIT_BATCH_FAILED);
+        let json: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+        assert_eq!(json[0]["error"]["kind"], "parse");
+        assert_eq!(json[1]["maxFretSpan"], 0);
+        assert_eq!(json[2]["unplayablePitches"][0]["value"], "C2");
+
+        let (code, stdout, _) = run_with(&["batch", &files[1], &files[2]], "");
+        assert_eq!(code, EXIT_BATCH_FAILED);
+        assert!(stdout.contains(&format!("{}: error: ", files[2])));
+        assert!(stdout.ends_with("1 arranged, 1 failed\n"));
+
+        // A shared option fails the run before any input.
+        let (code, stdout, _) = run_with(&["batch", "--tuning", "openZ", &files[1]], "");
+        assert_eq!((code, stdout.as_str()), (21, ""));
+        std::fs::remove_dir_all(songs).unwrap();
+
+        let (code, _, stderr) = run_with(&["batch", "/nonexistent/songs"], "");
+        assert_eq!(code, EXIT_IO);
+        assert!(stderr.starts_with("error: /nonexistent/songs: "));
+    }
+}
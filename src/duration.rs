@@ -0,0 +1,89 @@
+use std::fmt;
+
+/// The written length of a beat: a note value, optionally dotted.
+///
+/// Written after `@` at the end of a beat line, as in `E4 @q` or `@e.` on a rest. The note
+/// values are `w` (whole), `h` (half), `q` (quarter), `e` (eighth), `s` (sixteenth) and `t`
+/// (thirty-second). A trailing `.` dots the note, making it half again as long.
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+pub struct Duration {
+    /// 1 for a whole note, 2 for a half note, and so on down to 32.
+    denominator: u8,
+    dotted: bool,
+}
+impl Duration {
+    /// Parses a duration symbol such as `q`, `e.` or `h`. Letters are case-insensitive.
+    #[must_use]
+    pub fn from_symbol(symbol: &str) -> Option<Self> {
+        let (letter, dotted) = match symbol.strip_suffix('.') {
+            Some(letter) => (letter, true),
+            None => (symbol, false),
+        };
+        let denominator = match letter {
+            "w" | "W" => 1,
+            "h" | "H" => 2,
+            "q" | "Q" => 4,
+            "e" | "E" => 8,
+            "s" | "S" => 16,
+            "t" | "T" => 32,
+            _ => return None,
+        };
+        Some(Duration {
+            denominator,
+            dotted,
+        })
+    }
+
+    /// The length in sixty-fourth notes, the shortest unit a dotted thirty-second fills
+    /// exactly.
+    pub(crate) fn sixty_fourths(self) -> u32 {
+        let length = 64 / u32::from(self.denominator);
+        if self.dotted { length * 3 / 2 } else { length }
+    }
+}
+impl fmt::Display for Duration {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let letter = match self.denominator {
+            1 => 'w',
+            2 => 'h',
+            4 => 'q',
+            8 => 'e',
+            16 => 's',
+            _ => 't',
+        };
+        write!(f, "{letter}")?;
+        if self.dotted {
+            write!(f, ".")?;
+        }
+        Ok(())
+    }
+}
+#[cfg(test)]
+mod test_duration {
+    use super::*;
+
+    #[test]
+    fn symbols_round_trip() {
+        for symbol in ["w", "h", "q", "e", "s", "t", "w.", "q.", "t."] {
+            assert_eq!(Duration::from_symbol(symbol).unwrap().to_string(), symbol);
+        }
+        assert_eq!(Duration::from_symbol("Q"), Duration::from_symbol("q"));
+    }
+    #[test]
+    fn lengths() {
+        let length = |symbol| Duration::from_symbol(symbol).unwrap().sixty_fourths();
+        assert_eq!(length("w"), 64);
+        assert_eq!(length("q"), 16);
+        assert_eq!(length("q."), 24);
+        assert_eq!(length("t"), 2);
+        assert_eq!(length("t."), 3);
+    }
+    #[test]
+    fn rejects_other_text() {
+        assert_eq!(Duration::from_symbol(""), None);
+        assert_eq!(Duration::from_symbol("."), None);
+        assert_eq!(Duration::from_symbol("qq"), None);
+        assert_eq!(Duration::from_symbol("q.."), None);
+        assert_eq!(Duration::from_symbol("4"), None);
+    }
+}
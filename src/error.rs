@@ -8,12 +8,13 @@
 //! `Display` form is a developer-facing fallback only and doesn't justify an extra
 //! transitive dependency.
 
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "wasm")]
 use tsify::Tsify;
 
 /// One unparseable substring in the input, with its 1-indexed line number.
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Tsify)]
-#[tsify(into_wasm_abi)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "wasm", derive(Tsify), tsify(into_wasm_abi))]
 #[serde(rename_all = "camelCase")]
 pub struct ParseError {
     pub line: u32,
@@ -34,8 +35,8 @@ impl std::fmt::Display for ParseError {
 ///
 /// Public payload of [`TabError::UnplayablePitches`]. The structured `{ value, line }`
 /// record replaced the free-form prose string used before 2.0.0.
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Tsify)]
-#[tsify(into_wasm_abi)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "wasm", derive(Tsify), tsify(into_wasm_abi))]
 #[serde(rename_all = "camelCase")]
 pub struct UnplayablePitch {
     pub value: String,
@@ -57,8 +58,8 @@ impl std::fmt::Display for UnplayablePitch {
 /// Additional variants may be added in a non-breaking release. The `#[non_exhaustive]`
 /// attribute requires external matches to include a wildcard arm. JS consumers should keep a
 /// `default` arm in any `switch (err.kind)`.
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Tsify)]
-#[tsify(into_wasm_abi)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "wasm", derive(Tsify), tsify(into_wasm_abi))]
 #[serde(
     tag = "kind",
     rename_all = "camelCase",
@@ -128,6 +129,22 @@ pub enum TabError {
     DifficultyWeightOutOfRange {
         field: &'static str,
     },
+    /// The chord symbol `value` on the 1-indexed input `line` has no voicing the configured
+    /// guitar can strum, for example on a neck with too few frets.
+    UnplayableChord {
+        value: String,
+        line: u32,
+    },
+    /// An exported `ArrangementSet` could not be imported. `reason` says what was malformed.
+    SnapshotInvalid {
+        reason: String,
+    },
+    /// An exported `ArrangementSet` was written in snapshot format `version`, which this
+    /// build cannot read. `supported` is the version it reads and writes.
+    SnapshotVersionUnsupported {
+        version: u32,
+        supported: u32,
+    },
 }
 
 impl std::fmt::Display for TabError {
@@ -234,6 +251,21 @@ impl std::fmt::Display for TabError {
                     "The {field} difficulty weight must be a finite, non-negative number."
                 )
             }
+            TabError::UnplayableChord { value, line } => {
+                write!(
+                    f,
+                    "Chord {value} on line {line} has no voicing on the configured guitar."
+                )
+            }
+            TabError::SnapshotInvalid { reason } => {
+                write!(f, "The arrangement set could not be imported: {reason}")
+            }
+            TabError::SnapshotVersionUnsupported { version, supported } => {
+                write!(
+                    f,
+                    "The arrangement set was exported in format version {version}. This build reads version {supported}."
+                )
+            }
         }
     }
 }
@@ -461,4 +493,39 @@ mod test_new_variant_display {
             "The render width (3) is too small. The minimum is 4."
         );
     }
+
+    #[test]
+    fn unplayable_chord() {
+        let err = TabError::UnplayableChord {
+            value: "F#m".to_owned(),
+            line: 2,
+        };
+        assert_eq!(
+            err.to_string(),
+            "Chord F#m on line 2 has no voicing on the configured guitar."
+        );
+    }
+
+    #[test]
+    fn snapshot_invalid() {
+        let err = TabError::SnapshotInvalid {
+            reason: "unknown pitch H4".to_owned(),
+        };
+        assert_eq!(
+            err.to_string(),
+            "The arrangement set could not be imported: unknown pitch H4"
+        );
+    }
+
+    #[test]
+    fn snapshot_version_unsupported() {
+        let err = TabError::SnapshotVersionUnsupported {
+            version: 2,
+            supported: 1,
+        };
+        assert_eq!(
+            err.to_string(),
+            "The arrangement set was exported in format version 2. This build reads version 1."
+        );
+    }
 }
@@ -0,0 +1,585 @@
+//! The C API, built into the `cdylib` with the `ffi` feature.
+//!
+//! `include/guitar_tab_generator.h` declares it and is generated from this module by
+//! `cbindgen --config cbindgen.toml`. A caller builds a [`GtgRequest`] from the
+//! [`TabInput`] fields, arranges it with [`gtg_generate`] into a [`GtgSet`], and queries or
+//! renders the set by arrangement index. Each handle is freed by its own `_free` function,
+//! which accepts `NULL`.
+//!
+//! Every fallible call returns a [`GtgStatus`], `GTG_STATUS_OK` on success, and on failure
+//! can hand back a [`GtgError`] with the status and a message through its last argument. A
+//! [`TabError`] maps to the status of its variant, numbered like the `guitar-tab` exit
+//! codes. Strings cross as NUL-terminated UTF-8. A panic never unwinds into the caller:
+//! each function catches it, and a fallible call fails with `GTG_STATUS_INTERNAL`.
+
+use crate::{ArrangementSet, TabError, TabInput, generate_arrangements};
+use std::{
+    ffi::{CStr, CString, c_char},
+    ptr,
+};
+
+/// The outcome of a call. The statuses from 10 up are the [`TabError`] variants.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GtgStatus {
+    Ok = 0,
+    /// A required pointer argument was `NULL`.
+    NullArgument = 1,
+    /// A string argument was not valid UTF-8.
+    InvalidUtf8 = 2,
+    /// The caller's buffer cannot hold the output and its terminating NUL.
+    BufferTooSmall = 3,
+    /// The library panicked. The message holds the panic's, and is worth reporting as a bug.
+    Internal = 4,
+    Parse = 10,
+    InputTooManyLines = 11,
+    NumFretsTooHigh = 12,
+    CapoTooHigh = 13,
+    CapoExceedsFrets = 14,
+    StringNumberOutOfRange = 15,
+    OpenPitchOutOfRange = 16,
+    FretRangeExceedsPitchRange = 17,
+    UnplayablePitches = 18,
+    NoArrangementsFound = 19,
+    NumArrangementsOutOfRange = 20,
+    TuningNameUnknown = 21,
+    IndexOutOfBounds = 22,
+    RenderWidthTooSmall = 23,
+    DifficultyWeightOutOfRange = 24,
+    UnplayableChord = 25,
+    SnapshotInvalid = 26,
+    SnapshotVersionUnsupported = 27,
+}
+
+impl From<&TabError> for GtgStatus {
+    fn from(error: &TabError) -> Self {
+        match error {
+            TabError::Parse { .. } => Self::Parse,
+            TabError::InputTooManyLines { .. } => Self::InputTooManyLines,
+            TabError::NumFretsTooHigh { .. } => Self::NumFretsTooHigh,
+            TabError::CapoTooHigh { .. } => Self::CapoTooHigh,
+            TabError::CapoExceedsFrets { .. } => Self::CapoExceedsFrets,
+            TabError::StringNumberOutOfRange { .. } => Self::StringNumberOutOfRange,
+            TabError::OpenPitchOutOfRange { .. } => Self::OpenPitchOutOfRange,
+            TabError::FretRangeExceedsPitchRange { .. } => Self::FretRangeExceedsPitchRange,
+            TabError::UnplayablePitches { .. } => Self::UnplayablePitches,
+            TabError::NoArrangementsFound => Self::NoArrangementsFound,
+            TabError::NumArrangementsOutOfRange { .. } => Self::NumArrangementsOutOfRange,
+            TabError::TuningNameUnknown { .. } => Self::TuningNameUnknown,
+            TabError::IndexOutOfBounds { .. } => Self::IndexOutOfBounds,
+            TabError::RenderWidthTooSmall { .. } => Self::RenderWidthTooSmall,
+            TabError::DifficultyWeightOutOfRange { .. } => Self::DifficultyWeightOutOfRange,
+            TabError::UnplayableChord { .. } => Self::UnplayableChord,
+            TabError::SnapshotInvalid { .. } => Self::SnapshotInvalid,
+            TabError::SnapshotVersionUnsupported { .. } => Self::SnapshotVersionUnsupported,
+        }
+    }
+}
+#[cfg(test)]
+mod test_gtg_status {
+    use super::*;
+
+    #[test]
+    fn tab_errors_match_the_exit_codes() {
+        let statuses = [
+            TabError::Parse { errors: vec![] },
+            TabError::NoArrangementsFound,
+            TabError::SnapshotVersionUnsupported {
+                version: 2,
+                supported: 1,
+            },
+        ]
+        .iter()
+        .map(|error| GtgStatus::from(error) as i32)
+        .collect::<Vec<_>>();
+        assert_eq!(statuses, [10, 19, 27]);
+    }
+}
+
+/// A failed call's status and message. Free with [`gtg_error_free`].
+#[derive(Debug)]
+pub struct GtgError {
+    status: GtgStatus,
+    message: CString,
+}
+
+impl GtgError {
+    fn new(status: GtgStatus, message: impl Into<String>) -> Self {
+        let mut message = message.into();
+        message.retain(|character| character != '\0');
+        Self {
+            status,
+            message: CString::new(message).expect("BUG: NUL bytes were removed"),
+        }
+    }
+
+    fn null_argument(name: &str) -> Self {
+        Self::new(GtgStatus::NullArgument, format!("{name} must not be NULL."))
+    }
+}
+
+impl From<TabError> for GtgError {
+    fn from(error: TabError) -> Self {
+        Self::new(GtgStatus::from(&error), error.to_string())
+    }
+}
+
+/// A generation request: the input and guitar of a [`TabInput`]. Free with
+/// [`gtg_request_free`].
+#[derive(Debug)]
+pub struct GtgRequest {
+    tab_input: TabInput,
+}
+
+/// The arrangements of one [`gtg_generate`] call. Free with [`gtg_set_free`].
+#[derive(Debug)]
+pub struct GtgSet {
+    set: ArrangementSet,
+}
+
+/// Writes `value` to `out` and returns `Ok`, or hands the error to `out_error` and returns
+/// its status.
+///
+/// # Safety
+///
+/// `out` must be valid for a write. `out_error` must be `NULL` or valid for a write.
+unsafe fn finish<T>(
+    result: Result<T, GtgError>,
+    out: *mut T,
+    out_error: *mut *mut GtgError,
+) -> GtgStatus {
+    match result {
+        Ok(value) => {
+            // SAFETY: the caller guarantees `out` is writable.
+            unsafe { out.write(value) };
+            GtgStatus::Ok
+        }
+        Err(error) => {
+            let status = error.status;
+            if !out_error.is_null() {
+                // SAFETY: the caller guarantees a non-NULL `out_error` is writable.
+                unsafe { out_error.write(Box::into_raw(Box::new(error))) };
+            }
+            status
+        }
+    }
+}
+
+/// # Safety
+///
+/// `string` must be `NULL` or a NUL-terminated string.
+unsafe fn read_str<'a>(string: *const c_char, name: &str) -> Result<&'a str, GtgError> {
+    if string.is_null() {
+        return Err(GtgError::null_argument(name));
+    }
+    // SAFETY: the caller guarantees a non-NULL `string` is NUL-terminated.
+    unsafe { CStr::from_ptr(string) }
+        .to_str()
+        .map_err(|_| GtgError::new(GtgStatus::InvalidUtf8, format!("{name} is not UTF-8.")))
+}
+
+fn out_argument<T>(out: *mut T, name: &str) -> Result<(), GtgError> {
+    if out.is_null() {
+        Err(GtgError::null_argument(name))
+    } else {
+        Ok(())
+    }
+}
+
+/// Runs the body of a fallible call, turning a panic into a `GtgStatus::Internal` error so it
+/// does not unwind into C.
+fn catch_panic<T>(body: impl FnOnce() -> Result<T, GtgError>) -> Result<T, GtgError> {
+    std::panic::catch_unwind(std::panic::AssertUnwindSafe(body)).unwrap_or_else(|payload| {
+        let message = payload
+            .downcast_ref::<&str>()
+            .copied()
+            .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+            .unwrap_or("unknown panic");
+        Err(GtgError::new(
+            GtgStatus::Internal,
+            format!("Internal error: {message}"),
+        ))
+    })
+}
+#[cfg(test)]
+mod test_catch_panic {
+    use super::*;
+
+    #[test]
+    fn passes_results_through() {
+        assert_eq!(catch_panic(|| Ok(7)).unwrap(), 7);
+        let error = catch_panic(|| -> Result<(), _> { Err(GtgError::null_argument("set")) });
+        assert_eq!(error.unwrap_err().status, GtgStatus::NullArgument);
+    }
+    #[test]
+    fn turns_a_panic_into_an_internal_error() {
+        let error =
+            catch_panic(|| -> Result<(), GtgError> { panic!("lost {}", "track") }).unwrap_err();
+        assert_eq!(error.status, GtgStatus::Internal);
+        assert_eq!(
+            error.message.to_str().unwrap(),
+            "Internal error: lost track"
+        );
+        let error = catch_panic(|| -> Result<(), GtgError> { panic!("static") }).unwrap_err();
+        assert_eq!(error.message.to_str().unwrap(), "Internal error: static");
+    }
+}
+
+/// Runs the body of an infallible call, returning `on_panic` instead of unwinding into C.
+fn or_on_panic<T>(on_panic: T, body: impl FnOnce() -> T) -> T {
+    std::panic::catch_unwind(std::panic::AssertUnwindSafe(body)).unwrap_or(on_panic)
+}
+
+/// Creates a request to arrange `input` on a guitar with `tuning_name`, `num_frets` and
+/// `capo`, keeping the `num_arrangements` easiest arrangements. The arguments are checked
+/// by [`gtg_generate`], as by `generate_arrangements`.
+///
+/// # Safety
+///
+/// `input` and `tuning_name` must be `NULL` or NUL-terminated strings. `out_request` must be
+/// `NULL` or valid for a write, and `out_error` likewise.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn gtg_request_new(
+    input: *const c_char,
+    tuning_name: *const c_char,
+    num_frets: u8,
+    capo: u8,
+    num_arrangements: u8,
+    out_request: *mut *mut GtgRequest,
+    out_error: *mut *mut GtgError,
+) -> GtgStatus {
+    let result = catch_panic(|| {
+        out_argument(out_request, "out_request")?;
+        // SAFETY: the caller guarantees both strings are NULL or NUL-terminated.
+        let (input, tuning_name) = unsafe {
+            (
+                read_str(input, "input")?,
+                read_str(tuning_name, "tuning_name")?,
+            )
+        };
+        let tab_input = TabInput::new(input, tuning_name, num_frets, capo, num_arrangements);
+        Ok(Box::into_raw(Box::new(GtgRequest { tab_input })))
+    });
+    // SAFETY: `out_request` was checked, and the caller guarantees `out_error`.
+    unsafe { finish(result, out_request, out_error) }
+}
+
+/// Frees a request from [`gtg_request_new`]. `NULL` is a no-op.
+///
+/// # Safety
+///
+/// `request` must be `NULL` or a live request from [`gtg_request_new`].
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn gtg_request_free(request: *mut GtgRequest) {
+    if !request.is_null() {
+        // SAFETY: the caller guarantees the request is live and owned by this library.
+        or_on_panic((), || drop(unsafe { Box::from_raw(request) }));
+    }
+}
+
+/// Arranges `request` like `generate_arrangements` and writes the new set to `out_set`. The
+/// request stays usable.
+///
+/// # Safety
+///
+/// `request` must be `NULL` or a live request. `out_set` must be `NULL` or valid for a
+/// write, and `out_error` likewise.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn gtg_generate(
+    request: *const GtgRequest,
+    out_set: *mut *mut GtgSet,
+    out_error: *mut *mut GtgError,
+) -> GtgStatus {
+    let result = catch_panic(|| {
+        out_argument(out_set, "out_set")?;
+        // SAFETY: the caller guarantees a non-NULL request is live.
+        let request = unsafe { request.as_ref() }.ok_or(GtgError::null_argument("request"))?;
+        let set = generate_arrangements(request.tab_input.clone())?;
+        Ok(Box::into_raw(Box::new(GtgSet { set })))
+    });
+    // SAFETY: `out_set` was checked, and the caller guarantees `out_error`.
+    unsafe { finish(result, out_set, out_error) }
+}
+
+/// Frees a set from [`gtg_generate`]. `NULL` is a no-op.
+///
+/// # Safety
+///
+/// `set` must be `NULL` or a live set from [`gtg_generate`].
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn gtg_set_free(set: *mut GtgSet) {
+    if !set.is_null() {
+        // SAFETY: the caller guarantees the set is live and owned by this library.
+        or_on_panic((), || drop(unsafe { Box::from_raw(set) }));
+    }
+}
+
+/// Number of arrangements in `set`, or 0 for `NULL`.
+///
+/// # Safety
+///
+/// `set` must be `NULL` or a live set.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn gtg_set_len(set: *const GtgSet) -> usize {
+    // SAFETY: the caller guarantees a non-NULL set is live.
+    or_on_panic(0, || unsafe { set.as_ref() }.map_or(0, |set| set.set.len()))
+}
+
+/// Writes the difficulty of the arrangement at `index` to `out_difficulty`. Lower is easier.
+///
+/// # Safety
+///
+/// `set` must be `NULL` or a live set. `out_difficulty` must be `NULL` or valid for a write,
+/// and `out_error` likewise.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn gtg_set_difficulty(
+    set: *const GtgSet,
+    index: usize,
+    out_difficulty: *mut f64,
+    out_error: *mut *mut GtgError,
+) -> GtgStatus {
+    let result = catch_panic(|| {
+        out_argument(out_difficulty, "out_difficulty")?;
+        // SAFETY: the caller guarantees a non-NULL set is live.
+        let set = unsafe { set.as_ref() }.ok_or(GtgError::null_argument("set"))?;
+        Ok(set.set.difficulty(index)?)
+    });
+    // SAFETY: `out_difficulty` was checked, and the caller guarantees `out_error`.
+    unsafe { finish(result, out_difficulty, out_error) }
+}
+
+/// Writes the largest per-beat fret span of the arrangement at `index` to
+/// `out_max_fret_span`.
+///
+/// # Safety
+///
+/// `set` must be `NULL` or a live set. `out_max_fret_span` must be `NULL` or valid for a
+/// write, and `out_error` likewise.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn gtg_set_max_fret_span(
+    set: *const GtgSet,
+    index: usize,
+    out_max_fret_span: *mut u8,
+    out_error: *mut *mut GtgError,
+) -> GtgStatus {
+    let result = catch_panic(|| {
+        out_argument(out_max_fret_span, "out_max_fret_span")?;
+        // SAFETY: the caller guarantees a non-NULL set is live.
+        let set = unsafe { set.as_ref() }.ok_or(GtgError::null_argument("set"))?;
+        Ok(set.set.max_fret_span(index)?)
+    });
+    // SAFETY: `out_max_fret_span` was checked, and the caller guarantees `out_error`.
+    unsafe { finish(result, out_max_fret_span, out_error) }
+}
+
+/// Renders the arrangement at `index` like `ArrangementSet::render` into `buffer` as a
+/// NUL-terminated string.
+///
+/// `out_len` receives the length of the tab in bytes, without the NUL, even when `buffer` is
+/// too small. So a caller can pass a `NULL` buffer of length 0 to size it, then render
+/// again. A buffer without room for the NUL fails with `GTG_STATUS_BUFFER_TOO_SMALL` and is
+/// left untouched.
+///
+/// # Safety
+///
+/// `set` must be `NULL` or a live set. `buffer` must be valid for `buffer_len` bytes of
+/// writes, or `NULL` with `buffer_len` 0. `out_len` must be `NULL` or valid for a write, and
+/// `out_error` likewise.
+#[allow(clippy::too_many_arguments)]
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn gtg_set_render(
+    set: *const GtgSet,
+    index: usize,
+    width: u16,
+    padding: u8,
+    buffer: *mut c_char,
+    buffer_len: usize,
+    out_len: *mut usize,
+    out_error: *mut *mut GtgError,
+) -> GtgStatus {
+    let result = catch_panic(|| {
+        out_argument(out_len, "out_len")?;
+        // SAFETY: the caller guarantees a non-NULL set is live.
+        let set = unsafe { set.as_ref() }.ok_or(GtgError::null_argument("set"))?;
+        let tab = set.set.render(index, width, padding, None)?;
+        // SAFETY: `out_len` was checked.
+        unsafe { out_len.write(tab.len()) };
+        if buffer_len <= tab.len() {
+            return Err(GtgError::new(
+                GtgStatus::BufferTooSmall,
+                format!(
+                    "The buffer holds {buffer_len} bytes, but the tab needs {} and a NUL.",
+                    tab.len()
+                ),
+            ));
+        }
+        if buffer.is_null() {
+            return Err(GtgError::null_argument("buffer"));
+        }
+        // SAFETY: the caller guarantees `buffer_len` writable bytes, which hold the tab and
+        // its NUL.
+        unsafe {
+            ptr::copy_nonoverlapping(tab.as_ptr(), buffer.cast::<u8>(), tab.len());
+            buffer.add(tab.len()).write(0);
+        }
+        Ok(tab.len())
+    });
+    // SAFETY: `out_len` was checked, and the caller guarantees `out_error`.
+    unsafe { finish(result, out_len, out_error) }
+}
+
+/// The status `error` failed with, or `GTG_STATUS_NULL_ARGUMENT` for `NULL`.
+///
+/// # Safety
+///
+/// `error` must be `NULL` or a live error.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn gtg_error_status(error: *const GtgError) -> GtgStatus {
+    // SAFETY: the caller guarantees a non-NULL error is live.
+    or_on_panic(GtgStatus::Internal, || {
+        unsafe { error.as_ref() }.map_or(GtgStatus::NullArgument, |error| error.status)
+    })
+}
+
+/// The message of `error` as a NUL-terminated string owned by the error, or `NULL` for
+/// `NULL`. It is freed with the error.
+///
+/// # Safety
+///
+/// `error` must be `NULL` or a live error.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn gtg_error_message(error: *const GtgError) -> *const c_char {
+    // SAFETY: the caller guarantees a non-NULL error is live.
+    or_on_panic(ptr::null(), || {
+        unsafe { error.as_ref() }.map_or(ptr::null(), |error| error.message.as_ptr())
+    })
+}
+
+/// Frees an error handed back by a failed call. `NULL` is a no-op.
+///
+/// # Safety
+///
+/// `error` must be `NULL` or a live error from this library.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn gtg_error_free(error: *mut GtgError) {
+    if !error.is_null() {
+        // SAFETY: the caller guarantees the error is live and owned by this library.
+        or_on_panic((), || drop(unsafe { Box::from_raw(error) }));
+    }
+}
+#[cfg(test)]
+mod test_c_api {
+    use super::*;
+
+    fn request(input: &CStr, tuning_name: &CStr) -> *mut GtgRequest {
+        let mut request = ptr::null_mut();
+        let status = unsafe {
+            gtg_request_new(
+                input.as_ptr(),
+                tuning_name.as_ptr(),
+                18,
+                0,
+                2,
+                &mut request,
+                ptr::null_mut(),
+            )
+        };
+        assert_eq!(status, GtgStatus::Ok);
+        request
+    }
+
+    #[test]
+    fn generates_queries_and_renders() {
+        let request = request(c"E2\nA2", c"standard");
+        let mut set = ptr::null_mut();
+        unsafe {
+            assert_eq!(
+                gtg_generate(request, &mut set, ptr::null_mut()),
+                GtgStatus::Ok
+            );
+            gtg_request_free(request);
+            assert_eq!(gtg_set_len(set), 2);
+
+            let mut span = u8::MAX;
+            let mut difficulty = -1.0;
+            let errors = ptr::null_mut();
+            assert_eq!(
+                gtg_set_max_fret_span(set, 0, &mut span, errors),
+                GtgStatus::Ok
+            );
+            assert_eq!(
+                gtg_set_difficulty(set, 0, &mut difficulty, errors),
+                GtgStatus::Ok
+            );
+            assert_eq!(span, 0);
+            assert!(difficulty >= 0.0);
+
+            let mut len = 0;
+            let status = gtg_set_render(set, 0, 20, 1, ptr::null_mut(), 0, &mut len, errors);
+            assert_eq!(status, GtgStatus::BufferTooSmall);
+            let mut buffer = vec![0 as c_char; len + 1];
+            let status = gtg_set_render(
+                set,
+                0,
+                20,
+                1,
+                buffer.as_mut_ptr(),
+                buffer.len(),
+                &mut len,
+                errors,
+            );
+            assert_eq!(status, GtgStatus::Ok);
+            let tab = CStr::from_ptr(buffer.as_ptr()).to_str().unwrap();
+            let expected = (*set).set.render(0, 20, 1, None).unwrap();
+            assert_eq!((tab, len), (expected.as_str(), expected.len()));
+            gtg_set_free(set);
+        }
+    }
+    #[test]
+    fn errors_carry_status_and_message() {
+        let request = request(c"E2", c"openZ");
+        let mut set = ptr::null_mut();
+        let mut error = ptr::null_mut();
+        unsafe {
+            let status = gtg_generate(request, &mut set, &mut error);
+            assert_eq!(status, GtgStatus::TuningNameUnknown);
+            assert!(set.is_null());
+            assert_eq!(gtg_error_status(error), status);
+            let message = CStr::from_ptr(gtg_error_message(error));
+            assert!(message.to_str().unwrap().contains("openZ"));
+            gtg_error_free(error);
+            gtg_request_free(request);
+        }
+    }
+    #[test]
+    fn rejects_null_and_bad_arguments() {
+        let mut error = ptr::null_mut();
+        let mut difficulty = 0.0;
+        unsafe {
+            let status = gtg_set_difficulty(ptr::null(), 0, &mut difficulty, &mut error);
+            assert_eq!(status, GtgStatus::NullArgument);
+            assert_eq!(
+                CStr::from_ptr(gtg_error_message(error)),
+                c"set must not be NULL."
+            );
+            gtg_error_free(error);
+
+            let mut request = ptr::null_mut();
+            let status = gtg_request_new(
+                c"E2\xff".as_ptr(),
+                c"standard".as_ptr(),
+                18,
+                0,
+                1,
+                &mut request,
+                ptr::null_mut(),
+            );
+            assert_eq!(status, GtgStatus::InvalidUtf8);
+            assert!(request.is_null());
+
+            assert_eq!(gtg_set_len(ptr::null()), 0);
+            gtg_set_free(ptr::null_mut());
+            gtg_error_free(ptr::null_mut());
+        }
+    }
+}
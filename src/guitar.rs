@@ -1,6 +1,39 @@
-use crate::{arrangement::PitchVec, error::TabError, pitch::Pitch, string_number::StringNumber};
+use crate::{
+    arrangement::PitchVec, error::TabError, pitch::Pitch, string_number::StringNumber, voice::Voice,
+};
+use serde::{Deserialize, Serialize};
 use std::{collections::BTreeMap, fmt};
 use strum::IntoEnumIterator;
+#[cfg(feature = "wasm")]
+use tsify::Tsify;
+
+/// How a harmonic `PitchFingering` is sounded.
+///
+/// A `Natural` harmonic touches an open string above fret 12, 7, 5 or 4, and its `fret` is
+/// the touched fret. An `Artificial` harmonic frets a note and touches the string 12 frets
+/// higher, sounding an octave up, and its `fret` is the fretted one.
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[cfg_attr(feature = "wasm", derive(Tsify))]
+#[serde(rename_all = "camelCase")]
+pub enum Harmonic {
+    Natural,
+    Artificial,
+}
+
+/// Which harmonic fingerings the arranger may choose, on top of the fretted ones.
+///
+/// `Off` by default, which keeps every arrangement fretted. Harmonics carry an extra
+/// difficulty cost, so the arranger only reaches for one when it beats the fretted options
+/// or when the pitch is out of fretted range.
+#[derive(Debug, Clone, Copy, Default, Hash, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "wasm", derive(Tsify))]
+#[serde(rename_all = "camelCase")]
+pub enum HarmonicMode {
+    #[default]
+    Off,
+    Natural,
+    NaturalAndArtificial,
+}
 
 /// The assignment of a single `Pitch` to a specific `StringNumber` and `fret` position.
 ///
@@ -10,6 +43,8 @@ pub struct PitchFingering {
     pub(crate) string_number: StringNumber,
     pub(crate) fret: u8,
     pub(crate) pitch: Pitch,
+    pub(crate) harmonic: Option<Harmonic>,
+    pub(crate) voice: Option<Voice>,
 }
 impl PitchFingering {
     /// The guitar string the pitch is fretted on.
@@ -32,6 +67,20 @@ impl PitchFingering {
     pub fn pitch(&self) -> Pitch {
         self.pitch
     }
+
+    /// How the pitch is sounded as a harmonic, or `None` for a plainly fretted note.
+    #[inline]
+    #[must_use]
+    pub fn harmonic(&self) -> Option<Harmonic> {
+        self.harmonic
+    }
+
+    /// The voice the pitch came from in multi-voice input, or `None` for a single-voice line.
+    #[inline]
+    #[must_use]
+    pub fn voice(&self) -> Option<Voice> {
+        self.voice
+    }
 }
 impl fmt::Debug for PitchFingering {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
@@ -39,7 +88,18 @@ impl fmt::Debug for PitchFingering {
             f,
             "{} | {:?} => {}",
             self.pitch, self.string_number, self.fret
-        )
+        )?;
+        match self.harmonic {
+            None => {}
+            Some(Harmonic::Natural) => f.write_str(" (natural harmonic)")?,
+            Some(Harmonic::Artificial) => f.write_str(" (artificial harmonic)")?,
+        }
+        match self.voice {
+            None => Ok(()),
+            Some(Voice::Melody) => f.write_str(" [melody]"),
+            Some(Voice::Inner) => f.write_str(" [inner]"),
+            Some(Voice::Bass) => f.write_str(" [bass]"),
+        }
     }
 }
 #[cfg(test)]
@@ -51,10 +111,39 @@ mod test_pitch_fingering_debug {
             pitch: Pitch::ASharpBFlat4,
             string_number: StringNumber::new(2).unwrap(),
             fret: 3,
+            harmonic: None,
+            voice: None,
         };
 
         assert_eq!(format!("{pitch_fingering:?}"), "A♯B♭4 | 2_B => 3");
     }
+    #[test]
+    fn harmonic() {
+        let pitch_fingering = PitchFingering {
+            pitch: Pitch::E5,
+            string_number: StringNumber::new(1).unwrap(),
+            fret: 12,
+            harmonic: Some(Harmonic::Natural),
+            voice: None,
+        };
+
+        assert_eq!(
+            format!("{pitch_fingering:?}"),
+            "E5 | 1_e => 12 (natural harmonic)"
+        );
+    }
+    #[test]
+    fn voice() {
+        let pitch_fingering = PitchFingering {
+            pitch: Pitch::E2,
+            string_number: StringNumber::new(6).unwrap(),
+            fret: 0,
+            harmonic: None,
+            voice: Some(Voice::Bass),
+        };
+
+        assert_eq!(format!("{pitch_fingering:?}"), "E2 | 6_E => 0 [bass]");
+    }
 }
 
 /// Open-string pitches for the standard 6-string guitar tuning, from string 1 (highest, E4)
@@ -100,7 +189,10 @@ pub fn create_string_tuning(
 #[derive(Debug, PartialEq, Eq, Clone, Hash)]
 pub struct Guitar {
     pub(crate) playable_frets: u8,
+    /// Fret the capo sits at, 0 without one. `string_ranges` already start at the capo.
+    pub(crate) capo: u8,
     pub(crate) string_ranges: BTreeMap<StringNumber, Box<[Pitch]>>,
+    pub(crate) harmonic_mode: HarmonicMode,
 }
 impl Default for Guitar {
     fn default() -> Guitar {
@@ -160,9 +252,33 @@ impl Guitar {
 
         Ok(Guitar {
             playable_frets,
+            capo,
             string_ranges,
+            harmonic_mode: HarmonicMode::Off,
         })
     }
+
+    /// The fret the capo sits at, 0 without a capo.
+    #[must_use]
+    pub fn capo(&self) -> u8 {
+        self.capo
+    }
+
+    /// The open pitch of each string as tuned, before the capo, from string 1 down.
+    pub(crate) fn tuning(&self) -> impl Iterator<Item = Pitch> + '_ {
+        self.string_ranges.values().map(|range| {
+            range[0]
+                .plus_offset(-i16::from(self.capo))
+                .expect("BUG: the tuning below the capo is a valid pitch")
+        })
+    }
+
+    /// Allows the harmonic fingerings named by `harmonic_mode` as arrangement candidates.
+    #[must_use]
+    pub fn with_harmonic_mode(mut self, harmonic_mode: HarmonicMode) -> Self {
+        self.harmonic_mode = harmonic_mode;
+        self
+    }
 }
 #[cfg(test)]
 mod test_create_guitar {
@@ -176,6 +292,7 @@ mod test_create_guitar {
 
         let expected_guitar = Guitar {
             playable_frets: NUM_FRETS,
+            capo: 0,
             string_ranges: BTreeMap::from([
                 (
                     StringNumber::new(1).unwrap(),
@@ -207,6 +324,7 @@ mod test_create_guitar {
                     Box::from([Pitch::E2, Pitch::F2, Pitch::FSharpGFlat2, Pitch::G2]),
                 ),
             ]),
+            harmonic_mode: HarmonicMode::Off,
         };
 
         assert_eq!(Guitar::new(tuning, NUM_FRETS, 0)?, expected_guitar);
@@ -222,6 +340,7 @@ mod test_create_guitar {
 
         let expected_guitar = Guitar {
             playable_frets: NUM_FRETS - CAPO,
+            capo: CAPO,
             string_ranges: BTreeMap::from([
                 (
                     StringNumber::new(1).unwrap(),
@@ -284,9 +403,15 @@ mod test_create_guitar {
                     ]),
                 ),
             ]),
+            harmonic_mode: HarmonicMode::Off,
         };
 
         assert_eq!(Guitar::new(tuning, NUM_FRETS, CAPO)?, expected_guitar);
+        assert_eq!(expected_guitar.capo(), CAPO);
+        assert_eq!(
+            expected_guitar.tuning().collect::<Vec<_>>(),
+            [Pitch::E4, Pitch::B3, Pitch::G3]
+        );
 
         Ok(())
     }
@@ -298,6 +423,7 @@ mod test_create_guitar {
 
         let expected_guitar = Guitar {
             playable_frets: NUM_FRETS,
+            capo: 0,
             string_ranges: BTreeMap::from([
                 (
                     StringNumber::new(1).unwrap(),
@@ -444,6 +570,7 @@ mod test_create_guitar {
                     ]),
                 ),
             ]),
+            harmonic_mode: HarmonicMode::Off,
         };
 
         assert_eq!(Guitar::new(tuning, NUM_FRETS, 0)?, expected_guitar);
@@ -670,6 +797,8 @@ pub(crate) fn generate_pitch_fingerings(
                     pitch: *pitch,
                     string_number: *string_number,
                     fret: fret_number as u8,
+                    harmonic: None,
+                    voice: None,
                 })
         })
         .collect();
@@ -715,7 +844,9 @@ mod test_generate_pitch_fingering {
             vec![PitchFingering {
                 pitch: Pitch::E2,
                 string_number: StringNumber::new(6).unwrap(),
-                fret: 0
+                fret: 0,
+                harmonic: None,
+                voice: None,
             }]
         );
         assert_eq!(
@@ -724,17 +855,23 @@ mod test_generate_pitch_fingering {
                 PitchFingering {
                     pitch: Pitch::D3,
                     string_number: StringNumber::new(4).unwrap(),
-                    fret: 0
+                    fret: 0,
+                    harmonic: None,
+                    voice: None,
                 },
                 PitchFingering {
                     pitch: Pitch::D3,
                     string_number: StringNumber::new(5).unwrap(),
-                    fret: 5
+                    fret: 5,
+                    harmonic: None,
+                    voice: None,
                 },
                 PitchFingering {
                     pitch: Pitch::D3,
                     string_number: StringNumber::new(6).unwrap(),
-                    fret: 10
+                    fret: 10,
+                    harmonic: None,
+                    voice: None,
                 }
             ]
         );
@@ -744,17 +881,23 @@ mod test_generate_pitch_fingering {
                 PitchFingering {
                     pitch: Pitch::CSharpDFlat4,
                     string_number: StringNumber::new(2).unwrap(),
-                    fret: 2
+                    fret: 2,
+                    harmonic: None,
+                    voice: None,
                 },
                 PitchFingering {
                     pitch: Pitch::CSharpDFlat4,
                     string_number: StringNumber::new(3).unwrap(),
-                    fret: 6
+                    fret: 6,
+                    harmonic: None,
+                    voice: None,
                 },
                 PitchFingering {
                     pitch: Pitch::CSharpDFlat4,
                     string_number: StringNumber::new(4).unwrap(),
-                    fret: 11
+                    fret: 11,
+                    harmonic: None,
+                    voice: None,
                 }
             ]
         );
@@ -780,7 +923,9 @@ mod test_generate_pitch_fingering {
             vec![PitchFingering {
                 pitch: Pitch::DSharpEFlat4,
                 string_number: StringNumber::new(2).unwrap(),
-                fret: 0
+                fret: 0,
+                harmonic: None,
+                voice: None,
             }]
         );
         assert_eq!(
@@ -789,12 +934,16 @@ mod test_generate_pitch_fingering {
                 PitchFingering {
                     pitch: Pitch::ASharpBFlat4,
                     string_number: StringNumber::new(1).unwrap(),
-                    fret: 3
+                    fret: 3,
+                    harmonic: None,
+                    voice: None,
                 },
                 PitchFingering {
                     pitch: Pitch::ASharpBFlat4,
                     string_number: StringNumber::new(2).unwrap(),
-                    fret: 7
+                    fret: 7,
+                    harmonic: None,
+                    voice: None,
                 }
             ]
         );
@@ -836,7 +985,9 @@ mod test_generate_pitch_fingering {
             vec![PitchFingering {
                 pitch: Pitch::E3,
                 string_number: StringNumber::new(4).unwrap(),
-                fret: 2
+                fret: 2,
+                harmonic: None,
+                voice: None,
             }]
         );
         Ok(())
@@ -884,6 +1035,160 @@ mod test_generate_pitch_fingering {
     }
 }
 
+/// Touched fret and the semitones it sounds above the open string, for each natural harmonic.
+const NATURAL_HARMONICS: [(u8, i16); 4] = [(12, 12), (7, 19), (5, 24), (4, 28)];
+
+/// Returns the harmonic `PitchFingering`s for `pitch` that `harmonic_mode` allows on the
+/// supplied `string_ranges`.
+///
+/// A natural harmonic needs its touched fret within the playable range. An artificial
+/// harmonic frets a note one octave below `pitch`. The open string is skipped there, since
+/// it duplicates the natural harmonic at fret 12.
+#[must_use]
+pub(crate) fn generate_harmonic_fingerings(
+    string_ranges: &BTreeMap<StringNumber, Box<[Pitch]>>,
+    pitch: &Pitch,
+    harmonic_mode: HarmonicMode,
+) -> PitchVec<PitchFingering> {
+    if harmonic_mode == HarmonicMode::Off {
+        return vec![];
+    }
+    let mut fingerings: PitchVec<PitchFingering> = vec![];
+    for (string_number, string_range) in string_ranges {
+        let Some(open_pitch) = string_range.first() else {
+            continue;
+        };
+        let playable_frets = string_range.len() - 1;
+        for (fret, semitones) in NATURAL_HARMONICS {
+            if fret as usize <= playable_frets && open_pitch.plus_offset(semitones) == Some(*pitch)
+            {
+                fingerings.push(PitchFingering {
+                    pitch: *pitch,
+                    string_number: *string_number,
+                    fret,
+                    harmonic: Some(Harmonic::Natural),
+                    voice: None,
+                });
+            }
+        }
+        if harmonic_mode != HarmonicMode::NaturalAndArtificial {
+            continue;
+        }
+        if let Some(fret) = string_range
+            .iter()
+            .position(|fretted| fretted.plus_offset(12) == Some(*pitch))
+            .filter(|&fret| fret > 0)
+        {
+            fingerings.push(PitchFingering {
+                pitch: *pitch,
+                string_number: *string_number,
+                fret: fret as u8,
+                harmonic: Some(Harmonic::Artificial),
+                voice: None,
+            });
+        }
+    }
+    fingerings
+}
+#[cfg(test)]
+mod test_generate_harmonic_fingerings {
+    use super::*;
+
+    fn high_e_string() -> BTreeMap<StringNumber, Box<[Pitch]>> {
+        BTreeMap::from([(
+            StringNumber::new(1).unwrap(),
+            create_string_range(&Pitch::E4, 18)
+                .unwrap()
+                .into_boxed_slice(),
+        )])
+    }
+
+    #[test]
+    fn off_yields_nothing() {
+        assert_eq!(
+            generate_harmonic_fingerings(&high_e_string(), &Pitch::E5, HarmonicMode::Off),
+            vec![]
+        );
+    }
+    #[test]
+    fn natural_harmonics_above_the_fretboard() {
+        // E4 + 28 semitones is G#6, far above fret 18 on the high E string.
+        assert_eq!(
+            generate_harmonic_fingerings(
+                &high_e_string(),
+                &Pitch::GSharpAFlat6,
+                HarmonicMode::Natural
+            ),
+            vec![PitchFingering {
+                pitch: Pitch::GSharpAFlat6,
+                string_number: StringNumber::new(1).unwrap(),
+                fret: 4,
+                harmonic: Some(Harmonic::Natural),
+                voice: None,
+            }]
+        );
+    }
+    #[test]
+    fn natural_mode_skips_artificial_harmonics() {
+        assert_eq!(
+            generate_harmonic_fingerings(&high_e_string(), &Pitch::A5, HarmonicMode::Natural),
+            vec![]
+        );
+    }
+    #[test]
+    fn artificial_harmonics_sound_an_octave_up() {
+        assert_eq!(
+            generate_harmonic_fingerings(
+                &high_e_string(),
+                &Pitch::A5,
+                HarmonicMode::NaturalAndArtificial
+            ),
+            vec![PitchFingering {
+                pitch: Pitch::A5,
+                string_number: StringNumber::new(1).unwrap(),
+                fret: 5,
+                harmonic: Some(Harmonic::Artificial),
+                voice: None,
+            }]
+        );
+    }
+    #[test]
+    fn octave_harmonic_is_natural_not_artificial() {
+        assert_eq!(
+            generate_harmonic_fingerings(
+                &high_e_string(),
+                &Pitch::E5,
+                HarmonicMode::NaturalAndArtificial
+            ),
+            vec![PitchFingering {
+                pitch: Pitch::E5,
+                string_number: StringNumber::new(1).unwrap(),
+                fret: 12,
+                harmonic: Some(Harmonic::Natural),
+                voice: None,
+            }]
+        );
+    }
+    #[test]
+    fn touched_fret_must_be_playable() {
+        let short_string = BTreeMap::from([(
+            StringNumber::new(1).unwrap(),
+            create_string_range(&Pitch::E4, 6)
+                .unwrap()
+                .into_boxed_slice(),
+        )]);
+        // The octave harmonic sits at fret 12, past this 6-fret string. Fret 5 still works.
+        assert_eq!(
+            generate_harmonic_fingerings(&short_string, &Pitch::E5, HarmonicMode::Natural),
+            vec![]
+        );
+        assert_eq!(
+            generate_harmonic_fingerings(&short_string, &Pitch::E6, HarmonicMode::Natural).len(),
+            1
+        );
+    }
+}
+
 #[cfg(test)]
 mod test_create_string_tuning_bounds {
     use super::*;
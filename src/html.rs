@@ -0,0 +1,255 @@
+use crate::{
+    arrangement::{BeatVec, Line},
+    duration::Duration,
+    guitar::{Guitar, PitchFingering},
+    renderer::{LaidOutColumn, RenderOptions, escape_markup, lay_out_tab, push_playback_line},
+};
+use std::fmt::Write;
+
+/// Renders an `Arrangement`'s lines as an HTML guitar tab.
+///
+/// Returns a `<pre class="tab">` element whose text is exactly what
+/// [`render_tab`](crate::render_tab) returns for the same parameters, so it wraps at the same
+/// beats. On every row, each beat's cell is wrapped in a
+/// `<span class="beat" data-beat="{n}" data-line="{l}">`, where `n` counts beats like
+/// `playback` does and `l` is the beat's index in `arrangement_lines`. A page can highlight,
+/// hover or seek to beat `n` through `[data-beat="n"]` without rendering again. The beat at
+/// `playback` also gets the class `playing`.
+#[must_use]
+pub fn render_tab_html(
+    arrangement_lines: &[Line<BeatVec<PitchFingering>>],
+    guitar: &Guitar,
+    width: u16,
+    padding: u8,
+    playback: Option<u16>,
+) -> String {
+    render_tab_html_with_options(
+        arrangement_lines,
+        &[],
+        guitar,
+        &RenderOptions {
+            playback,
+            ..RenderOptions::new(width, padding)
+        },
+    )
+}
+
+/// Renders the HTML tab around the text of
+/// [`render_tab_with_rhythm`](crate::render_tab_with_rhythm) for `options` and `durations`.
+/// Every field of `options` applies.
+pub(crate) fn render_tab_html_with_options(
+    arrangement_lines: &[Line<BeatVec<PitchFingering>>],
+    durations: &[Option<Duration>],
+    guitar: &Guitar,
+    options: &RenderOptions,
+) -> String {
+    let Some(layout) = lay_out_tab(arrangement_lines, guitar, options, durations) else {
+        return "<pre class=\"tab\"></pre>".to_owned();
+    };
+
+    let playing_beat = options.playback.map(usize::from);
+    let pos = layout.playback_indicator_position.as_ref();
+    let mut out = String::from("<pre class=\"tab\">");
+    for row_group_index in 0..layout.num_row_groups() {
+        for (_, heading_rows) in layout.heading_rows() {
+            if let Some(heading_row) = heading_rows
+                .get(row_group_index)
+                .filter(|row| !row.is_empty())
+            {
+                out.push_str(&escape_markup(heading_row));
+                out.push('\n');
+            }
+        }
+        push_playback_line(&mut out, "▼", row_group_index, pos);
+
+        let beat_columns: Vec<&LaidOutColumn> = layout
+            .columns
+            .iter()
+            .filter(|column| {
+                column.position.row_group_index == row_group_index && column.beat_index.is_some()
+            })
+            .collect();
+        for single_string_rows in &layout.rows_by_string {
+            let row = single_string_rows
+                .get(row_group_index)
+                .expect("BUG: every string has the same row-group count");
+            push_row(&mut out, row, &beat_columns, playing_beat);
+            out.push('\n');
+        }
+
+        push_playback_line(&mut out, "▲", row_group_index, pos);
+        out.push('\n'); // blank line between row groups
+    }
+    // Match `render_tab`, which drops the newline of the final blank line.
+    out.pop();
+    out.push_str("</pre>");
+    out
+}
+#[cfg(test)]
+mod test_render_tab_html {
+    use super::*;
+    use crate::{pitch::Pitch, renderer::render_tab, string_number::StringNumber};
+
+    fn fingering(string_number: u8, fret: u8) -> PitchFingering {
+        PitchFingering {
+            pitch: Pitch::E4,
+            string_number: StringNumber::new(string_number).unwrap(),
+            fret,
+            harmonic: None,
+            voice: None,
+        }
+    }
+
+    /// The text a browser shows for `html`: tags dropped and entities decoded.
+    fn text_content(html: &str) -> String {
+        let mut text = String::new();
+        let mut in_tag = false;
+        for c in html.chars() {
+            match c {
+                '<' => in_tag = true,
+                '>' => in_tag = false,
+                _ if !in_tag => text.push(c),
+                _ => {}
+            }
+        }
+        text.replace("&lt;", "<")
+            .replace("&gt;", ">")
+            .replace("&quot;", "\"")
+            .replace("&#39;", "'")
+            .replace("&amp;", "&")
+    }
+
+    fn lines() -> Vec<Line<BeatVec<PitchFingering>>> {
+        vec![
+            Line::Section("Verse & <Coda>".to_owned()),
+            Line::Playable(vec![fingering(1, 0), fingering(2, 12)]),
+            Line::Technique(crate::technique::Technique::HammerOn),
+            Line::Playable(vec![fingering(1, 3)]),
+            Line::MeasureBreak,
+            Line::Rest,
+            Line::Playable(vec![fingering(3, 5)]),
+            Line::Playable(vec![fingering(6, 7)]),
+        ]
+    }
+
+    #[test]
+    fn text_matches_the_ascii_tab() {
+        for (width, playback) in [(40, None), (9, Some(2)), (6, Some(4))] {
+            let html = render_tab_html(&lines(), &Guitar::default(), width, 1, playback);
+            assert_eq!(
+                text_content(&html),
+                render_tab(&lines(), &Guitar::default(), width, 1, playback),
+                "width {width}, playback {playback:?}"
+            );
+        }
+    }
+    #[test]
+    fn wraps_each_beat_cell() {
+        let html = render_tab_html(&lines(), &Guitar::default(), 40, 1, None);
+        // One span per string for each of the five beats.
+        assert_eq!(html.matches("<span class=\"beat\"").count(), 5 * 6);
+        assert_eq!(html.matches("data-beat=\"0\" data-line=\"1\"").count(), 6);
+        assert!(html.contains("<span class=\"beat\" data-beat=\"0\" data-line=\"1\">12</span>"));
+        assert!(html.contains("<span class=\"beat\" data-beat=\"4\" data-line=\"7\">7</span>"));
+    }
+    #[test]
+    fn marks_the_playing_beat() {
+        let html = render_tab_html(&lines(), &Guitar::default(), 40, 1, Some(2));
+        assert_eq!(html.matches("class=\"beat playing\"").count(), 6);
+        assert!(html.contains("<span class=\"beat playing\" data-beat=\"2\""));
+    }
+    #[test]
+    fn escapes_labels() {
+        let html = render_tab_html(&lines(), &Guitar::default(), 40, 1, None);
+        assert!(html.contains("[Verse &amp; &lt;Coda&gt;]"));
+        assert!(!html.contains("<Coda>"));
+    }
+    #[test]
+    fn text_matches_the_ascii_tab_with_options() {
+        let options = RenderOptions::new(20, 1)
+            .with_playback(1)
+            .with_dash_style(crate::renderer::DashStyle::Unicode)
+            .with_empty_string_glyph('·')
+            .with_label_mode(crate::renderer::LabelMode::All);
+        let html = render_tab_html_with_options(&lines(), &[], &Guitar::default(), &options);
+        assert_eq!(
+            text_content(&html),
+            crate::render_tab_with_options(&lines(), &Guitar::default(), &options)
+        );
+        assert!(html.contains("<span class=\"beat\" data-beat=\"0\" data-line=\"1\">12</span>"));
+    }
+    #[test]
+    fn nothing_to_draw() {
+        assert_eq!(
+            render_tab_html(&[], &Guitar::default(), 20, 1, None),
+            "<pre class=\"tab\"></pre>"
+        );
+    }
+}
+
+/// Writes one string's `row`, wrapping the cells of `beat_columns` in beat spans and
+/// escaping everything else.
+fn push_row(
+    out: &mut String,
+    row: &str,
+    beat_columns: &[&LaidOutColumn],
+    playing_beat: Option<usize>,
+) {
+    // Column positions count characters, and glyphs such as `─` take several bytes.
+    let chars: Vec<char> = row.chars().collect();
+    let text =
+        |range: std::ops::Range<usize>| escape_markup(&chars[range].iter().collect::<String>());
+    let mut cursor = 0;
+    for column in beat_columns {
+        let Some(beat_index) = column.beat_index else {
+            continue;
+        };
+        let start = column.position.column_index;
+        let end = start + column.width();
+        out.push_str(&text(cursor..start));
+        let class = if playing_beat == Some(beat_index) {
+            "beat playing"
+        } else {
+            "beat"
+        };
+        let _ = write!(
+            out,
+            "<span class=\"{class}\" data-beat=\"{beat_index}\" data-line=\"{}\">{}</span>",
+            column.line_index,
+            text(start..end),
+        );
+        cursor = end;
+    }
+    out.push_str(&text(cursor..chars.len()));
+}
+#[cfg(test)]
+mod test_push_row {
+    use super::*;
+    use crate::renderer::ColumnPosition;
+
+    fn column(beat_index: Option<usize>, cell: &str, column_index: usize) -> LaidOutColumn {
+        LaidOutColumn {
+            line_index: column_index,
+            beat_index,
+            cells: vec![cell.to_owned()],
+            position: ColumnPosition {
+                row_group_index: 0,
+                column_index,
+            },
+        }
+    }
+
+    #[test]
+    fn wraps_beat_cells_only() {
+        let beat = column(Some(0), "12", 1);
+        let bar = column(None, "|", 4);
+        let playing = column(Some(1), "-", 6);
+        let mut out = String::new();
+        push_row(&mut out, "-12-|-<-", &[&beat, &bar, &playing], Some(1));
+        assert_eq!(
+            out,
+            "-<span class=\"beat\" data-beat=\"0\" data-line=\"1\">12</span>-|-\
+             <span class=\"beat playing\" data-beat=\"1\" data-line=\"6\">&lt;</span>-"
+        );
+    }
+}
@@ -86,27 +86,73 @@
 use ordered_float::OrderedFloat;
 use serde::{Deserialize, Serialize};
 use std::num::NonZeroU8;
+#[cfg(feature = "wasm")]
 use tsify::Tsify;
+#[cfg(feature = "wasm")]
 use wasm_bindgen::prelude::*;
 
 pub(crate) mod arrangement;
+pub(crate) mod batch;
+pub(crate) mod chord_diagram;
+pub(crate) mod chord_name;
+pub(crate) mod chord_symbol;
+#[cfg(feature = "cli")]
+pub mod cli;
+pub(crate) mod duration;
 pub(crate) mod error;
+#[cfg(feature = "ffi")]
+pub mod ffi;
 pub(crate) mod guitar;
+pub(crate) mod html;
+#[cfg(feature = "lsp")]
+pub mod lsp;
 pub(crate) mod parser;
 pub(crate) mod pitch;
 pub(crate) mod renderer;
+#[cfg(feature = "rpc")]
+pub mod rpc;
+#[cfg(feature = "serde")]
+pub(crate) mod serde_impls;
+pub(crate) mod snapshot;
 pub(crate) mod string_number;
+pub(crate) mod svg;
+pub(crate) mod technique;
+pub(crate) mod voice;
+#[cfg(feature = "wasm")]
+pub(crate) mod worker;
 
 /// `Arrangement` is re-exported for direct Rust consumers. The canonical 2.x access path
 /// for per-arrangement metadata is `ArrangementSet::difficulty(i)` and
 /// `ArrangementSet::max_fret_span(i)`. Direct construction of `Arrangement` values is internal.
-pub use arrangement::{Arrangement, BeatVec, Line, create_arrangements};
+pub use arrangement::{
+    Arrangement, BeatVec, CutOffNote, Line, create_arrangements, create_parsed_arrangements,
+    expand_repeats,
+};
+pub use batch::{BatchEntry, BatchInput, BatchReport, BatchSummaryRow, arrange_batch};
+pub use chord_diagram::{Barre, ChordShape, render_chord_diagram, render_chord_diagram_svg};
+pub use chord_name::name_chord;
+pub use chord_symbol::ChordSymbol;
+pub use duration::Duration;
 pub use error::{ParseError, TabError, UnplayablePitch};
-pub use guitar::{Guitar, PitchFingering, create_string_tuning};
-pub use parser::{TuningName, get_tuning_names, parse_lines};
+pub use guitar::{Guitar, Harmonic, HarmonicMode, PitchFingering, create_string_tuning};
+pub use html::render_tab_html;
+pub use parser::{
+    ParsedLine, TuningName, get_tuning_names, parse_input, parse_input_lenient, parse_lines,
+    parse_lines_lenient,
+};
 pub use pitch::Pitch;
-pub use renderer::render_tab;
+pub use renderer::{
+    BeatPosition, ColumnPosition, DashStyle, LabelMode, PlaybackIndicatorPosition, RenderOptions,
+    RenderedTab, render_tab, render_tab_header, render_tab_with_chord_names,
+    render_tab_with_labels, render_tab_with_layout, render_tab_with_options,
+    render_tab_with_rhythm,
+};
 pub use string_number::StringNumber;
+pub use svg::render_tab_svg;
+pub use technique::Technique;
+pub use voice::{Voice, VoiceColumn};
+#[cfg(feature = "wasm")]
+pub use worker::{WorkerRequest, WorkerResponse, handle_worker_request};
 
 /// Bench-only escape hatches the crate exposes for criterion benchmarks.
 ///
@@ -131,7 +177,8 @@ pub mod __bench_internals {
 /// Raw difficulty weights as they cross the WASM boundary. Validated into
 /// [`DifficultyWeights`] by [`generate_arrangements`]. Omitting it (or `null`)
 /// uses [`DifficultyWeights::standard`].
-#[derive(Debug, Clone, Copy, Deserialize, Tsify)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[cfg_attr(feature = "wasm", derive(Tsify))]
 #[serde(rename_all = "camelCase")]
 pub struct DifficultyWeightsInput {
     pub movement: f64,
@@ -144,8 +191,8 @@ pub struct DifficultyWeightsInput {
 /// Crosses the WASM boundary via `tsify`. JS sees a camelCase interface generated
 /// alongside the `.wasm`. `num_arrangements` must be in `1..=NumArrangements::MAX`. The value is validated
 /// at the boundary and a [`TabError::NumArrangementsOutOfRange`] is thrown when out of range.
-#[derive(Debug, Clone, Deserialize, Tsify)]
-#[tsify(from_wasm_abi)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "wasm", derive(Tsify), tsify(from_wasm_abi))]
 #[serde(rename_all = "camelCase")]
 #[non_exhaustive]
 pub struct TabInput {
@@ -160,13 +207,31 @@ pub struct TabInput {
     pub num_arrangements: u8,
     /// Upper bound on per-beat fret span. An aggressive value can drop the set to zero
     /// arrangements. Callers receive `Ok(set)` with `set.len == 0`, not `Err`.
-    #[tsify(optional)]
+    #[cfg_attr(feature = "wasm", tsify(optional))]
     pub max_fret_span_filter: Option<u8>,
     /// Per-call override of the difficulty-scoring coefficients. Omitted (or
     /// `None`) means [`DifficultyWeights::standard`], reproducing the built-in
     /// ranking. Validated at the boundary by [`generate_arrangements`].
-    #[tsify(optional)]
+    #[cfg_attr(feature = "wasm", tsify(optional))]
     pub difficulty_weights: Option<DifficultyWeightsInput>,
+    /// Recover from unparseable text instead of failing with [`TabError::Parse`]. Bad
+    /// substrings are dropped, lines left without a pitch become rests, and the dropped text
+    /// is reported on [`ArrangementSet::parse_warnings`]. Omitted means `false`.
+    #[serde(default)]
+    #[cfg_attr(feature = "wasm", tsify(optional))]
+    pub lenient_parse: bool,
+    /// Unroll repeats and volta endings with [`expand_repeats`] before arranging, so the
+    /// arrangement, the normalized input and the playback cursor all follow the order the
+    /// piece is played in. Omitted means `false`.
+    #[serde(default)]
+    #[cfg_attr(feature = "wasm", tsify(optional))]
+    pub expand_repeats: bool,
+    /// Harmonic fingerings the arranger may use, on top of fretted notes. Omitted means
+    /// `"off"`. `"natural"` adds natural harmonics at frets 12, 7, 5 and 4, and
+    /// `"naturalAndArtificial"` also adds artificial harmonics an octave above a fretted note.
+    #[serde(default)]
+    #[cfg_attr(feature = "wasm", tsify(optional))]
+    pub harmonic_mode: HarmonicMode,
 }
 
 impl TabInput {
@@ -190,6 +255,9 @@ impl TabInput {
             num_arrangements,
             max_fret_span_filter: None,
             difficulty_weights: None,
+            lenient_parse: false,
+            expand_repeats: false,
+            harmonic_mode: HarmonicMode::Off,
         }
     }
 
@@ -206,6 +274,27 @@ impl TabInput {
         self.difficulty_weights = Some(weights);
         self
     }
+
+    /// Sets `lenient_parse`.
+    #[must_use]
+    pub fn with_lenient_parse(mut self, lenient: bool) -> Self {
+        self.lenient_parse = lenient;
+        self
+    }
+
+    /// Sets `expand_repeats`.
+    #[must_use]
+    pub fn with_expand_repeats(mut self, expand: bool) -> Self {
+        self.expand_repeats = expand;
+        self
+    }
+
+    /// Sets `harmonic_mode`.
+    #[must_use]
+    pub fn with_harmonic_mode(mut self, harmonic_mode: HarmonicMode) -> Self {
+        self.harmonic_mode = harmonic_mode;
+        self
+    }
 }
 
 /// Validated count of arrangements to compute. Construction enforces `1..=NumArrangements::MAX`.
@@ -428,13 +517,163 @@ mod test_difficulty_weights_input {
 ///
 /// Serialized as a discriminated union tagged by `kind`, so JS code can `switch (b.kind)`
 /// instead of comparing strings.
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Tsify)]
-#[tsify(into_wasm_abi)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "wasm", derive(Tsify), tsify(into_wasm_abi))]
 #[serde(tag = "kind", rename_all = "camelCase")]
 pub enum NormalizedBeat {
-    Playable { pitches: Vec<String> },
+    /// `chord` names the chord the pitches spell, such as `Am7` or `G/B`, and is `None` for
+    /// fewer than three pitches or a set no known chord spells. For a chord line such as
+    /// `{Am}`, `chord` is the symbol and `pitches` is the voicing the easiest arrangement chose,
+    /// lowest first.
+    Playable {
+        pitches: Vec<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        #[cfg_attr(feature = "wasm", tsify(optional))]
+        chord: Option<String>,
+    },
     Rest,
     MeasureBreak,
+    RepeatStart,
+    RepeatEnd,
+    Ending {
+        number: u8,
+    },
+    Section {
+        label: String,
+    },
+    Technique {
+        technique: Technique,
+    },
+    /// `beats` is `None` when the beat before rings to the end.
+    LetRing {
+        beats: Option<u8>,
+    },
+}
+
+/// One line of an arrangement read back from `ArrangementSet::lines`, at the same index as
+/// its `NormalizedBeat`.
+///
+/// Tagged by `kind` like `NormalizedBeat`. A `playable` line lists where each of its notes is
+/// played, so a UI can draw its own fretboard instead of reading the rendered tab.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "wasm", derive(Tsify), tsify(into_wasm_abi))]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum ArrangementLine {
+    Playable {
+        fingerings: Vec<NoteFingering>,
+    },
+    Rest,
+    MeasureBreak,
+    RepeatStart,
+    RepeatEnd,
+    Ending {
+        number: u8,
+    },
+    Section {
+        label: String,
+    },
+    Technique {
+        technique: Technique,
+    },
+    /// `beats` is `None` when the beat before rings to the end.
+    LetRing {
+        beats: Option<u8>,
+    },
+}
+impl ArrangementLine {
+    fn from_line(line: &Line<BeatVec<PitchFingering>>) -> Self {
+        match line {
+            Line::Playable(fingerings) => ArrangementLine::Playable {
+                fingerings: fingerings
+                    .iter()
+                    .map(|fingering| NoteFingering {
+                        string: fingering.string_number().get(),
+                        fret: fingering.fret(),
+                        pitch: fingering.pitch().plain_text().to_owned(),
+                        harmonic: fingering.harmonic(),
+                        voice: fingering.voice(),
+                    })
+                    .collect(),
+            },
+            Line::Rest => ArrangementLine::Rest,
+            Line::MeasureBreak => ArrangementLine::MeasureBreak,
+            Line::RepeatStart => ArrangementLine::RepeatStart,
+            Line::RepeatEnd => ArrangementLine::RepeatEnd,
+            Line::Ending(number) => ArrangementLine::Ending { number: *number },
+            Line::Section(label) => ArrangementLine::Section {
+                label: label.clone(),
+            },
+            Line::Technique(technique) => ArrangementLine::Technique {
+                technique: *technique,
+            },
+            Line::LetRing(beats) => ArrangementLine::LetRing { beats: *beats },
+        }
+    }
+}
+#[cfg(test)]
+mod test_arrangement_line {
+    use super::*;
+
+    #[test]
+    fn playable_lists_each_fingering() {
+        let line = Line::Playable(vec![PitchFingering {
+            string_number: StringNumber::new(1).unwrap(),
+            fret: 12,
+            pitch: Pitch::E5,
+            harmonic: Some(Harmonic::Natural),
+            voice: Some(Voice::Melody),
+        }]);
+        assert_eq!(
+            ArrangementLine::from_line(&line),
+            ArrangementLine::Playable {
+                fingerings: vec![NoteFingering {
+                    string: 1,
+                    fret: 12,
+                    pitch: "E5".to_owned(),
+                    harmonic: Some(Harmonic::Natural),
+                    voice: Some(Voice::Melody),
+                }],
+            }
+        );
+    }
+    #[test]
+    fn serializes_tagged_by_kind() {
+        let line = Line::Playable(vec![PitchFingering {
+            string_number: StringNumber::new(5).unwrap(),
+            fret: 1,
+            pitch: Pitch::ASharpBFlat2,
+            harmonic: None,
+            voice: None,
+        }]);
+        assert_eq!(
+            serde_json::to_string(&ArrangementLine::from_line(&line)).unwrap(),
+            r#"{"kind":"playable","fingerings":[{"string":5,"fret":1,"pitch":"Bb2"}]}"#
+        );
+        assert_eq!(
+            serde_json::to_string(&ArrangementLine::from_line(&Line::MeasureBreak)).unwrap(),
+            r#"{"kind":"measureBreak"}"#
+        );
+    }
+}
+
+/// Where one note of a `playable` [`ArrangementLine`] is played: its string (1 is the
+/// highest), its fret (0 for an open string) and its sounding pitch.
+///
+/// `harmonic` is set for a note sounded as a harmonic, and `voice` for a note from
+/// multi-voice input.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "wasm", derive(Tsify))]
+#[serde(rename_all = "camelCase")]
+pub struct NoteFingering {
+    pub string: u8,
+    pub fret: u8,
+    pub pitch: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "wasm", tsify(optional))]
+    pub harmonic: Option<Harmonic>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "wasm", tsify(optional))]
+    pub voice: Option<Voice>,
 }
 
 /// Opaque handle holding the result of one `generate_arrangements` call.
@@ -443,11 +682,20 @@ pub enum NormalizedBeat {
 /// arrangements. Per-arrangement metadata (`difficulty`, `max_fret_span`) and the rendered
 /// tab string are reached by index through methods on the handle.
 #[derive(Debug)]
-#[wasm_bindgen]
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
 pub struct ArrangementSet {
     arrangements: Vec<arrangement::Arrangement>,
     guitar: Guitar,
     normalized_input: Vec<NormalizedBeat>,
+    parse_warnings: Vec<ParseError>,
+    /// 1-indexed input line of each line in every arrangement's `lines`, which all share the
+    /// same line sequence.
+    input_line_numbers: Vec<u32>,
+    /// Duration of each line in every arrangement's `lines`. The renders space the tab by
+    /// them.
+    durations: Vec<Option<Duration>>,
+    /// The request that generated the set, kept so an export can replay it.
+    parameters: TabInput,
 }
 
 /// `ArrangementSet` indexed accessors return [`TabError::IndexOutOfBounds`] when
@@ -455,18 +703,18 @@ pub struct ArrangementSet {
 /// calling). Downstream callers can branch on the typed variant to surface it differently
 /// from user-facing errors like [`TabError::TuningNameUnknown`] or
 /// [`TabError::NumArrangementsOutOfRange`].
-#[wasm_bindgen]
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
 impl ArrangementSet {
     /// Number of arrangements in the set. Equal to the requested `num_arrangements`, possibly
     /// reduced by `max_fret_span_filter` when filtering would otherwise drop below the count.
-    #[wasm_bindgen(getter)]
+    #[cfg_attr(feature = "wasm", wasm_bindgen(getter))]
     #[must_use]
     pub fn len(&self) -> usize {
         self.arrangements.len()
     }
 
     /// Returns true when `len == 0`.
-    #[wasm_bindgen(getter, js_name = "isEmpty")]
+    #[cfg_attr(feature = "wasm", wasm_bindgen(getter, js_name = "isEmpty"))]
     #[must_use]
     pub fn is_empty(&self) -> bool {
         self.arrangements.is_empty()
@@ -478,18 +726,44 @@ impl ArrangementSet {
     /// Returns a fresh `Vec` on each call. Cache on the JS side if reading repeatedly.
     /// `examples/wasm.html` caches the result on `state.normalizedInput` and reads from that
     /// cache in the rerender path. That pattern is the intended consumer shape.
-    #[wasm_bindgen(getter, js_name = "normalizedInput")]
+    #[cfg_attr(feature = "wasm", wasm_bindgen(getter, js_name = "normalizedInput"))]
     #[must_use]
     pub fn normalized_input(&self) -> Vec<NormalizedBeat> {
         self.normalized_input.clone()
     }
 
+    /// Unparseable substrings that a lenient parse dropped, in input order. Always empty
+    /// unless `TabInput::lenient_parse` was set, since a strict parse fails instead.
+    ///
+    /// Returns a fresh `Vec` on each call.
+    #[cfg_attr(feature = "wasm", wasm_bindgen(getter, js_name = "parseWarnings"))]
+    #[must_use]
+    pub fn parse_warnings(&self) -> Vec<ParseError> {
+        self.parse_warnings.clone()
+    }
+
+    /// The lines of the arrangement at `index`, each at the same index as its entry in
+    /// [`ArrangementSet::normalized_input`], with the string, fret and pitch of every note.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TabError::IndexOutOfBounds`] when `index >= self.len`.
+    pub fn lines(&self, index: usize) -> Result<Vec<ArrangementLine>, TabError> {
+        self.arrangements
+            .get(index)
+            .map(|a| a.lines().iter().map(ArrangementLine::from_line).collect())
+            .ok_or(TabError::IndexOutOfBounds {
+                index,
+                len: self.arrangements.len(),
+            })
+    }
+
     /// Largest non-zero fret span across any beat in the arrangement at `index`.
     ///
     /// # Errors
     ///
     /// Returns [`TabError::IndexOutOfBounds`] when `index >= self.len`.
-    #[wasm_bindgen(js_name = "maxFretSpan")]
+    #[cfg_attr(feature = "wasm", wasm_bindgen(js_name = "maxFretSpan"))]
     pub fn max_fret_span(&self, index: usize) -> Result<u8, TabError> {
         self.arrangements
             .get(index)
@@ -515,6 +789,22 @@ impl ArrangementSet {
             })
     }
 
+    /// Let-ring notes that the arrangement at `index` cuts off by replaying their string.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TabError::IndexOutOfBounds`] when `index >= self.len`.
+    #[cfg_attr(feature = "wasm", wasm_bindgen(js_name = "cutOffNotes"))]
+    pub fn cut_off_notes(&self, index: usize) -> Result<Vec<CutOffNote>, TabError> {
+        self.arrangements
+            .get(index)
+            .map(|a| a.cut_off_notes().to_vec())
+            .ok_or(TabError::IndexOutOfBounds {
+                index,
+                len: self.arrangements.len(),
+            })
+    }
+
     /// Renders the arrangement at `index` at the supplied `width`, `padding`, and optional
     /// `playback` beat indicator. Cheap to call repeatedly with different render parameters
     /// -- pathfinding does not re-run.
@@ -531,6 +821,246 @@ impl ArrangementSet {
         padding: u8,
         playback: Option<u16>,
     ) -> Result<String, TabError> {
+        self.render_with_options(
+            index,
+            RenderOptions {
+                playback,
+                ..RenderOptions::new(width, padding)
+            },
+        )
+    }
+
+    /// Renders the arrangement at `index` as laid out by `options`, using
+    /// [`render_tab_with_options`]. [`ArrangementSet::render`] and the other text renders are
+    /// this method with the matching options.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`ArrangementSet::render`], for `options.width` and
+    /// `options.padding`.
+    #[cfg_attr(feature = "wasm", wasm_bindgen(js_name = "renderWithOptions"))]
+    pub fn render_with_options(
+        &self,
+        index: usize,
+        options: RenderOptions,
+    ) -> Result<String, TabError> {
+        let arrangement = self.arrangement_to_render(index, options.width, options.padding)?;
+        Ok(renderer::render_tab_with_rhythm(
+            &arrangement.lines,
+            &self.durations,
+            &self.guitar,
+            &options,
+        ))
+    }
+
+    /// The distinct chord shapes the arrangement at `index` uses, in order of first
+    /// appearance, for a "chords used" legend. A chord is a beat of three or more notes.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TabError::IndexOutOfBounds`] when `index >= self.len`.
+    #[cfg_attr(feature = "wasm", wasm_bindgen(js_name = "chordShapes"))]
+    pub fn chord_shapes(&self, index: usize) -> Result<Vec<ChordShape>, TabError> {
+        self.arrangements
+            .get(index)
+            .map(|a| chord_diagram::chord_shapes(&a.lines, &self.guitar))
+            .ok_or(TabError::IndexOutOfBounds {
+                index,
+                len: self.arrangements.len(),
+            })
+    }
+
+    /// ASCII chord diagrams of [`ArrangementSet::chord_shapes`], in the same order.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TabError::IndexOutOfBounds`] when `index >= self.len`.
+    #[cfg_attr(feature = "wasm", wasm_bindgen(js_name = "chordDiagrams"))]
+    pub fn chord_diagrams(&self, index: usize) -> Result<Vec<String>, TabError> {
+        Ok(self
+            .chord_shapes(index)?
+            .iter()
+            .map(render_chord_diagram)
+            .collect())
+    }
+
+    /// SVG chord diagrams of [`ArrangementSet::chord_shapes`], in the same order.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TabError::IndexOutOfBounds`] when `index >= self.len`.
+    #[cfg_attr(feature = "wasm", wasm_bindgen(js_name = "chordDiagramsSvg"))]
+    pub fn chord_diagrams_svg(&self, index: usize) -> Result<Vec<String>, TabError> {
+        Ok(self
+            .chord_shapes(index)?
+            .iter()
+            .map(render_chord_diagram_svg)
+            .collect())
+    }
+
+    /// Renders the arrangement at `index` like [`ArrangementSet::render`] with a chord-name row
+    /// above the tab, using [`render_tab_with_chord_names`].
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`ArrangementSet::render`].
+    #[cfg_attr(feature = "wasm", wasm_bindgen(js_name = "renderWithChordNames"))]
+    pub fn render_with_chord_names(
+        &self,
+        index: usize,
+        width: u16,
+        padding: u8,
+        playback: Option<u16>,
+    ) -> Result<String, TabError> {
+        self.render_with_options(
+            index,
+            RenderOptions {
+                playback,
+                ..RenderOptions::new(width, padding).with_label_mode(LabelMode::ChordNames)
+            },
+        )
+    }
+
+    /// Renders the arrangement at `index` like [`ArrangementSet::render`] under a header
+    /// listing the tuning, capo and difficulty, with string names before each row and measure
+    /// numbers above it. See [`render_tab_header`] and [`render_tab_with_labels`].
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`ArrangementSet::render`].
+    #[cfg_attr(feature = "wasm", wasm_bindgen(js_name = "renderWithLabels"))]
+    pub fn render_with_labels(
+        &self,
+        index: usize,
+        width: u16,
+        padding: u8,
+        playback: Option<u16>,
+    ) -> Result<String, TabError> {
+        let tab = self.render_with_options(
+            index,
+            RenderOptions {
+                playback,
+                ..RenderOptions::new(width, padding).with_label_mode(LabelMode::Strings)
+            },
+        )?;
+        let header = renderer::render_tab_header(&self.guitar, self.difficulty(index)?);
+        Ok(format!("{header}\n{tab}"))
+    }
+
+    /// Renders the arrangement at `index` like [`ArrangementSet::render_with_options`] and also
+    /// returns where each beat landed in the tab, with the input line it came from.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`ArrangementSet::render_with_options`].
+    #[cfg_attr(feature = "wasm", wasm_bindgen(js_name = "renderWithLayout"))]
+    pub fn render_with_layout(
+        &self,
+        index: usize,
+        options: RenderOptions,
+    ) -> Result<RenderedTab, TabError> {
+        let arrangement = self.arrangement_to_render(index, options.width, options.padding)?;
+        let mut rendered = renderer::render_tab_with_layout_and_options(
+            &arrangement.lines,
+            &self.durations,
+            &self.guitar,
+            &options,
+        );
+        for beat in &mut rendered.beats {
+            if let Some(&input_line) = self.input_line_numbers.get(beat.line as usize - 1) {
+                beat.line = input_line;
+            }
+        }
+        Ok(rendered)
+    }
+
+    /// Renders the arrangement at `index` as an SVG tab like [`render_tab_svg`], laid out by
+    /// `options` and wrapped at the same beats as [`ArrangementSet::render_with_options`]. The
+    /// drawing has no dash or bar characters, so `bar_char`, `dash_style` and
+    /// `empty_string_glyph` do not apply.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`ArrangementSet::render_with_options`].
+    #[cfg_attr(feature = "wasm", wasm_bindgen(js_name = "renderSvg"))]
+    pub fn render_svg(&self, index: usize, options: RenderOptions) -> Result<String, TabError> {
+        let arrangement = self.arrangement_to_render(index, options.width, options.padding)?;
+        Ok(svg::render_tab_svg_with_options(
+            &arrangement.lines,
+            &self.durations,
+            &self.guitar,
+            &options,
+        ))
+    }
+
+    /// Renders the arrangement at `index` as an HTML tab like [`render_tab_html`]. The text
+    /// matches [`ArrangementSet::render_with_options`] for the same `options`, with each beat
+    /// cell wrapped in a span carrying `data-beat` and `data-line`.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`ArrangementSet::render_with_options`].
+    #[cfg_attr(feature = "wasm", wasm_bindgen(js_name = "renderHtml"))]
+    pub fn render_html(&self, index: usize, options: RenderOptions) -> Result<String, TabError> {
+        let arrangement = self.arrangement_to_render(index, options.width, options.padding)?;
+        Ok(html::render_tab_html_with_options(
+            &arrangement.lines,
+            &self.durations,
+            &self.guitar,
+            &options,
+        ))
+    }
+
+    /// Serializes the whole set as a versioned JSON snapshot: the arrangements with their
+    /// fingerings, the guitar, the normalized input and the `TabInput` that generated it.
+    /// [`ArrangementSet::import_json`] reloads it without pathfinding.
+    #[cfg_attr(feature = "wasm", wasm_bindgen(js_name = "exportJson"))]
+    #[must_use]
+    pub fn export_json(&self) -> String {
+        snapshot::to_json(self)
+    }
+
+    /// Serializes the set like [`ArrangementSet::export_json`], as compact CBOR bytes.
+    /// [`ArrangementSet::import_binary`] reloads it.
+    #[cfg_attr(feature = "wasm", wasm_bindgen(js_name = "exportBinary"))]
+    #[must_use]
+    pub fn export_binary(&self) -> Vec<u8> {
+        snapshot::to_binary(self)
+    }
+
+    /// Rebuilds a set from [`ArrangementSet::export_json`] output, without parsing or
+    /// pathfinding.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TabError::SnapshotVersionUnsupported`] for a snapshot in another format
+    /// version, and [`TabError::SnapshotInvalid`] for malformed or inconsistent data.
+    #[cfg_attr(feature = "wasm", wasm_bindgen(js_name = "importJson"))]
+    pub fn import_json(json: &str) -> Result<ArrangementSet, TabError> {
+        snapshot::from_json(json)
+    }
+
+    /// Rebuilds a set from [`ArrangementSet::export_binary`] output, without parsing or
+    /// pathfinding.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`ArrangementSet::import_json`].
+    #[cfg_attr(feature = "wasm", wasm_bindgen(js_name = "importBinary"))]
+    pub fn import_binary(bytes: &[u8]) -> Result<ArrangementSet, TabError> {
+        snapshot::from_binary(bytes)
+    }
+}
+
+impl ArrangementSet {
+    /// The arrangement at `index`, once `width` is checked against `padding`. Shared by the
+    /// render methods so every output format fails the same way.
+    fn arrangement_to_render(
+        &self,
+        index: usize,
+        width: u16,
+        padding: u8,
+    ) -> Result<&arrangement::Arrangement, TabError> {
         let arrangement = self
             .arrangements
             .get(index)
@@ -542,13 +1072,7 @@ impl ArrangementSet {
         if width < min {
             return Err(TabError::RenderWidthTooSmall { width, min });
         }
-        Ok(renderer::render_tab(
-            &arrangement.lines,
-            &self.guitar,
-            width,
-            padding,
-            playback,
-        ))
+        Ok(arrangement)
     }
 }
 
@@ -561,8 +1085,8 @@ impl ArrangementSet {
 ///
 /// - Input-shape validation: [`TabError::NumArrangementsOutOfRange`], [`TabError::TuningNameUnknown`],
 ///   [`TabError::NumFretsTooHigh`], [`TabError::CapoTooHigh`], [`TabError::CapoExceedsFrets`].
-/// - Parser: [`TabError::Parse`] (carries `Vec<ParseError>` with line/text per unparseable substring),
-///   [`TabError::InputTooManyLines`] (input exceeds the 65,535-line cap).
+/// - Parser: [`TabError::Parse`] (carries `Vec<ParseError>` with line/text per unparseable substring,
+///   strict mode only), [`TabError::InputTooManyLines`] (input exceeds the 65,535-line cap).
 /// - Pathfinding: [`TabError::UnplayablePitches`] (one or more pitches reach no string),
 ///   [`TabError::NoArrangementsFound`] (every pitch reaches the guitar but no valid combination exists,
 ///   for example duplicate pitches in a single beat that the no-duplicate-strings constraint filters away).
@@ -577,68 +1101,149 @@ impl ArrangementSet {
 ///
 /// # Validation order
 ///
-/// Input-shape errors (currently `numArrangements` range) are reported before `parse_lines`
+/// Input-shape errors (currently `numArrangements` range) are reported before `parse_input`
 /// runs. The ordering is deliberate: shape checks are O(1) and unambiguous, while parse errors
 /// depend on the full input. When both are present the shape error wins because the parser's
 /// output would be discarded anyway.
 ///
 /// Guitar-configuration errors (`TuningNameUnknown`, `NumFretsTooHigh`, `CapoTooHigh`,
-/// `CapoExceedsFrets`) are checked before the normalized input is built, so an invalid guitar
-/// config does not pay for the per-beat allocation. `parse_lines` still runs first, so a `Parse`
-/// error outranks a guitar-config error.
+/// `CapoExceedsFrets`) are checked before pathfinding runs, so an invalid guitar config does
+/// not pay for the search. `parse_input` still runs first, so a `Parse` error outranks a
+/// guitar-config error.
 ///
 /// # Performance
 ///
-/// `tab_input.input` is cloned once per call because `parse_lines` is `#[memoize]`d on owned
+/// `tab_input.input` is cloned once per call because `parse_input` is `#[memoize]`d on owned
 /// `String`. Memoization makes a repeat call with the same input cheap, but the clone runs
 /// on every call (including cache hits). Hot loops over `generate_arrangements` should expect
 /// one `String::clone` per invocation in addition to the boundary deserialization cost.
-#[wasm_bindgen(js_name = "generateArrangements")]
+#[cfg_attr(feature = "wasm", wasm_bindgen(js_name = "generateArrangements"))]
 pub fn generate_arrangements(tab_input: TabInput) -> Result<ArrangementSet, TabError> {
+    generate(tab_input, true)
+}
+
+/// Body of [`generate_arrangements`]. With `memoized` false it calls the uncached parser and
+/// arranger instead, so a run of one-off inputs such as [`arrange_batch`] neither pays for
+/// the caches nor evicts what they hold.
+pub(crate) fn generate(tab_input: TabInput, memoized: bool) -> Result<ArrangementSet, TabError> {
     let num_arrangements = NumArrangements::try_new(tab_input.num_arrangements)?;
     let difficulty_weights = match tab_input.difficulty_weights {
         Some(raw) => DifficultyWeights::try_new(raw.movement, raw.span, raw.position)?,
         None => DifficultyWeights::standard(),
     };
 
-    let input_lines = parser::parse_lines(tab_input.input.clone())?;
+    let (parsed_lines, parse_warnings) = parse_tab_input(&tab_input, memoized)?;
 
-    // Validate the guitar configuration before materializing the normalized input, so a
-    // request with a valid pitch list but a bad tuning name or out-of-range fret/capo fails
-    // before allocating the per-beat `normalized_input` vector. `parse_lines` still runs
-    // first, so a `Parse` error keeps precedence over a guitar-config error.
+    // Validate the guitar configuration before arranging, so a request with a valid pitch
+    // list but a bad tuning name or out-of-range fret/capo fails before pathfinding runs.
+    // The input is still parsed first, so a `Parse` error keeps precedence over a
+    // guitar-config error.
     let tuning = parser::create_string_tuning_offset(parser::parse_tuning(&tab_input.tuning_name)?);
-    let guitar = Guitar::new(tuning, tab_input.guitar_num_frets, tab_input.guitar_capo)?;
+    let guitar = Guitar::new(tuning, tab_input.guitar_num_frets, tab_input.guitar_capo)?
+        .with_harmonic_mode(tab_input.harmonic_mode);
 
-    let first_playable_index = arrangement::first_playable_index(&input_lines);
-
-    let normalized_input: Vec<NormalizedBeat> = input_lines
-        .iter()
-        .skip(first_playable_index)
-        .map(|line| match line {
-            arrangement::Line::Playable(pitches) => NormalizedBeat::Playable {
-                pitches: pitches.iter().map(|p| p.plain_text().to_owned()).collect(),
-            },
-            arrangement::Line::Rest => NormalizedBeat::Rest,
-            arrangement::Line::MeasureBreak => NormalizedBeat::MeasureBreak,
-        })
-        .collect();
+    let parsed_lines = if tab_input.expand_repeats {
+        arrangement::expand_parsed_repeats(&parsed_lines)
+    } else {
+        parsed_lines
+    };
 
-    let arrangements = arrangement::create_arrangements(
+    let create_arrangements = if memoized {
+        arrangement::create_parsed_arrangements
+    } else {
+        arrangement::memoized_original_create_parsed_arrangements
+    };
+    let arrangements = create_arrangements(
         guitar.clone(),
-        input_lines,
+        parsed_lines.clone(),
         num_arrangements,
         difficulty_weights,
         tab_input.max_fret_span_filter,
     )?;
 
+    // The set describes the lines from the first content line on, like the arrangements.
+    let first_content_index =
+        arrangement::first_content_index(parsed_lines.iter().map(|parsed| &parsed.line));
+    let parsed_lines = &parsed_lines[first_content_index..];
+    let chosen_lines = arrangements
+        .first()
+        .map(|arrangement| arrangement.lines.as_slice())
+        .unwrap_or_default();
+    let normalized_input = parsed_lines
+        .iter()
+        .enumerate()
+        .map(|(line_index, parsed)| normalize_line(parsed, chosen_lines.get(line_index)))
+        .collect();
+
     Ok(ArrangementSet {
         arrangements,
         guitar,
         normalized_input,
+        parse_warnings,
+        input_line_numbers: parsed_lines
+            .iter()
+            .map(|parsed| parsed.input_line)
+            .collect(),
+        durations: parsed_lines.iter().map(|parsed| parsed.duration).collect(),
+        parameters: tab_input,
     })
 }
 
+/// Parses `tab_input.input` with the parser `lenient_parse` picks. The strict parser has no
+/// warnings to return.
+fn parse_tab_input(
+    tab_input: &TabInput,
+    memoized: bool,
+) -> Result<(Vec<ParsedLine>, Vec<ParseError>), TabError> {
+    Ok(match (tab_input.lenient_parse, memoized) {
+        (true, true) => parser::parse_input_lenient(tab_input.input.clone())?,
+        (true, false) => parser::memoized_original_parse_input_lenient(tab_input.input.clone())?,
+        (false, true) => (parser::parse_input(tab_input.input.clone())?, Vec::new()),
+        (false, false) => (
+            parser::memoized_original_parse_input(tab_input.input.clone())?,
+            Vec::new(),
+        ),
+    })
+}
+
+/// Echoes one parsed line as a `NormalizedBeat`. A chord line echoes its symbol with the
+/// voicing `chosen_line` plays, since the parsed close-position pitches are not what gets
+/// played.
+fn normalize_line(
+    parsed: &ParsedLine,
+    chosen_line: Option<&arrangement::Line<BeatVec<PitchFingering>>>,
+) -> NormalizedBeat {
+    match &parsed.line {
+        arrangement::Line::Playable(pitches) => match (parsed.chord, chosen_line) {
+            (Some(chord), Some(arrangement::Line::Playable(fingerings))) => {
+                let mut voicing: Vec<Pitch> =
+                    fingerings.iter().map(|fingering| fingering.pitch).collect();
+                voicing.sort();
+                NormalizedBeat::Playable {
+                    pitches: voicing.iter().map(|p| p.plain_text().to_owned()).collect(),
+                    chord: Some(chord.to_string()),
+                }
+            }
+            (chord, _) => NormalizedBeat::Playable {
+                pitches: pitches.iter().map(|p| p.plain_text().to_owned()).collect(),
+                chord: chord.map_or_else(|| name_chord(pitches), |chord| Some(chord.to_string())),
+            },
+        },
+        arrangement::Line::Rest => NormalizedBeat::Rest,
+        arrangement::Line::MeasureBreak => NormalizedBeat::MeasureBreak,
+        arrangement::Line::RepeatStart => NormalizedBeat::RepeatStart,
+        arrangement::Line::RepeatEnd => NormalizedBeat::RepeatEnd,
+        arrangement::Line::Ending(number) => NormalizedBeat::Ending { number: *number },
+        arrangement::Line::Section(label) => NormalizedBeat::Section {
+            label: label.clone(),
+        },
+        arrangement::Line::Technique(technique) => NormalizedBeat::Technique {
+            technique: *technique,
+        },
+        arrangement::Line::LetRing(beats) => NormalizedBeat::LetRing { beats: *beats },
+    }
+}
+
 #[cfg(test)]
 mod test_generate_arrangements_and_render {
     use super::*;
@@ -653,6 +1258,9 @@ mod test_generate_arrangements_and_render {
             num_arrangements: 1,
             max_fret_span_filter: None,
             difficulty_weights: None,
+            lenient_parse: false,
+            expand_repeats: false,
+            harmonic_mode: HarmonicMode::Off,
         };
         let set = generate_arrangements(tab_input).unwrap();
 
@@ -668,7 +1276,7 @@ mod test_generate_arrangements_and_render {
         let beats = set.normalized_input();
         assert_eq!(beats.len(), 8);
         assert!(
-            matches!(beats[0], NormalizedBeat::Playable { ref pitches } if pitches == &["E2".to_owned()])
+            matches!(beats[0], NormalizedBeat::Playable { ref pitches, .. } if pitches == &["E2".to_owned()])
         );
         assert!(matches!(beats[3], NormalizedBeat::Rest));
         assert!(matches!(beats[6], NormalizedBeat::MeasureBreak));
@@ -684,13 +1292,16 @@ mod test_generate_arrangements_and_render {
             num_arrangements: 2,
             max_fret_span_filter: None,
             difficulty_weights: None,
+            lenient_parse: false,
+            expand_repeats: false,
+            harmonic_mode: HarmonicMode::Off,
         };
         let set = generate_arrangements(tab_input).unwrap();
         assert_eq!(set.len(), 2);
         assert_eq!(set.render(0, 30, 2, Some(3)).unwrap(), "");
         assert_eq!(set.render(1, 30, 2, Some(3)).unwrap(), "");
 
-        // Pins the current behaviour: when no `Playable` line exists, `first_playable_index`
+        // Pins the current behaviour: when no `Playable` line exists, `first_content_index`
         // falls back to 0 and `normalized_input` echoes every input line (the trailing
         // `MeasureBreak` from `---` and the leading blank rests). Empty / all-rest input
         // returns Ok(set) by design (see docs/adr/0006-empty-input-returns-empty-set.md).
@@ -718,6 +1329,9 @@ mod test_generate_arrangements_and_render {
             num_arrangements: 1,
             max_fret_span_filter: None,
             difficulty_weights: None,
+            lenient_parse: false,
+            expand_repeats: false,
+            harmonic_mode: HarmonicMode::Off,
         };
         let err = generate_arrangements(tab_input).unwrap_err();
         match err {
@@ -730,6 +1344,471 @@ mod test_generate_arrangements_and_render {
         }
     }
 
+    #[test]
+    fn lenient_parse_surfaces_warnings_on_set() {
+        let tab_input = TabInput {
+            input: "E2\nA2\nD3\n???\nG3x\nB3\nE4".to_owned(),
+            tuning_name: "standard".to_owned(),
+            guitar_num_frets: 20,
+            guitar_capo: 0,
+            num_arrangements: 1,
+            max_fret_span_filter: None,
+            difficulty_weights: None,
+            lenient_parse: true,
+            expand_repeats: false,
+            harmonic_mode: HarmonicMode::Off,
+        };
+        let set = generate_arrangements(tab_input).unwrap();
+        assert_eq!(set.len(), 1);
+        assert_eq!(
+            set.parse_warnings(),
+            vec![
+                ParseError {
+                    line: 4,
+                    text: "???".to_owned()
+                },
+                ParseError {
+                    line: 5,
+                    text: "x".to_owned()
+                },
+            ]
+        );
+
+        let beats = set.normalized_input();
+        assert_eq!(beats[3], NormalizedBeat::Rest);
+        assert_eq!(
+            beats[4],
+            NormalizedBeat::Playable {
+                pitches: vec!["G3".to_owned()],
+                chord: None,
+            }
+        );
+    }
+
+    #[test]
+    fn strict_parse_has_no_warnings() {
+        let set = generate_arrangements(TabInput::new("E2\nA2", "standard", 18, 0, 1)).unwrap();
+        assert!(set.parse_warnings().is_empty());
+    }
+
+    #[test]
+    fn repeat_markers_flow_through_to_render() {
+        let tab_input = TabInput::new("[Verse]\n|:\nE2\nA2\n:|\nD3", "standard", 18, 0, 1);
+        let set = generate_arrangements(tab_input).unwrap();
+
+        let beats = set.normalized_input();
+        assert_eq!(
+            beats[0],
+            NormalizedBeat::Section {
+                label: "Verse".to_owned()
+            }
+        );
+        assert_eq!(beats[1], NormalizedBeat::RepeatStart);
+        assert_eq!(beats[4], NormalizedBeat::RepeatEnd);
+
+        let tab = set.render(0, 40, 1, None).unwrap();
+        assert!(tab.starts_with(" [Verse]\n"), "got:\n{tab}");
+        assert!(tab.contains("-|:-0---:|"), "got:\n{tab}");
+    }
+
+    #[test]
+    fn techniques_flow_through_to_render() {
+        let tab_input = TabInput::new("E4\np\nD4\n~", "standard", 18, 0, 1);
+        let set = generate_arrangements(tab_input).unwrap();
+
+        let beats = set.normalized_input();
+        assert_eq!(
+            beats[1],
+            NormalizedBeat::Technique {
+                technique: Technique::PullOff
+            }
+        );
+        assert_eq!(
+            beats[3],
+            NormalizedBeat::Technique {
+                technique: Technique::Vibrato
+            }
+        );
+
+        // The pull-off holds both notes on string 2, which is the second row.
+        let tab = set.render(0, 20, 1, None).unwrap();
+        assert_eq!(
+            tab.lines().nth(2),
+            Some("-5p3~---------------"),
+            "got:\n{tab}"
+        );
+    }
+
+    #[test]
+    fn techniques_between_pitches_share_their_input_line() {
+        let tab_input = TabInput::new("E4 h F#4\nG4", "standard", 18, 0, 1);
+        let set = generate_arrangements(tab_input).unwrap();
+
+        assert_eq!(set.input_line_numbers, vec![1, 1, 1, 2]);
+        assert_eq!(
+            set.normalized_input()[1],
+            NormalizedBeat::Technique {
+                technique: Technique::HammerOn
+            }
+        );
+        let tab = set.render(0, 20, 1, None).unwrap();
+        assert!(tab.contains("0h2"), "got:\n{tab}");
+    }
+
+    #[test]
+    fn let_ring_moves_the_next_note_off_the_ringing_string() {
+        let tab_input = TabInput::new("B3\nlet ring\nB3", "standard", 18, 0, 1);
+        let set = generate_arrangements(tab_input).unwrap();
+
+        assert_eq!(
+            set.normalized_input()[1],
+            NormalizedBeat::LetRing { beats: None }
+        );
+        assert!(set.cut_off_notes(0).unwrap().is_empty());
+
+        // The open B string rings while the second B3 moves to fret 4 of the G string.
+        let tab = set.render(0, 20, 1, None).unwrap();
+        assert_eq!(tab.lines().next(), Some(" let ring"), "got:\n{tab}");
+        assert_eq!(
+            tab.lines().nth(3),
+            Some("-0------------------"),
+            "got:\n{tab}"
+        );
+        assert_eq!(
+            tab.lines().nth(4),
+            Some("---4----------------"),
+            "got:\n{tab}"
+        );
+    }
+
+    #[test]
+    fn let_ring_reports_unavoidable_cut_offs() {
+        let tab_input = TabInput::new("E2\nring 1\nE2", "standard", 18, 0, 1);
+        let set = generate_arrangements(tab_input).unwrap();
+        assert_eq!(
+            set.cut_off_notes(0).unwrap(),
+            vec![CutOffNote {
+                pitch: "E2".to_owned(),
+                string: 6,
+                line: 1,
+                cut_off_line: 3,
+            }]
+        );
+        assert!(matches!(
+            set.cut_off_notes(1),
+            Err(TabError::IndexOutOfBounds { index: 1, len: 1 })
+        ));
+    }
+
+    #[test]
+    fn long_let_ring_passages_arrange_promptly() {
+        // Tracking each ringing note separately made the search grow exponentially with the
+        // number of ringing beats. 48 of them took far longer than any test run.
+        let input = ["E3B3", "G3D4", "A3E4", "D3F#4"]
+            .iter()
+            .cycle()
+            .take(48)
+            .map(|beat| format!("{beat}\nring"))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let set = generate_arrangements(TabInput::new(input, "standard", 18, 0, 1)).unwrap();
+        assert!(!set.cut_off_notes(0).unwrap().is_empty());
+    }
+
+    #[test]
+    fn voices_prefer_their_strings() {
+        // Merged into one voice, A3 takes the low fret on the G string.
+        let set = generate_arrangements(TabInput::new("E4A3", "standard", 18, 0, 1)).unwrap();
+        let tab = set.render(0, 20, 1, None).unwrap();
+        assert_eq!(
+            tab.lines().nth(3),
+            Some("-2------------------"),
+            "got:\n{tab}"
+        );
+
+        // As the bass voice, A3 moves to the D string.
+        let set = generate_arrangements(TabInput::new("E4 ; A3", "standard", 18, 0, 1)).unwrap();
+        let tab = set.render(0, 20, 1, None).unwrap();
+        assert_eq!(
+            tab.lines().nth(4),
+            Some("-7------------------"),
+            "got:\n{tab}"
+        );
+    }
+
+    #[test]
+    fn held_voice_reports_cut_offs() {
+        let tab_input = TabInput::new(" ; E2:2\nE2", "standard", 18, 0, 1);
+        let set = generate_arrangements(tab_input).unwrap();
+        assert_eq!(
+            set.cut_off_notes(0).unwrap(),
+            vec![CutOffNote {
+                pitch: "E2".to_owned(),
+                string: 6,
+                line: 1,
+                cut_off_line: 2,
+            }]
+        );
+    }
+
+    #[test]
+    fn voices_follow_expanded_repeats() {
+        let tab_input =
+            TabInput::new("|:\nE4 ; A3\n:|", "standard", 18, 0, 1).with_expand_repeats(true);
+        let set = generate_arrangements(tab_input).unwrap();
+        let tab = set.render(0, 20, 1, None).unwrap();
+        assert_eq!(
+            tab.lines().nth(4),
+            Some("-7-7----------------"),
+            "got:\n{tab}"
+        );
+    }
+
+    #[test]
+    fn chord_lines_echo_the_chosen_voicing() {
+        let tab_input = TabInput::new("[Verse]\n{Am} // strum", "standard", 18, 0, 1);
+        let set = generate_arrangements(tab_input).unwrap();
+        let beats = set.normalized_input();
+        assert!(matches!(&beats[0], NormalizedBeat::Section { label } if label == "Verse"));
+        // The open Am shape, x02210, rather than the parsed close position A2 C3 E3.
+        assert_eq!(
+            beats[1],
+            NormalizedBeat::Playable {
+                pitches: ["A2", "E3", "A3", "C4", "E4"].map(str::to_owned).to_vec(),
+                chord: Some("Am".to_owned()),
+            }
+        );
+    }
+
+    #[test]
+    fn layout_reports_input_lines() {
+        let tab_input = TabInput::new("\n-\nE2\n[Intro]\nA2", "standard", 18, 0, 1);
+        let set = generate_arrangements(tab_input).unwrap();
+        let rendered = set
+            .render_with_layout(0, RenderOptions::new(20, 1).with_playback(1))
+            .unwrap();
+        assert_eq!(rendered.tab, set.render(0, 20, 1, Some(1)).unwrap());
+        assert_eq!(
+            rendered
+                .beats
+                .iter()
+                .map(|beat| beat.line)
+                .collect::<Vec<_>>(),
+            vec![3, 5]
+        );
+
+        let tab_input =
+            TabInput::new("|:\nE2\nA2\n:|\nD3", "standard", 18, 0, 1).with_expand_repeats(true);
+        let set = generate_arrangements(tab_input).unwrap();
+        let rendered = set
+            .render_with_layout(0, RenderOptions::new(40, 1))
+            .unwrap();
+        assert_eq!(
+            rendered
+                .beats
+                .iter()
+                .map(|beat| beat.line)
+                .collect::<Vec<_>>(),
+            vec![2, 3, 2, 3, 5]
+        );
+    }
+
+    #[test]
+    fn labels_render_under_a_header() {
+        let tab_input = TabInput::new("E3\n\nA3", "openG", 18, 2, 1);
+        let set = generate_arrangements(tab_input).unwrap();
+        let rendered = set.render_with_labels(0, 20, 1, None).unwrap();
+        let (header, tab) = rendered.split_once("\n\n").unwrap();
+        assert!(header.starts_with("Tuning: D G D G B D\nCapo: 2\nDifficulty: "));
+        assert!(tab.starts_with("   1\n\nd|---"));
+        assert!(tab.contains("\nd|-0---"));
+        assert_eq!(
+            set.render_with_labels(0, 1, 1, None),
+            Err(TabError::RenderWidthTooSmall {
+                width: 1,
+                min: renderer::min_render_width(1),
+            })
+        );
+    }
+
+    #[test]
+    fn options_render_like_the_positional_arguments() {
+        let set = generate_arrangements(TabInput::new("E2\nA2", "standard", 18, 0, 1)).unwrap();
+        let options = RenderOptions::new(20, 1).with_playback(1);
+        assert_eq!(
+            set.render_with_options(0, options),
+            set.render(0, 20, 1, Some(1))
+        );
+        let unicode = set
+            .render_with_options(0, options.with_dash_style(DashStyle::Unicode))
+            .unwrap();
+        assert!(unicode.contains("─0───────"));
+        assert_eq!(
+            set.render_with_options(0, RenderOptions::new(1, 1)),
+            Err(TabError::RenderWidthTooSmall {
+                width: 1,
+                min: renderer::min_render_width(1),
+            })
+        );
+        assert_eq!(
+            set.render_with_options(1, options),
+            Err(TabError::IndexOutOfBounds { index: 1, len: 1 })
+        );
+    }
+
+    #[test]
+    fn durations_space_every_render() {
+        let tab_input = TabInput::new("E4 @h\nF4 @q\n\nG4", "standard", 18, 0, 1);
+        let set = generate_arrangements(tab_input).unwrap();
+        let options = RenderOptions::new(20, 1).with_rhythm_row(true);
+        let tab = set.render_with_options(0, options).unwrap();
+        // The rest and the last beat keep the quarter note before them.
+        assert!(tab.starts_with(" h   q q q\n\n-0---1---3----------\n"));
+        let rendered = set.render_with_layout(0, options).unwrap();
+        assert_eq!(rendered.tab, tab);
+        assert_eq!(
+            rendered
+                .beats
+                .iter()
+                .map(|beat| beat.start.column_index)
+                .collect::<Vec<_>>(),
+            vec![1, 5, 7, 9]
+        );
+    }
+
+    #[test]
+    fn every_format_follows_the_render_options() {
+        let tab_input = TabInput::new(
+            "{Am}
+-
+E4",
+            "standard",
+            18,
+            0,
+            1,
+        );
+        let set = generate_arrangements(tab_input).unwrap();
+        let options = RenderOptions::new(30, 1)
+            .with_label_mode(LabelMode::All)
+            .with_dash_style(DashStyle::Unicode)
+            .with_bar_char('‖');
+        let tab = set.render_with_options(0, options).unwrap();
+        assert!(tab.contains("‖"), "got:\n{tab}");
+
+        let rendered = set.render_with_layout(0, options).unwrap();
+        assert_eq!(rendered.tab, tab);
+        // String labels shift the beats right, and positions count characters.
+        let first_beat = &rendered.beats[0];
+        let first_row = rendered
+            .tab
+            .lines()
+            .nth(rendered.row_group_lines[0])
+            .unwrap();
+        let cell: String = first_row
+            .chars()
+            .skip(first_beat.start.column_index)
+            .take(first_beat.end_column_index - first_beat.start.column_index)
+            .collect();
+        assert_eq!(cell, "0");
+        assert!(first_row.starts_with("e|"), "got:\n{tab}");
+
+        let svg = set.render_svg(0, options).unwrap();
+        assert!(svg.contains("class=\"chord-name\""));
+        assert!(svg.contains("class=\"string-label\""));
+        assert!(svg.contains("class=\"measure-number\""));
+        let html = set.render_html(0, options).unwrap();
+        assert!(
+            html.starts_with("<pre class=\"tab\">   1 2\n   Am\n"),
+            "got:\n{html}"
+        );
+        assert!(html.contains("\ne|─<span class=\"beat\" data-beat=\"0\""));
+        assert!(html.contains("─‖─"));
+    }
+
+    #[test]
+    fn harmonics_reach_pitches_above_the_fretboard() {
+        // E6 sits past fret 18 on every string, so only a natural harmonic reaches it.
+        let fretted_only = TabInput::new("E6", "standard", 18, 0, 1);
+        assert!(matches!(
+            generate_arrangements(fretted_only),
+            Err(TabError::UnplayablePitches { .. })
+        ));
+
+        let tab_input =
+            TabInput::new("E6", "standard", 18, 0, 1).with_harmonic_mode(HarmonicMode::Natural);
+        let set = generate_arrangements(tab_input).unwrap();
+        let tab = set.render(0, 20, 1, None).unwrap();
+        assert!(tab.contains("-<5>-"), "got:\n{tab}");
+
+        // A6 needs an artificial harmonic over A5 at fret 17 of the high E string.
+        let tab_input = TabInput::new("A6", "standard", 18, 0, 1)
+            .with_harmonic_mode(HarmonicMode::NaturalAndArtificial);
+        let set = generate_arrangements(tab_input).unwrap();
+        let tab = set.render(0, 20, 1, None).unwrap();
+        assert!(tab.contains("-17*-"), "got:\n{tab}");
+    }
+
+    #[test]
+    fn fretted_notes_win_over_costlier_harmonics() {
+        // E5 is fret 12 fretted or fret 12 as a harmonic. The harmonic carries a penalty.
+        let tab_input =
+            TabInput::new("E5", "standard", 18, 0, 1).with_harmonic_mode(HarmonicMode::Natural);
+        let set = generate_arrangements(tab_input).unwrap();
+        let tab = set.render(0, 20, 1, None).unwrap();
+        assert!(!tab.contains('<'), "got:\n{tab}");
+    }
+
+    #[test]
+    fn expand_repeats_unrolls_before_arranging() {
+        let tab_input =
+            TabInput::new("|:\nE2\nA2\n:|\nD3", "standard", 18, 0, 1).with_expand_repeats(true);
+        let set = generate_arrangements(tab_input).unwrap();
+
+        let beats = set.normalized_input();
+        assert_eq!(beats.len(), 5);
+        assert!(
+            beats
+                .iter()
+                .all(|b| matches!(b, NormalizedBeat::Playable { .. }))
+        );
+        assert!(!set.render(0, 40, 1, None).unwrap().contains(':'));
+    }
+
+    #[test]
+    fn annotations_follow_expanded_repeats() {
+        let tab_input = TabInput::new("[Intro]\n|:\n{Am} @h\n:|\nE2 @q", "standard", 18, 0, 1)
+            .with_expand_repeats(true);
+        let set = generate_arrangements(tab_input).unwrap();
+        assert_eq!(set.input_line_numbers, [1, 3, 3, 5]);
+        let half = Duration::from_symbol("h");
+        assert_eq!(
+            set.durations,
+            [None, half, half, Duration::from_symbol("q")]
+        );
+        let beats = set.normalized_input();
+        for beat in &beats[1..3] {
+            assert!(
+                matches!(beat, NormalizedBeat::Playable { chord, .. } if chord.as_deref() == Some("Am"))
+            );
+        }
+        assert!(
+            matches!(&beats[3], NormalizedBeat::Playable { pitches, .. } if pitches == &["E2".to_owned()])
+        );
+
+        // Errors name the input line, not the line's place in the expansion.
+        let tab_input =
+            TabInput::new("|:\nE2\n:|\nA1", "standard", 18, 0, 1).with_expand_repeats(true);
+        assert_eq!(
+            generate_arrangements(tab_input).unwrap_err(),
+            TabError::UnplayablePitches {
+                pitches: vec![UnplayablePitch {
+                    value: "A1".to_owned(),
+                    line: 4,
+                }],
+            }
+        );
+    }
+
     #[test]
     fn num_arrangements_zero_is_invalid() {
         let tab_input = TabInput {
@@ -740,6 +1819,9 @@ mod test_generate_arrangements_and_render {
             num_arrangements: 0,
             max_fret_span_filter: None,
             difficulty_weights: None,
+            lenient_parse: false,
+            expand_repeats: false,
+            harmonic_mode: HarmonicMode::Off,
         };
         let err = generate_arrangements(tab_input).unwrap_err();
         match err {
@@ -761,6 +1843,9 @@ mod test_generate_arrangements_and_render {
             num_arrangements: 21,
             max_fret_span_filter: None,
             difficulty_weights: None,
+            lenient_parse: false,
+            expand_repeats: false,
+            harmonic_mode: HarmonicMode::Off,
         };
         let err = generate_arrangements(tab_input).unwrap_err();
         match err {
@@ -782,6 +1867,9 @@ mod test_generate_arrangements_and_render {
             num_arrangements: 1,
             max_fret_span_filter: None,
             difficulty_weights: None,
+            lenient_parse: false,
+            expand_repeats: false,
+            harmonic_mode: HarmonicMode::Off,
         };
         let err = generate_arrangements(tab_input).unwrap_err();
         match err {
@@ -803,6 +1891,9 @@ mod test_generate_arrangements_and_render {
             num_arrangements: 1,
             max_fret_span_filter: None,
             difficulty_weights: None,
+            lenient_parse: false,
+            expand_repeats: false,
+            harmonic_mode: HarmonicMode::Off,
         };
         let err = generate_arrangements(tab_input).unwrap_err();
         match err {
@@ -828,6 +1919,9 @@ mod test_generate_arrangements_and_render {
             num_arrangements: 1,
             max_fret_span_filter: None,
             difficulty_weights: None,
+            lenient_parse: false,
+            expand_repeats: false,
+            harmonic_mode: HarmonicMode::Off,
         };
         let err = generate_arrangements(tab_input).unwrap_err();
         match err {
@@ -850,6 +1944,9 @@ mod test_generate_arrangements_and_render {
             num_arrangements: 1,
             max_fret_span_filter: None,
             difficulty_weights: None,
+            lenient_parse: false,
+            expand_repeats: false,
+            harmonic_mode: HarmonicMode::Off,
         };
         let set = generate_arrangements(tab_input).unwrap();
         let narrow = set.render(0, 12, 1, None).unwrap();
@@ -875,6 +1972,29 @@ mod test_boundary_types {
         assert!(matches!(beats[0], NormalizedBeat::Playable { .. }));
     }
 
+    #[test]
+    fn arrangement_set_lines_parallel_normalized_input() {
+        let set = arrangement_set_fixture(1);
+        let lines = set.lines(0).unwrap();
+        assert_eq!(lines.len(), set.normalized_input().len());
+        assert_eq!(
+            lines[0],
+            ArrangementLine::Playable {
+                fingerings: vec![NoteFingering {
+                    string: 6,
+                    fret: 0,
+                    pitch: "E2".to_owned(),
+                    harmonic: None,
+                    voice: None,
+                }],
+            }
+        );
+        assert_eq!(
+            set.lines(1).unwrap_err(),
+            TabError::IndexOutOfBounds { index: 1, len: 1 }
+        );
+    }
+
     #[test]
     fn arrangement_set_render_returns_string_for_in_bounds_index() {
         let set = arrangement_set_fixture(1);
@@ -956,6 +2076,9 @@ mod test_boundary_types {
             num_arrangements: 5,
             max_fret_span_filter: Some(0),
             difficulty_weights: None,
+            lenient_parse: false,
+            expand_repeats: false,
+            harmonic_mode: HarmonicMode::Off,
         };
         let set = generate_arrangements(tab_input).unwrap();
         assert!(set.is_empty());
@@ -975,6 +2098,9 @@ mod test_boundary_types {
             num_arrangements: 5,
             max_fret_span_filter: Some(0),
             difficulty_weights: None,
+            lenient_parse: false,
+            expand_repeats: false,
+            harmonic_mode: HarmonicMode::Off,
         };
         let set = generate_arrangements(tab_input).unwrap();
         assert!(set.is_empty());
@@ -994,6 +2120,9 @@ mod test_boundary_types {
             num_arrangements,
             max_fret_span_filter: None,
             difficulty_weights: None,
+            lenient_parse: false,
+            expand_repeats: false,
+            harmonic_mode: HarmonicMode::Off,
         };
         generate_arrangements(tab_input).unwrap()
     }
@@ -1014,16 +2143,42 @@ mod test_boundary_types {
         assert_eq!(input.guitar_num_frets, 18);
         assert_eq!(input.num_arrangements, 1);
         assert!(input.max_fret_span_filter.is_none());
+        assert_eq!(input.harmonic_mode, HarmonicMode::Off);
+    }
+
+    #[test]
+    fn tab_input_deserializes_harmonic_mode() {
+        let json = r#"{
+            "input": "E6",
+            "tuningName": "standard",
+            "guitarNumFrets": 18,
+            "guitarCapo": 0,
+            "numArrangements": 1,
+            "harmonicMode": "naturalAndArtificial"
+        }"#;
+        let input: TabInput = serde_json::from_str(json).unwrap();
+        assert_eq!(input.harmonic_mode, HarmonicMode::NaturalAndArtificial);
     }
 
     #[test]
     fn normalized_beat_serializes_with_kind_discriminant() {
         let playable = NormalizedBeat::Playable {
             pitches: vec!["E2".to_owned()],
+            chord: None,
         };
         let json = serde_json::to_string(&playable).unwrap();
         assert_eq!(json, r#"{"kind":"playable","pitches":["E2"]}"#);
 
+        let chord = NormalizedBeat::Playable {
+            pitches: vec!["A2".to_owned(), "C3".to_owned(), "E3".to_owned()],
+            chord: Some("Am".to_owned()),
+        };
+        let json = serde_json::to_string(&chord).unwrap();
+        assert_eq!(
+            json,
+            r#"{"kind":"playable","pitches":["A2","C3","E3"],"chord":"Am"}"#
+        );
+
         let rest = NormalizedBeat::Rest;
         let json = serde_json::to_string(&rest).unwrap();
         assert_eq!(json, r#"{"kind":"rest"}"#);
@@ -1031,6 +2186,12 @@ mod test_boundary_types {
         let mb = NormalizedBeat::MeasureBreak;
         let json = serde_json::to_string(&mb).unwrap();
         assert_eq!(json, r#"{"kind":"measureBreak"}"#);
+
+        let technique = NormalizedBeat::Technique {
+            technique: Technique::HammerOn,
+        };
+        let json = serde_json::to_string(&technique).unwrap();
+        assert_eq!(json, r#"{"kind":"technique","technique":"hammerOn"}"#);
     }
 }
 
@@ -1091,6 +2252,13 @@ mod test_tab_input {
         assert_eq!(input.max_fret_span_filter, None);
     }
 
+    #[test]
+    fn new_defaults_lenient_parse_to_false() {
+        let input = TabInput::new("E2", "standard", 18, 0, 1);
+        assert!(!input.lenient_parse);
+        assert!(input.with_lenient_parse(true).lenient_parse);
+    }
+
     #[test]
     fn with_max_fret_span_filter_sets_some() {
         let input = TabInput::new("E2", "standard", 18, 0, 1).with_max_fret_span_filter(5);
@@ -0,0 +1,690 @@
+//! The `guitar-tab-lsp` language server for the pitch input format, built with the `lsp`
+//! feature.
+//!
+//! Each open document is arranged on every change, the way `generate_arrangements` would with
+//! a lenient parse, so an editor shows problems while the file is typed:
+//!
+//! - Diagnostics for each unparseable substring and each unplayable pitch, at its line.
+//! - Completion of pitch names, and of tuning names after a `// tuning:` directive.
+//! - Hover over a line to see where the easiest arrangement plays each of its notes.
+//! - Code actions that transpose the line under the cursor up or down an octave.
+//!
+//! The parser skips comments, so the guitar is configured by comment directives that only
+//! the server reads: `// tuning: dropD`, `// capo: 2` and `// frets: 20`. Without them the
+//! document is arranged for a standard-tuned guitar with 18 frets and no capo.
+
+use crate::{
+    ArrangementLine, ArrangementSet, Harmonic, Pitch, TabError, TabInput, generate_arrangements,
+    get_tuning_names, parser,
+};
+use lsp_server::{Connection, ErrorCode, Message, Notification, Request, Response};
+use lsp_types::{
+    CodeAction, CodeActionKind, CodeActionOrCommand, CodeActionParams,
+    CodeActionProviderCapability, CompletionItem, CompletionItemKind, CompletionOptions,
+    CompletionParams, CompletionResponse, Diagnostic, DiagnosticSeverity,
+    DidChangeTextDocumentParams, DidCloseTextDocumentParams, DidOpenTextDocumentParams, Hover,
+    HoverContents, HoverParams, HoverProviderCapability, MarkupContent, MarkupKind, Position,
+    PublishDiagnosticsParams, Range, ServerCapabilities, TextDocumentSyncCapability,
+    TextDocumentSyncKind, TextEdit, Uri, WorkspaceEdit,
+    notification::{
+        DidChangeTextDocument, DidCloseTextDocument, DidOpenTextDocument, Notification as _,
+        PublishDiagnostics,
+    },
+    request::{CodeActionRequest, Completion, HoverRequest, Request as _},
+};
+use serde::de::DeserializeOwned;
+use std::{collections::HashMap, error::Error, str::FromStr};
+use strum::IntoEnumIterator;
+
+/// The source named on every diagnostic.
+const SOURCE: &str = "guitar-tab";
+
+/// The `// key: value` directive on `line`, if its comment holds one.
+fn directive(line: &str) -> Option<(&str, &str)> {
+    let (_, comment) = line.split_once("//")?;
+    let (key, value) = comment.split_once(':')?;
+    Some((key.trim(), value.trim()))
+}
+
+/// The `TabInput` a document is arranged with, and a diagnostic for each directive whose
+/// value is not a number.
+fn read_directives(text: &str) -> (TabInput, Vec<Diagnostic>) {
+    let mut tab_input = TabInput::new(text, "standard", 18, 0, 1).with_lenient_parse(true);
+    let mut diagnostics = Vec::new();
+    for (line_index, line) in text.lines().enumerate() {
+        let Some((key, value)) = directive(line) else {
+            continue;
+        };
+        let number = match key.to_ascii_lowercase().as_str() {
+            "tuning" => {
+                tab_input.tuning_name = value.to_owned();
+                continue;
+            }
+            "capo" => &mut tab_input.guitar_capo,
+            "frets" => &mut tab_input.guitar_num_frets,
+            _ => continue,
+        };
+        match value.parse() {
+            Ok(value) => *number = value,
+            Err(_) => diagnostics.push(error_at(
+                line_range(line_index, line),
+                format!("The {key} directive expects a number, not {value:?}."),
+            )),
+        }
+    }
+    (tab_input, diagnostics)
+}
+#[cfg(test)]
+mod test_read_directives {
+    use super::*;
+
+    #[test]
+    fn configures_the_guitar() {
+        let (tab_input, diagnostics) =
+            read_directives("// tuning: dropD\nE2 // capo: 2\n//FRETS:20\n// notes: slow");
+        assert_eq!(tab_input.tuning_name, "dropD");
+        assert_eq!((tab_input.guitar_capo, tab_input.guitar_num_frets), (2, 20));
+        assert!(tab_input.lenient_parse);
+        assert!(diagnostics.is_empty());
+    }
+    #[test]
+    fn flags_bad_numbers() {
+        let (tab_input, diagnostics) = read_directives("E2\n// capo: two");
+        assert_eq!(tab_input.guitar_capo, 0);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].range.start.line, 1);
+    }
+}
+
+/// A document's diagnostics, and its arrangement set when it arranges.
+fn analyze(text: &str) -> (Vec<Diagnostic>, Option<ArrangementSet>) {
+    let (tab_input, mut diagnostics) = read_directives(text);
+    let lines: Vec<&str> = text.lines().collect();
+    let text_range = |line_number: u32, text: &str| {
+        let line_index = line_number.saturating_sub(1) as usize;
+        let line = lines.get(line_index).copied().unwrap_or_default();
+        match line.find(text) {
+            Some(start) => Range::new(
+                Position::new(line_index as u32, utf16_column(line, start)),
+                Position::new(line_index as u32, utf16_column(line, start + text.len())),
+            ),
+            None => line_range(line_index, line),
+        }
+    };
+    // Only the guitar directives can make a configuration error, so it is reported on them.
+    let directive_range = || {
+        lines
+            .iter()
+            .position(|line| directive(line).is_some())
+            .map_or_else(
+                || line_range(0, lines.first().copied().unwrap_or_default()),
+                |line_index| line_range(line_index, lines[line_index]),
+            )
+    };
+
+    if let Ok((_, warnings)) = parser::parse_lines_lenient(text.to_owned()) {
+        diagnostics.extend(
+            warnings.iter().map(|warning| {
+                error_at(text_range(warning.line, &warning.text), warning.to_string())
+            }),
+        );
+    }
+    match generate_arrangements(tab_input) {
+        Ok(set) => return (diagnostics, Some(set)),
+        Err(TabError::UnplayablePitches { pitches }) => {
+            diagnostics.extend(
+                pitches
+                    .iter()
+                    .map(|pitch| error_at(text_range(pitch.line, &pitch.value), pitch.to_string())),
+            );
+        }
+        Err(
+            error @ (TabError::TuningNameUnknown { .. }
+            | TabError::NumFretsTooHigh { .. }
+            | TabError::CapoTooHigh { .. }
+            | TabError::CapoExceedsFrets { .. }),
+        ) => diagnostics.push(error_at(directive_range(), error.to_string())),
+        Err(error) => diagnostics.push(error_at(
+            line_range(0, lines.first().copied().unwrap_or_default()),
+            error.to_string(),
+        )),
+    }
+    (diagnostics, None)
+}
+#[cfg(test)]
+mod test_analyze {
+    use super::*;
+
+    #[test]
+    fn clean_document() {
+        let (diagnostics, set) = analyze("E2\nA2\n");
+        assert!(diagnostics.is_empty());
+        assert_eq!(set.unwrap().len(), 1);
+    }
+    #[test]
+    fn parse_errors_and_unplayable_pitches_at_their_text() {
+        let (diagnostics, set) = analyze("E2 xyz\nC2\n");
+        assert!(set.is_none());
+        let ranges: Vec<Range> = diagnostics
+            .iter()
+            .map(|diagnostic| diagnostic.range)
+            .collect();
+        assert_eq!(
+            ranges,
+            [
+                Range::new(Position::new(0, 3), Position::new(0, 6)),
+                Range::new(Position::new(1, 0), Position::new(1, 2)),
+            ]
+        );
+        assert!(diagnostics[1].message.starts_with("Pitch C2 on line 2"));
+        assert_eq!(diagnostics[0].source.as_deref(), Some(SOURCE));
+    }
+    #[test]
+    fn configuration_errors_on_the_directive() {
+        let (diagnostics, _) = analyze("E2\n// tuning: openZ\n");
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].range.start.line, 1);
+        assert!(diagnostics[0].message.contains("openZ"));
+    }
+}
+
+/// Hover text for the line at `line_index`: where the easiest arrangement plays its notes.
+fn hover_text(set: &ArrangementSet, line_index: u32) -> Option<String> {
+    let lines = set.lines(0).ok()?;
+    let notes: Vec<String> = set
+        .input_line_numbers
+        .iter()
+        .zip(&lines)
+        .filter(|(input_line, _)| **input_line == line_index + 1)
+        .filter_map(|(_, line)| match line {
+            ArrangementLine::Playable { fingerings } => Some(fingerings),
+            _ => None,
+        })
+        .flatten()
+        .map(|note| {
+            let harmonic = match note.harmonic {
+                Some(Harmonic::Natural) => " (natural harmonic)",
+                Some(Harmonic::Artificial) => " (artificial harmonic)",
+                None => "",
+            };
+            format!(
+                "- `{}`: string {}, fret {}{harmonic}",
+                note.pitch, note.string, note.fret
+            )
+        })
+        .collect();
+    (!notes.is_empty()).then(|| notes.join("\n"))
+}
+#[cfg(test)]
+mod test_hover_text {
+    use super::*;
+
+    #[test]
+    fn lists_each_note() {
+        let (_, set) = analyze("\nE2\n\nA2B3\n");
+        let set = set.unwrap();
+        assert_eq!(hover_text(&set, 1).unwrap(), "- `E2`: string 6, fret 0");
+        assert_eq!(
+            hover_text(&set, 3).unwrap(),
+            "- `A2`: string 5, fret 0\n- `B3`: string 2, fret 0"
+        );
+        assert_eq!(hover_text(&set, 0), None);
+        assert_eq!(hover_text(&set, 2), None);
+    }
+    #[test]
+    fn lists_every_beat_of_a_technique_line() {
+        let (_, set) = analyze("E4 h F#4\n");
+        let hover = hover_text(&set.unwrap(), 0).unwrap();
+        assert_eq!(hover.lines().count(), 2, "got:\n{hover}");
+        assert!(hover.starts_with("- `E4`"), "got:\n{hover}");
+    }
+}
+
+/// Completions for a line whose text before the cursor is `prefix`.
+fn completions(prefix: &str) -> Vec<CompletionItem> {
+    let item = |label: String, kind: CompletionItemKind, sort_text: String| CompletionItem {
+        label,
+        kind: Some(kind),
+        sort_text: Some(sort_text),
+        ..CompletionItem::default()
+    };
+    if let Some((_, comment)) = prefix.split_once("//") {
+        return match comment.split_once(':') {
+            Some((key, _)) if key.trim().eq_ignore_ascii_case("tuning") => {
+                std::iter::once("standard".to_owned())
+                    .chain(get_tuning_names().into_iter().map(|name| {
+                        serde_json::to_value(name)
+                            .ok()
+                            .and_then(|name| name.as_str().map(str::to_owned))
+                            .expect("BUG: a tuning name serializes to a string")
+                    }))
+                    .enumerate()
+                    .map(|(index, name)| {
+                        item(name, CompletionItemKind::ENUM_MEMBER, format!("{index:02}"))
+                    })
+                    .collect()
+            }
+            Some(_) => Vec::new(),
+            None => ["tuning", "capo", "frets"]
+                .into_iter()
+                .enumerate()
+                .map(|(index, key)| {
+                    item(
+                        format!("{key}: "),
+                        CompletionItemKind::KEYWORD,
+                        index.to_string(),
+                    )
+                })
+                .collect(),
+        };
+    }
+    Pitch::iter()
+        .map(|pitch| {
+            item(
+                pitch.plain_text().to_owned(),
+                CompletionItemKind::VALUE,
+                format!("{:03}", pitch.index()),
+            )
+        })
+        .collect()
+}
+#[cfg(test)]
+mod test_completions {
+    use super::*;
+
+    fn labels(prefix: &str) -> Vec<String> {
+        completions(prefix)
+            .into_iter()
+            .map(|item| item.label)
+            .collect()
+    }
+
+    #[test]
+    fn pitches_outside_comments() {
+        let labels = labels("E2 ");
+        assert!(labels.contains(&"Db4".to_owned()));
+        assert!(labels.contains(&"E2".to_owned()));
+    }
+    #[test]
+    fn directives_and_tuning_names_in_comments() {
+        assert_eq!(labels("// "), ["tuning: ", "capo: ", "frets: "]);
+        let tunings = labels("// tuning: d");
+        assert_eq!(tunings[0], "standard");
+        assert!(tunings.contains(&"dropD".to_owned()));
+        assert!(labels("// capo: ").is_empty());
+    }
+}
+
+/// `line` with each pitch moved by `octaves`, keeping its spelling. `None` when the line has
+/// no pitch to move, is a chord symbol or marker, or would leave the supported pitch range.
+fn transpose_line(line: &str, octaves: i8) -> Option<String> {
+    if parser::is_symbol_line(line) {
+        return None;
+    }
+    let content_end = line.find("//").unwrap_or(line.len());
+    let mut transposed = String::with_capacity(line.len());
+    let mut copied_to = 0;
+    for token in parser::PITCH_REGEX.find_iter(&line[..content_end]) {
+        let (name, octave) = token.as_str().split_at(token.len() - 1);
+        let octave = octave.parse::<i8>().ok()? + octaves;
+        if !(0..=9).contains(&octave) {
+            return None;
+        }
+        let plain_name = name.replace('♯', "#").replace('♭', "b");
+        Pitch::from_str(&format!("{plain_name}{octave}")).ok()?;
+        transposed.push_str(&line[copied_to..token.start()]);
+        transposed.push_str(&format!("{name}{octave}"));
+        copied_to = token.end();
+    }
+    if copied_to == 0 {
+        return None;
+    }
+    transposed.push_str(&line[copied_to..]);
+    Some(transposed)
+}
+#[cfg(test)]
+mod test_transpose_line {
+    use super::*;
+
+    #[test]
+    fn moves_every_pitch() {
+        assert_eq!(
+            transpose_line("E2 Db3 ; G3:2 @q // E2", 1).unwrap(),
+            "E3 Db4 ; G4:2 @q // E2"
+        );
+        assert_eq!(transpose_line("E3B♭3", -1).unwrap(), "E2B♭2");
+    }
+    #[test]
+    fn leaves_other_lines() {
+        assert_eq!(transpose_line("{A7}", 1), None);
+        assert_eq!(transpose_line("[A7]", 1), None);
+        assert_eq!(transpose_line("ring 2", 1), None);
+        assert_eq!(transpose_line("// E2", 1), None);
+        assert_eq!(transpose_line("C0", -1), None);
+    }
+}
+
+/// The transpose actions for the line at `line_index`.
+fn code_actions(uri: &Uri, text: &str, line_index: u32) -> Vec<CodeActionOrCommand> {
+    let Some(line) = text.lines().nth(line_index as usize) else {
+        return Vec::new();
+    };
+    [(1, "up"), (-1, "down")]
+        .into_iter()
+        .filter_map(|(octaves, direction)| {
+            let new_text = transpose_line(line, octaves)?;
+            let edit = TextEdit::new(line_range(line_index as usize, line), new_text);
+            Some(CodeActionOrCommand::CodeAction(CodeAction {
+                title: format!("Transpose this line {direction} an octave"),
+                kind: Some(CodeActionKind::REFACTOR_REWRITE),
+                edit: Some(WorkspaceEdit {
+                    changes: Some(HashMap::from([(uri.clone(), vec![edit])])),
+                    ..WorkspaceEdit::default()
+                }),
+                ..CodeAction::default()
+            }))
+        })
+        .collect()
+}
+
+fn error_at(range: Range, message: String) -> Diagnostic {
+    Diagnostic {
+        range,
+        severity: Some(DiagnosticSeverity::ERROR),
+        source: Some(SOURCE.to_owned()),
+        message,
+        ..Diagnostic::default()
+    }
+}
+
+fn line_range(line_index: usize, line: &str) -> Range {
+    Range::new(
+        Position::new(line_index as u32, 0),
+        Position::new(line_index as u32, utf16_column(line, line.len())),
+    )
+}
+
+/// The UTF-16 column, which LSP positions count in, of byte `index` of `line`.
+fn utf16_column(line: &str, index: usize) -> u32 {
+    line[..index].encode_utf16().count() as u32
+}
+
+/// The byte index of UTF-16 `column` of `line`, clamped to the line.
+fn byte_index(line: &str, column: u32) -> usize {
+    let mut units = 0;
+    for (index, character) in line.char_indices() {
+        if units >= column as usize {
+            return index;
+        }
+        units += character.len_utf16();
+    }
+    line.len()
+}
+
+/// An open document, arranged as of its last change.
+struct Document {
+    text: String,
+    set: Option<ArrangementSet>,
+}
+
+/// The open documents, by URI.
+#[derive(Default)]
+struct Server {
+    documents: HashMap<Uri, Document>,
+}
+
+impl Server {
+    /// Updates the documents for `notification` and returns the notifications to send back.
+    fn handle_notification(&mut self, notification: Notification) -> Vec<Notification> {
+        let (uri, text, version) = match notification.method.as_str() {
+            DidOpenTextDocument::METHOD => {
+                let Ok(params) = parse_params::<DidOpenTextDocumentParams>(notification.params)
+                else {
+                    return Vec::new();
+                };
+                let document = params.text_document;
+                (document.uri, Some(document.text), Some(document.version))
+            }
+            DidChangeTextDocument::METHOD => {
+                let Ok(mut params) =
+                    parse_params::<DidChangeTextDocumentParams>(notification.params)
+                else {
+                    return Vec::new();
+                };
+                // Full sync: the last change holds the whole document.
+                let Some(change) = params.content_changes.pop() else {
+                    return Vec::new();
+                };
+                let document = params.text_document;
+                (document.uri, Some(change.text), Some(document.version))
+            }
+            DidCloseTextDocument::METHOD => {
+                let Ok(params) = parse_params::<DidCloseTextDocumentParams>(notification.params)
+                else {
+                    return Vec::new();
+                };
+                (params.text_document.uri, None, None)
+            }
+            _ => return Vec::new(),
+        };
+
+        let diagnostics = match text {
+            Some(text) => {
+                let (diagnostics, set) = analyze(&text);
+                self.documents.insert(uri.clone(), Document { text, set });
+                diagnostics
+            }
+            None => {
+                self.documents.remove(&uri);
+                Vec::new()
+            }
+        };
+        vec![Notification::new(
+            PublishDiagnostics::METHOD.to_owned(),
+            PublishDiagnosticsParams::new(uri, diagnostics, version),
+        )]
+    }
+
+    fn handle_request(&self, request: Request) -> Response {
+        let id = request.id.clone();
+        let result = match request.method.as_str() {
+            HoverRequest::METHOD => parse_params::<HoverParams>(request.params).map(|params| {
+                let position = params.text_document_position_params;
+                let hover = self
+                    .documents
+                    .get(&position.text_document.uri)
+                    .and_then(|document| document.set.as_ref())
+                    .and_then(|set| hover_text(set, position.position.line))
+                    .map(|value| Hover {
+                        contents: HoverContents::Markup(MarkupContent {
+                            kind: MarkupKind::Markdown,
+                            value,
+                        }),
+                        range: None,
+                    });
+                serde_json::to_value(hover)
+            }),
+            Completion::METHOD => parse_params::<CompletionParams>(request.params).map(|params| {
+                let position = params.text_document_position;
+                let prefix = self
+                    .documents
+                    .get(&position.text_document.uri)
+                    .and_then(|document| {
+                        let line = document.text.lines().nth(position.position.line as usize)?;
+                        Some(&line[..byte_index(line, position.position.character)])
+                    })
+                    .unwrap_or_default();
+                serde_json::to_value(CompletionResponse::Array(completions(prefix)))
+            }),
+            CodeActionRequest::METHOD => {
+                parse_params::<CodeActionParams>(request.params).map(|params| {
+                    let uri = params.text_document.uri;
+                    let actions = self.documents.get(&uri).map_or_else(Vec::new, |document| {
+                        code_actions(&uri, &document.text, params.range.start.line)
+                    });
+                    serde_json::to_value(actions)
+                })
+            }
+            method => {
+                return Response::new_err(
+                    id,
+                    ErrorCode::MethodNotFound as i32,
+                    format!("unknown method {method:?}"),
+                );
+            }
+        };
+        match result {
+            Ok(result) => Response::new_ok(
+                id,
+                result.expect("BUG: every LSP result serializes to JSON"),
+            ),
+            Err(message) => Response::new_err(id, ErrorCode::InvalidParams as i32, message),
+        }
+    }
+}
+
+fn parse_params<T: DeserializeOwned>(params: serde_json::Value) -> Result<T, String> {
+    serde_json::from_value(params).map_err(|error| error.to_string())
+}
+
+fn capabilities() -> ServerCapabilities {
+    ServerCapabilities {
+        text_document_sync: Some(TextDocumentSyncCapability::Kind(TextDocumentSyncKind::FULL)),
+        hover_provider: Some(HoverProviderCapability::Simple(true)),
+        completion_provider: Some(CompletionOptions {
+            trigger_characters: Some(vec![":".to_owned()]),
+            ..CompletionOptions::default()
+        }),
+        code_action_provider: Some(CodeActionProviderCapability::Simple(true)),
+        ..ServerCapabilities::default()
+    }
+}
+
+/// Runs the initialize handshake on `connection`, then serves it until the client shuts the
+/// server down.
+fn serve(connection: &Connection) -> Result<(), Box<dyn Error + Send + Sync>> {
+    connection.initialize(serde_json::to_value(capabilities())?)?;
+    let mut server = Server::default();
+    for message in &connection.receiver {
+        match message {
+            Message::Request(request) => {
+                if connection.handle_shutdown(&request)? {
+                    return Ok(());
+                }
+                connection
+                    .sender
+                    .send(Message::Response(server.handle_request(request)))?;
+            }
+            Message::Notification(notification) => {
+                for reply in server.handle_notification(notification) {
+                    connection.sender.send(Message::Notification(reply))?;
+                }
+            }
+            Message::Response(_) => {}
+        }
+    }
+    Ok(())
+}
+#[cfg(test)]
+mod test_serve {
+    use super::*;
+    use lsp_server::RequestId;
+    use serde_json::{Value, json};
+
+    fn request(id: i32, method: &str, params: Value) -> Message {
+        Message::Request(Request::new(RequestId::from(id), method.to_owned(), params))
+    }
+
+    fn response(client: &Connection) -> Value {
+        match client.receiver.recv().unwrap() {
+            Message::Response(response) => response.result.unwrap(),
+            other => panic!("expected a response, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn session() {
+        let (server_connection, client) = Connection::memory();
+        let server = std::thread::spawn(move || serve(&server_connection).unwrap());
+        let send = |message: Message| client.sender.send(message).unwrap();
+        let notify = |method: &str, params: Value| {
+            send(Message::Notification(Notification::new(
+                method.to_owned(),
+                params,
+            )))
+        };
+
+        send(request(1, "initialize", json!({ "capabilities": {} })));
+        assert_eq!(response(&client)["capabilities"]["hoverProvider"], true);
+        notify("initialized", json!({}));
+
+        let uri = "file:///song.txt";
+        notify(
+            "textDocument/didOpen",
+            json!({ "textDocument": {
+                "uri": uri, "languageId": "guitar-tab", "version": 1, "text": "E2\nC2\n",
+            }}),
+        );
+        let Message::Notification(published) = client.receiver.recv().unwrap() else {
+            panic!("expected diagnostics");
+        };
+        assert_eq!(published.method, "textDocument/publishDiagnostics");
+        assert_eq!(
+            published.params["diagnostics"][0]["range"]["start"]["line"],
+            1
+        );
+
+        notify(
+            "textDocument/didChange",
+            json!({
+                "textDocument": { "uri": uri, "version": 2 },
+                "contentChanges": [{ "text": "E2\nA2\n" }],
+            }),
+        );
+        let Message::Notification(published) = client.receiver.recv().unwrap() else {
+            panic!("expected diagnostics");
+        };
+        assert_eq!(published.params["diagnostics"], json!([]));
+
+        let position =
+            json!({ "textDocument": { "uri": uri }, "position": { "line": 1, "character": 1 } });
+        send(request(2, "textDocument/hover", position.clone()));
+        assert_eq!(
+            response(&client)["contents"]["value"],
+            "- `A2`: string 5, fret 0"
+        );
+        send(request(3, "textDocument/completion", position));
+        assert!(!response(&client).as_array().unwrap().is_empty());
+        send(request(
+            4,
+            "textDocument/codeAction",
+            json!({
+                "textDocument": { "uri": uri },
+                "range": { "start": { "line": 1, "character": 0 }, "end": { "line": 1, "character": 0 } },
+                "context": { "diagnostics": [] },
+            }),
+        ));
+        let actions = response(&client);
+        assert_eq!(actions[0]["title"], "Transpose this line up an octave");
+        assert_eq!(actions[0]["edit"]["changes"][uri][0]["newText"], "A3");
+
+        send(request(5, "shutdown", Value::Null));
+        response(&client);
+        notify("exit", Value::Null);
+        server.join().unwrap();
+    }
+}
+
+/// Serves the language server protocol on stdin and stdout until the client exits.
+///
+/// # Errors
+///
+/// Returns a protocol error from the handshake, or an I/O error on stdin or stdout.
+pub fn serve_stdio() -> Result<(), Box<dyn Error + Send + Sync>> {
+    let (connection, io_threads) = Connection::stdio();
+    serve(&connection)?;
+    // The writer thread runs until the connection's sender is dropped.
+    drop(connection);
+    io_threads.join()?;
+    Ok(())
+}
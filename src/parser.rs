@@ -1,8 +1,12 @@
 use crate::{
     arrangement::{BeatVec, Line},
+    chord_symbol::ChordSymbol,
+    duration::Duration,
     guitar::{STD_6_STRING_TUNING_OPEN_PITCHES, create_string_tuning},
     pitch::Pitch,
     string_number::StringNumber,
+    technique::Technique,
+    voice::{Voice, VoiceColumn},
 };
 use itertools::Itertools;
 use memoize::memoize;
@@ -13,13 +17,15 @@ use std::{collections::BTreeMap, result::Result::Ok};
 use std::{collections::HashSet, str::FromStr};
 use strum::IntoEnumIterator;
 use strum_macros::{EnumIter, EnumString};
+#[cfg(feature = "wasm")]
 use tsify::Tsify;
+#[cfg(feature = "wasm")]
 use wasm_bindgen::prelude::*;
 
 const PITCH_PATTERN: &str =
     r"(?P<three_char_pitch>[A-G][#♯b♭][0-9])|(?P<two_char_pitch>[A-G][0-9])";
 
-static PITCH_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+pub(crate) static PITCH_REGEX: LazyLock<Regex> = LazyLock::new(|| {
     RegexBuilder::new(PITCH_PATTERN)
         .case_insensitive(true)
         .build()
@@ -35,9 +41,10 @@ fn test_pitch_regex() -> Regex {
 ///
 /// Additional variants may be added in a non-breaking release. The `#[non_exhaustive]`
 /// attribute requires external matches to include a wildcard arm.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EnumString, EnumIter, Serialize, Tsify)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EnumString, EnumIter, Serialize)]
+#[cfg_attr(feature = "wasm", derive(Tsify))]
 #[strum(ascii_case_insensitive)]
-#[tsify(into_wasm_abi)]
+#[cfg_attr(feature = "wasm", tsify(into_wasm_abi))]
 #[serde(rename_all = "camelCase")]
 #[non_exhaustive]
 pub enum TuningName {
@@ -54,7 +61,7 @@ pub enum TuningName {
 }
 
 /// Returns the supported `TuningName` variants, typed for JS consumption via tsify.
-#[wasm_bindgen(js_name = "getTuningNames")]
+#[cfg_attr(feature = "wasm", wasm_bindgen(js_name = "getTuningNames"))]
 #[must_use]
 pub fn get_tuning_names() -> Vec<TuningName> {
     TuningName::iter().collect()
@@ -208,11 +215,66 @@ mod test_create_string_tuning_offset {
 /// `Node::line_index` (`u16`) can address every beat without truncating.
 pub(crate) const MAX_INPUT_LINES: usize = u16::MAX as usize;
 
-/// Parses a newline-delimited input string into a sequence of `Line` values.
+/// One input line as the parser reads it: the [`Line`] it becomes, with the annotations
+/// a `Line` does not carry.
+///
+/// [`parse_input`] reads the line and its annotations in one pass, so they stay together
+/// through repeat expansion and lenient recovery.
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct ParsedLine {
+    /// The line, as [`parse_lines`] returns it.
+    pub line: Line<BeatVec<Pitch>>,
+    /// 1-indexed input line the line was read from.
+    pub input_line: u32,
+    /// The trailing duration, such as the `@q` of `E4 G4 @q`. See [`Duration`].
+    pub duration: Option<Duration>,
+    /// The voice columns of a line split with `;`, in column order. The `Playable` beat holds
+    /// their pitches in the same order. `None` for a single-voice line.
+    pub voices: Option<Vec<VoiceColumn>>,
+    /// The chord symbol of a chord line, whose `Playable` beat holds the chord in close
+    /// position.
+    pub chord: Option<ChordSymbol>,
+}
+
+impl ParsedLine {
+    /// A line without annotations, read from the 1-indexed `input_line`.
+    #[must_use]
+    pub fn new(line: Line<BeatVec<Pitch>>, input_line: u32) -> Self {
+        Self {
+            line,
+            input_line,
+            duration: None,
+            voices: None,
+            chord: None,
+        }
+    }
+
+    /// Numbers `lines` from input line 1, without annotations. Lets a hand-built
+    /// `Vec<Line>` go where parsed input does.
+    pub(crate) fn from_lines(lines: Vec<Line<BeatVec<Pitch>>>) -> Vec<Self> {
+        lines
+            .into_iter()
+            .enumerate()
+            .map(|(input_index, line)| Self::new(line, input_index as u32 + 1))
+            .collect()
+    }
+}
+
+/// Parses a newline-delimited input string into a sequence of [`ParsedLine`] values, one per
+/// input line.
 ///
 /// Each input line is classified as `Playable` (one or more pitches, e.g. `"A3"` or
-/// `"G4Bb2"`), `Rest` (empty or comment-only), or `MeasureBreak` (a line of dash
-/// characters: `-`, `–`, or `—`). Call results are cached for the 10 most recent inputs.
+/// `"G4Bb2"`), `Rest` (empty or comment-only), `MeasureBreak` (a line of dash
+/// characters: `-`, `–`, or `—`), or a structural marker on its own line: `RepeatStart`
+/// (`|:`), `RepeatEnd` (`:|`), `Ending` (`[1]` or `[1.]`), `Section` (`[Verse]`), or
+/// `Technique` (`h`, `p`, `/`, `b` or `~` between the beats it links), or `LetRing` (`ring`
+/// or `ring 3` after the beat that rings). A technique symbol may also stand between pitches
+/// on one line: `E4 h F#4` parses as three lines that share the input line number. A line split into voice columns with `;`, such as
+/// `E4 ; E2:2`, merges its columns into one `Playable` beat and keeps them as
+/// [`ParsedLine::voices`]. A chord symbol in braces or after `chord:`, such as `{Am}` or
+/// `chord: Cmaj7/E`, is a `Playable` beat holding the chord in close position, with the
+/// symbol as [`ParsedLine::chord`]. A trailing duration such as `@q` becomes
+/// [`ParsedLine::duration`]. Call results are cached for the 10 most recent inputs.
 ///
 /// # Errors
 ///
@@ -220,26 +282,15 @@ pub(crate) const MAX_INPUT_LINES: usize = u16::MAX as usize;
 /// 1-indexed line number, or [`crate::error::TabError::InputTooManyLines`] when the input
 /// exceeds `MAX_INPUT_LINES` lines.
 #[memoize(Capacity: 10)]
-pub fn parse_lines(input: String) -> Result<Vec<Line<BeatVec<Pitch>>>, crate::error::TabError> {
-    // Reject pathological input up front so every beat index stays within the u16 range
-    // used by the pathfinding graph. `take` short-circuits, so an enormous paste is not
-    // fully scanned. A real transcription is far below this bound. The cap is its own
-    // variant rather than a Parse error because no single line is at fault.
-    if input.lines().take(MAX_INPUT_LINES + 1).count() > MAX_INPUT_LINES {
-        return Err(crate::error::TabError::InputTooManyLines {
-            max: MAX_INPUT_LINES as u32,
-        });
-    }
+pub fn parse_input(input: String) -> Result<Vec<ParsedLine>, crate::error::TabError> {
+    check_line_count(&input)?;
 
-    let (parsed_lines, errors): (
-        Vec<Line<BeatVec<Pitch>>>,
-        Vec<Vec<crate::error::ParseError>>,
-    ) = input
+    let (parsed_lines, errors): (Vec<Vec<ParsedLine>>, Vec<Vec<crate::error::ParseError>>) = input
         .lines()
         .enumerate()
         .map(|(input_index, input_line)| parse_line(&PITCH_REGEX, input_index, input_line))
         .partition_map(|result| match result {
-            Ok(line) => itertools::Either::Left(line),
+            Ok(lines) => itertools::Either::Left(lines),
             Err(errs) => itertools::Either::Right(errs),
         });
 
@@ -250,7 +301,53 @@ pub fn parse_lines(input: String) -> Result<Vec<Line<BeatVec<Pitch>>>, crate::er
         });
     }
 
-    Ok(parsed_lines)
+    Ok(parsed_lines.into_iter().flatten().collect())
+}
+#[cfg(test)]
+mod test_parse_input {
+    use super::*;
+
+    #[test]
+    fn annotations_stay_on_their_lines() {
+        let parsed_lines = parse_input("E4 @q\n\n{Am} @e\nE4 ; E2:2 // bass".to_owned()).unwrap();
+        assert_eq!(
+            parsed_lines
+                .iter()
+                .map(|parsed| parsed.input_line)
+                .collect_vec(),
+            [1, 2, 3, 4]
+        );
+        assert_eq!(parsed_lines[0].duration, Duration::from_symbol("q"));
+        assert_eq!(parsed_lines[1], ParsedLine::new(Line::Rest, 2));
+        assert_eq!(parsed_lines[2].chord, ChordSymbol::from_symbol("Am"));
+        assert_eq!(parsed_lines[2].duration, Duration::from_symbol("e"));
+        let voices = parsed_lines[3].voices.as_ref().unwrap();
+        assert_eq!(voices[1].voice, Voice::Bass);
+        assert_eq!(voices[1].beats, 2);
+        assert_eq!(parsed_lines[3].chord, None);
+    }
+    #[test]
+    fn rejects_what_parse_lines_rejects() {
+        assert_eq!(
+            parse_input("E2\n???".to_owned()).unwrap_err(),
+            parse_lines("E2\n???".to_owned()).unwrap_err()
+        );
+    }
+}
+
+/// Parses a newline-delimited input string into a sequence of `Line` values, one per input
+/// line. Drops the annotations [`parse_input`] keeps, and otherwise reads the input the same
+/// way. Call results are cached for the 10 most recent inputs.
+///
+/// # Errors
+///
+/// Fails like [`parse_input`].
+#[memoize(Capacity: 10)]
+pub fn parse_lines(input: String) -> Result<Vec<Line<BeatVec<Pitch>>>, crate::error::TabError> {
+    Ok(memoized_original_parse_input(input)?
+        .into_iter()
+        .map(|parsed| parsed.line)
+        .collect())
 }
 #[cfg(test)]
 mod test_parse_lines {
@@ -306,21 +403,193 @@ mod test_parse_lines {
     }
 }
 
+/// Output of the line parser, before any fingering is chosen.
+type ParsedLines = Vec<Line<BeatVec<Pitch>>>;
+
+/// Parses input like [`parse_input`], but recovers from unparseable text instead of failing.
+///
+/// Unparseable substrings are dropped and the pitches around them are kept. A line with no
+/// parseable pitch left becomes a `Rest`. Every dropped substring is returned as a
+/// `ParseError` warning, in input order. Meant for live-typing UIs that keep rendering while
+/// the user is mid-edit. Call results are cached for the 10 most recent inputs.
+///
+/// # Errors
+///
+/// Returns [`crate::error::TabError::InputTooManyLines`] when the input exceeds
+/// `MAX_INPUT_LINES` lines. No single line is at fault there, so it stays an error.
+#[memoize(Capacity: 10)]
+pub fn parse_input_lenient(
+    input: String,
+) -> Result<(Vec<ParsedLine>, Vec<crate::error::ParseError>), crate::error::TabError> {
+    check_line_count(&input)?;
+
+    let mut warnings: Vec<crate::error::ParseError> = Vec::new();
+    let parsed_lines: Vec<ParsedLine> = input
+        .lines()
+        .enumerate()
+        .flat_map(|(input_index, input_line)| {
+            let (lines, errors) = parse_line_lenient(&PITCH_REGEX, input_index, input_line);
+            warnings.extend(errors);
+            lines
+        })
+        .collect();
+
+    Ok((parsed_lines, warnings))
+}
+
+/// Parses input like [`parse_lines`], but recovers from unparseable text like
+/// [`parse_input_lenient`]. Call results are cached for the 10 most recent inputs.
+///
+/// # Errors
+///
+/// Fails like [`parse_input_lenient`].
+#[memoize(Capacity: 10)]
+pub fn parse_lines_lenient(
+    input: String,
+) -> Result<(ParsedLines, Vec<crate::error::ParseError>), crate::error::TabError> {
+    let (parsed_lines, warnings) = memoized_original_parse_input_lenient(input)?;
+    Ok((
+        parsed_lines.into_iter().map(|parsed| parsed.line).collect(),
+        warnings,
+    ))
+}
+#[cfg(test)]
+mod test_parse_lines_lenient {
+    use super::*;
+
+    #[test]
+    fn clean_input_matches_strict_parse() {
+        let input = "A3\nE2// Comment\n\nG4BB2G4\n-\nE4".to_owned();
+        let (lines, warnings) = parse_lines_lenient(input.clone()).unwrap();
+        assert_eq!(lines, parse_lines(input).unwrap());
+        assert!(warnings.is_empty());
+    }
+    #[test]
+    fn drops_bad_tokens_and_keeps_pitches() {
+        let input = "A3xyz\nE2\n\nG4BB.2\n-\nE4".to_owned();
+        let (lines, warnings) = parse_lines_lenient(input).unwrap();
+        assert_eq!(
+            lines,
+            vec![
+                Line::Playable(vec![Pitch::A3]),
+                Line::Playable(vec![Pitch::E2]),
+                Line::Rest,
+                Line::Playable(vec![Pitch::G4]),
+                Line::MeasureBreak,
+                Line::Playable(vec![Pitch::E4]),
+            ]
+        );
+        assert_eq!(
+            warnings,
+            vec![
+                crate::error::ParseError {
+                    line: 1,
+                    text: "xyz".to_owned()
+                },
+                crate::error::ParseError {
+                    line: 4,
+                    text: "BB.2".to_owned()
+                },
+            ]
+        );
+    }
+    #[test]
+    fn line_without_pitches_becomes_rest() {
+        let (lines, warnings) = parse_lines_lenient("E2\n???\nA2".to_owned()).unwrap();
+        assert_eq!(
+            lines,
+            vec![
+                Line::Playable(vec![Pitch::E2]),
+                Line::Rest,
+                Line::Playable(vec![Pitch::A2]),
+            ]
+        );
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].line, 2);
+        assert_eq!(warnings[0].text, "???");
+    }
+    #[test]
+    fn still_rejects_input_beyond_max_lines() {
+        let input = "A2\n".repeat(MAX_INPUT_LINES + 1);
+
+        let err = parse_lines_lenient(input).unwrap_err();
+        assert_eq!(
+            err,
+            crate::error::TabError::InputTooManyLines {
+                max: MAX_INPUT_LINES as u32,
+            }
+        );
+    }
+}
+
+/// Rejects pathological input up front so every beat index stays within the u16 range used
+/// by the pathfinding graph. `take` short-circuits, so an enormous paste is not fully
+/// scanned. A real transcription is far below this bound. The cap is its own variant rather
+/// than a Parse error because no single line is at fault.
+fn check_line_count(input: &str) -> Result<(), crate::error::TabError> {
+    if input.lines().take(MAX_INPUT_LINES + 1).count() > MAX_INPUT_LINES {
+        return Err(crate::error::TabError::InputTooManyLines {
+            max: MAX_INPUT_LINES as u32,
+        });
+    }
+    Ok(())
+}
+
+/// Parses one input line into `ParsedLine`s, reading its duration, voice columns and chord
+/// symbol along with the line. A line with techniques between its pitches, such as
+/// `E4 h F#4`, yields a beat, a `Technique` and a beat. Every other line yields one line.
 fn parse_line(
     regex: &Regex,
     input_index: usize,
-    mut input_line: &str,
-) -> Result<Line<Vec<Pitch>>, Vec<crate::error::ParseError>> {
-    input_line = remove_comments(input_line);
-    let line_content: String = remove_whitespace(input_line);
-
-    if let Some(rest) = parse_rest(&line_content) {
-        return Ok(rest);
+    input_line: &str,
+) -> Result<Vec<ParsedLine>, Vec<crate::error::ParseError>> {
+    let (input_line, duration) = split_duration(remove_comments(input_line));
+    let parsed = ParsedLine {
+        duration,
+        ..ParsedLine::new(Line::Rest, input_index as u32 + 1)
+    };
+    if let Some((columns, errors)) = parse_voice_columns(regex, input_index, input_line) {
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+        return Ok(vec![ParsedLine {
+            line: merge_voice_columns(&columns),
+            voices: Some(columns),
+            ..parsed
+        }]);
     }
-    if let Some(measure_break) = parse_measure_break(&line_content) {
-        return Ok(measure_break);
+    if let Some(chord) = parse_chord_line(input_line) {
+        return Ok(vec![ParsedLine {
+            line: Line::Playable(chord.default_pitches()),
+            chord: Some(chord),
+            ..parsed
+        }]);
+    }
+    if let Some(marker) = parse_marker(input_line) {
+        return Ok(vec![ParsedLine {
+            line: marker,
+            ..parsed
+        }]);
+    }
+
+    let mut errors: Vec<crate::error::ParseError> = Vec::new();
+    let parsed_lines = split_techniques(input_line)
+        .into_iter()
+        .filter_map(|segment| match segment {
+            itertools::Either::Left(technique) => Some(Line::Technique(technique)),
+            itertools::Either::Right(beat_content) => parse_beat(regex, input_index, &beat_content)
+                .map_err(|beat_errors| errors.extend(beat_errors))
+                .ok(),
+        })
+        .map(|line| ParsedLine {
+            line,
+            ..parsed.clone()
+        })
+        .collect_vec();
+    if !errors.is_empty() {
+        return Err(errors);
     }
-    parse_pitch(regex, input_index, &line_content)
+    Ok(parsed_lines)
 }
 #[cfg(test)]
 mod test_parse_line {
@@ -328,39 +597,174 @@ mod test_parse_line {
 
     #[test]
     fn empty() {
-        assert_eq!(parse_line(&test_pitch_regex(), 0, "").unwrap(), Line::Rest);
+        assert_eq!(
+            parse_line(&test_pitch_regex(), 0, "").unwrap()[0].line,
+            Line::Rest
+        );
     }
     #[test]
     fn only_comment() {
         assert_eq!(
-            parse_line(&test_pitch_regex(), 0, "  // Long comment.... ").unwrap(),
+            parse_line(&test_pitch_regex(), 0, "  // Long comment.... ").unwrap()[0].line,
             Line::Rest
         );
     }
     #[test]
     fn measure_break() {
         assert_eq!(
-            parse_line(&test_pitch_regex(), 0, "    --    ").unwrap(),
+            parse_line(&test_pitch_regex(), 0, "    --    ").unwrap()[0].line,
             Line::MeasureBreak
         );
         assert_eq!(
-            parse_line(&test_pitch_regex(), 0, "- //comment").unwrap(),
+            parse_line(&test_pitch_regex(), 0, "- //comment").unwrap()[0].line,
             Line::MeasureBreak
         );
     }
     #[test]
+    fn structural_markers() {
+        assert_eq!(
+            parse_line(&test_pitch_regex(), 0, " |: // start").unwrap()[0].line,
+            Line::RepeatStart
+        );
+        assert_eq!(
+            parse_line(&test_pitch_regex(), 0, "[Verse 2]").unwrap()[0].line,
+            Line::Section("Verse 2".to_owned())
+        );
+    }
+    #[test]
+    fn chord_lines_hold_the_chord_in_close_position() {
+        let parsed = parse_line(&test_pitch_regex(), 0, "{Am} // strum")
+            .unwrap()
+            .remove(0);
+        assert_eq!(
+            parsed.line,
+            Line::Playable(vec![Pitch::A2, Pitch::C3, Pitch::E3])
+        );
+        assert_eq!(parsed.chord, ChordSymbol::from_symbol("Am"));
+        let parsed = parse_line(&test_pitch_regex(), 0, "chord: C/E")
+            .unwrap()
+            .remove(0);
+        assert_eq!(
+            parsed.line,
+            Line::Playable(vec![Pitch::E2, Pitch::G2, Pitch::C3])
+        );
+        assert_eq!(parsed.chord, ChordSymbol::from_symbol("C/E"));
+        assert_eq!(
+            parse_line(&test_pitch_regex(), 0, "[Verse]").unwrap()[0].chord,
+            None
+        );
+        assert_eq!(
+            parse_line(&test_pitch_regex(), 0, "[A]").unwrap()[0].line,
+            Line::Section("A".to_owned())
+        );
+        assert!(parse_line(&test_pitch_regex(), 0, "chord: Verse").is_err());
+    }
+    #[test]
     fn parses_line_with_pitches_whitespace_and_comments() {
         let expected = Line::Playable(vec![Pitch::GSharpAFlat2, Pitch::A4, Pitch::E3, Pitch::G2]);
         assert_eq!(
-            parse_line(&test_pitch_regex(), 123, "    G#2A4  E3 G2 ").unwrap(),
+            parse_line(&test_pitch_regex(), 123, "    G#2A4  E3 G2 ").unwrap()[0].line,
             expected
         );
         assert_eq!(
-            parse_line(&test_pitch_regex(), 123, "G#2A4E3 G2// Comment").unwrap(),
+            parse_line(&test_pitch_regex(), 123, "G#2A4E3 G2// Comment").unwrap()[0].line,
             expected
         );
     }
     #[test]
+    fn splits_off_the_duration() {
+        let parsed = parse_line(&test_pitch_regex(), 0, "E4 G4 @q // quarter")
+            .unwrap()
+            .remove(0);
+        assert_eq!(parsed.line, Line::Playable(vec![Pitch::E4, Pitch::G4]));
+        assert_eq!(parsed.duration, Duration::from_symbol("q"));
+
+        let parsed = parse_line(&test_pitch_regex(), 0, "@h.").unwrap().remove(0);
+        assert_eq!(parsed.line, Line::Rest);
+        assert_eq!(parsed.duration, Duration::from_symbol("h."));
+
+        let parsed = parse_line(&test_pitch_regex(), 0, "{Am} @w")
+            .unwrap()
+            .remove(0);
+        assert_eq!(
+            parsed.line,
+            Line::Playable(vec![Pitch::A2, Pitch::C3, Pitch::E3])
+        );
+        assert_eq!(parsed.duration, Duration::from_symbol("w"));
+
+        let parsed = parse_line(&test_pitch_regex(), 0, "E4 ; E2:2 @e")
+            .unwrap()
+            .remove(0);
+        assert_eq!(parsed.line, Line::Playable(vec![Pitch::E4, Pitch::E2]));
+        assert_eq!(parsed.duration, Duration::from_symbol("e"));
+
+        assert_eq!(
+            parse_line(&test_pitch_regex(), 0, "E4").unwrap()[0].duration,
+            None
+        );
+        assert!(parse_line(&test_pitch_regex(), 0, "E4 @x").is_err());
+    }
+    #[test]
+    fn merges_voice_columns() {
+        let parsed = parse_line(&test_pitch_regex(), 0, "E4 G4 ; E2:2 // bass holds")
+            .unwrap()
+            .remove(0);
+        assert_eq!(
+            parsed.line,
+            Line::Playable(vec![Pitch::E4, Pitch::G4, Pitch::E2])
+        );
+        assert_eq!(
+            parsed.voices,
+            Some(vec![
+                VoiceColumn {
+                    voice: Voice::Melody,
+                    pitches: vec![Pitch::E4, Pitch::G4],
+                    beats: 1,
+                },
+                VoiceColumn {
+                    voice: Voice::Bass,
+                    pitches: vec![Pitch::E2],
+                    beats: 2,
+                },
+            ])
+        );
+        assert_eq!(
+            parse_line(&test_pitch_regex(), 0, "E4 G4").unwrap()[0].voices,
+            None
+        );
+        assert_eq!(
+            parse_line(&test_pitch_regex(), 0, " ; ").unwrap()[0].line,
+            Line::Rest
+        );
+        assert!(parse_line(&test_pitch_regex(), 0, "E4 ; E2:x").is_err());
+    }
+    #[test]
+    fn splits_at_techniques_between_pitches() {
+        let parsed = parse_line(&test_pitch_regex(), 6, "E4 h F#4 @e // legato").unwrap();
+        assert_eq!(
+            parsed
+                .iter()
+                .map(|parsed| parsed.line.clone())
+                .collect_vec(),
+            [
+                Line::Playable(vec![Pitch::E4]),
+                Line::Technique(Technique::HammerOn),
+                Line::Playable(vec![Pitch::FSharpGFlat4]),
+            ]
+        );
+        assert!(parsed.iter().all(|parsed| parsed.input_line == 7));
+        assert!(
+            parsed
+                .iter()
+                .all(|parsed| parsed.duration == Duration::from_symbol("e"))
+        );
+        assert_eq!(
+            parse_line(&test_pitch_regex(), 0, "B 3").unwrap()[0].line,
+            Line::Playable(vec![Pitch::B3])
+        );
+        assert!(parse_line(&test_pitch_regex(), 0, "E4 h X4").is_err());
+    }
+    #[test]
     fn reports_error_for_unparseable_text() {
         let errors = parse_line(&test_pitch_regex(), 4, "  Invalid Text  ").unwrap_err();
         assert_eq!(errors.len(), 1);
@@ -369,6 +773,344 @@ mod test_parse_line {
     }
 }
 
+/// Lenient counterpart of `parse_line`. Returns the recovered lines together with the
+/// substrings that were dropped from them.
+fn parse_line_lenient(
+    regex: &Regex,
+    input_index: usize,
+    input_line: &str,
+) -> (Vec<ParsedLine>, Vec<crate::error::ParseError>) {
+    let (input_line, duration) = split_duration(remove_comments(input_line));
+    let parsed = ParsedLine {
+        duration,
+        ..ParsedLine::new(Line::Rest, input_index as u32 + 1)
+    };
+    if let Some((columns, errors)) = parse_voice_columns(regex, input_index, input_line) {
+        let voiced = ParsedLine {
+            line: merge_voice_columns(&columns),
+            voices: Some(columns),
+            ..parsed
+        };
+        return (vec![voiced], errors);
+    }
+    if let Some(chord) = parse_chord_line(input_line) {
+        let chord_line = ParsedLine {
+            line: Line::Playable(chord.default_pitches()),
+            chord: Some(chord),
+            ..parsed
+        };
+        return (vec![chord_line], Vec::new());
+    }
+    if let Some(marker) = parse_marker(input_line) {
+        return (
+            vec![ParsedLine {
+                line: marker,
+                ..parsed
+            }],
+            Vec::new(),
+        );
+    }
+
+    let mut errors: Vec<crate::error::ParseError> = Vec::new();
+    let parsed_lines = split_techniques(input_line)
+        .into_iter()
+        .map(|segment| match segment {
+            itertools::Either::Left(technique) => Line::Technique(technique),
+            itertools::Either::Right(beat_content) => {
+                let (line, beat_errors) = parse_beat_lenient(regex, input_index, &beat_content);
+                errors.extend(beat_errors);
+                line
+            }
+        })
+        .map(|line| ParsedLine {
+            line,
+            ..parsed.clone()
+        })
+        .collect_vec();
+    (parsed_lines, errors)
+}
+#[cfg(test)]
+mod test_parse_line_lenient {
+    use super::*;
+
+    #[test]
+    fn clean_line_has_no_errors() {
+        let (parsed, errors) = parse_line_lenient(&test_pitch_regex(), 0, "G#2 A4 // Comment");
+        assert_eq!(
+            parsed[0].line,
+            Line::Playable(vec![Pitch::GSharpAFlat2, Pitch::A4])
+        );
+        assert!(errors.is_empty());
+    }
+    #[test]
+    fn measure_break_has_no_errors() {
+        let (parsed, errors) = parse_line_lenient(&test_pitch_regex(), 0, "---");
+        assert_eq!(parsed[0].line, Line::MeasureBreak);
+        assert!(errors.is_empty());
+    }
+    #[test]
+    fn keeps_pitches_around_bad_tokens() {
+        let (parsed, errors) = parse_line_lenient(&test_pitch_regex(), 12, "ZA2G#444B3");
+        assert_eq!(
+            parsed[0].line,
+            Line::Playable(vec![Pitch::A2, Pitch::GSharpAFlat4, Pitch::B3])
+        );
+        assert_eq!(errors.len(), 2);
+        assert_eq!(errors[0].line, 13);
+        assert_eq!(errors[0].text, "Z");
+        assert_eq!(errors[1].text, "44");
+    }
+    #[test]
+    fn keeps_techniques_around_bad_beats() {
+        let (parsed, errors) = parse_line_lenient(&test_pitch_regex(), 0, "E4 h ???");
+        assert_eq!(
+            parsed
+                .iter()
+                .map(|parsed| parsed.line.clone())
+                .collect_vec(),
+            [
+                Line::Playable(vec![Pitch::E4]),
+                Line::Technique(Technique::HammerOn),
+                Line::Rest,
+            ]
+        );
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].text, "???");
+    }
+    #[test]
+    fn unparseable_line_becomes_rest() {
+        let (parsed, errors) = parse_line_lenient(&test_pitch_regex(), 4, "  Invalid Text  ");
+        assert_eq!(parsed[0].line, Line::Rest);
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].line, 5);
+        assert_eq!(errors[0].text, "InvalidText");
+    }
+}
+
+/// Splits a comment-free line at each technique symbol that stands alone between its
+/// pitches, such as the `h` of `E4 h F#4`. Returns the whitespace-free text of each beat,
+/// with the techniques in their places. A line without one is a single beat.
+///
+/// A letter symbol followed by a digit, as in `B 3`, is the pitch `B3` spelled with a space.
+fn split_techniques(input_line: &str) -> Vec<itertools::Either<Technique, String>> {
+    let mut segments: Vec<itertools::Either<Technique, String>> = Vec::new();
+    let mut beat_content = String::new();
+    let mut tokens = input_line.split_whitespace().peekable();
+    while let Some(token) = tokens.next() {
+        let splits_a_pitch = tokens
+            .peek()
+            .is_some_and(|next| next.starts_with(|c: char| c.is_ascii_digit()));
+        match Technique::from_symbol(token) {
+            Some(technique) if !splits_a_pitch => {
+                if !beat_content.is_empty() {
+                    segments.push(itertools::Either::Right(std::mem::take(&mut beat_content)));
+                }
+                segments.push(itertools::Either::Left(technique));
+            }
+            _ => beat_content.push_str(token),
+        }
+    }
+    if !beat_content.is_empty() || segments.is_empty() {
+        segments.push(itertools::Either::Right(beat_content));
+    }
+    segments
+}
+#[cfg(test)]
+mod test_split_techniques {
+    use super::*;
+    use itertools::Either::{Left, Right};
+
+    #[test]
+    fn techniques_between_pitches() {
+        assert_eq!(
+            split_techniques(" E4 h F#4 / G4 A4 "),
+            vec![
+                Right("E4".to_owned()),
+                Left(Technique::HammerOn),
+                Right("F#4".to_owned()),
+                Left(Technique::Slide),
+                Right("G4A4".to_owned()),
+            ]
+        );
+        assert_eq!(
+            split_techniques("E4 ~"),
+            vec![Right("E4".to_owned()), Left(Technique::Vibrato)]
+        );
+    }
+    #[test]
+    fn single_beat() {
+        assert_eq!(split_techniques("E4 G4"), vec![Right("E4G4".to_owned())]);
+        assert_eq!(split_techniques("Bb2 B 3"), vec![Right("Bb2B3".to_owned())]);
+        assert_eq!(split_techniques("Ab4"), vec![Right("Ab4".to_owned())]);
+        assert_eq!(split_techniques(""), vec![Right(String::new())]);
+    }
+}
+
+/// Parses the whitespace-free text of one beat: a rest, a measure break or pitches.
+fn parse_beat(
+    regex: &Regex,
+    input_index: usize,
+    beat_content: &str,
+) -> Result<Line<Vec<Pitch>>, Vec<crate::error::ParseError>> {
+    if let Some(line) = parse_rest(beat_content).or_else(|| parse_measure_break(beat_content)) {
+        return Ok(line);
+    }
+    parse_pitch(regex, input_index, beat_content)
+}
+
+/// Lenient counterpart of `parse_beat`. A beat with no parseable pitch becomes a `Rest`.
+fn parse_beat_lenient(
+    regex: &Regex,
+    input_index: usize,
+    beat_content: &str,
+) -> (Line<Vec<Pitch>>, Vec<crate::error::ParseError>) {
+    if let Some(line) = parse_rest(beat_content).or_else(|| parse_measure_break(beat_content)) {
+        return (line, Vec::new());
+    }
+    let (pitches, errors) = scan_pitches(regex, input_index, beat_content);
+    if pitches.is_empty() {
+        return (Line::Rest, errors);
+    }
+    (Line::Playable(pitches), errors)
+}
+
+/// Splits a comment-free line into voice columns on `;`, or returns `None` for a line
+/// without one. Returns the columns together with the text that could not be parsed.
+///
+/// The first column is the melody, the last is the bass, and any between are inner voices.
+/// A column may end in `:N` to hold its pitches for N beats, from 1 to 255. A bad hold
+/// count is reported and the column falls back to one beat.
+fn parse_voice_columns(
+    regex: &Regex,
+    input_index: usize,
+    input_line: &str,
+) -> Option<(Vec<VoiceColumn>, Vec<crate::error::ParseError>)> {
+    if !input_line.contains(';') {
+        return None;
+    }
+    let num_columns = input_line.split(';').count();
+    let mut errors: Vec<crate::error::ParseError> = Vec::new();
+    let columns = input_line
+        .split(';')
+        .enumerate()
+        .map(|(column_index, column)| {
+            let column_content = remove_whitespace(column);
+            let (pitch_text, beats) = match column_content.rsplit_once(':') {
+                None => (column_content.as_str(), 1),
+                Some((pitch_text, beats_text)) => match beats_text.parse::<u8>() {
+                    Ok(beats) if beats > 0 => (pitch_text, beats),
+                    _ => {
+                        errors.push(crate::error::ParseError {
+                            line: (input_index + 1) as u32,
+                            text: format!(":{beats_text}"),
+                        });
+                        (pitch_text, 1)
+                    }
+                },
+            };
+            let (pitches, pitch_errors) = scan_pitches(regex, input_index, pitch_text);
+            errors.extend(pitch_errors);
+            VoiceColumn {
+                voice: Voice::of_column(column_index, num_columns),
+                pitches,
+                beats,
+            }
+        })
+        .collect_vec();
+    Some((columns, errors))
+}
+#[cfg(test)]
+mod test_parse_voice_columns {
+    use super::*;
+
+    #[test]
+    fn single_voice_line() {
+        assert_eq!(parse_voice_columns(&test_pitch_regex(), 0, "E4G4"), None);
+    }
+    #[test]
+    fn three_columns() {
+        let (columns, errors) =
+            parse_voice_columns(&test_pitch_regex(), 0, "E5 ; G4B4 ; E2 : 4").unwrap();
+        assert!(errors.is_empty());
+        assert_eq!(
+            columns.iter().map(|column| column.voice).collect_vec(),
+            vec![Voice::Melody, Voice::Inner, Voice::Bass]
+        );
+        assert_eq!(columns[1].pitches, vec![Pitch::G4, Pitch::B4]);
+        assert_eq!(columns[2].beats, 4);
+    }
+    #[test]
+    fn reports_bad_text_and_keeps_the_rest() {
+        let (columns, errors) = parse_voice_columns(&test_pitch_regex(), 6, "E4x ; E2:0").unwrap();
+        assert_eq!(columns[0].pitches, vec![Pitch::E4]);
+        assert_eq!(columns[1].pitches, vec![Pitch::E2]);
+        assert_eq!(columns[1].beats, 1);
+        assert_eq!(
+            errors,
+            vec![
+                crate::error::ParseError {
+                    line: 7,
+                    text: "x".to_owned()
+                },
+                crate::error::ParseError {
+                    line: 7,
+                    text: ":0".to_owned()
+                },
+            ]
+        );
+    }
+}
+
+/// Merges voice columns into one beat, in column order. A line whose columns are all empty
+/// is a `Rest`.
+fn merge_voice_columns(columns: &[VoiceColumn]) -> Line<Vec<Pitch>> {
+    let pitches = columns
+        .iter()
+        .flat_map(|column| column.pitches.iter().copied())
+        .collect_vec();
+    if pitches.is_empty() {
+        return Line::Rest;
+    }
+    Line::Playable(pitches)
+}
+
+/// Whether a raw input line, read like [`parse_input`] reads it, is a chord symbol or a
+/// marker, whose text spells no pitches even where it looks like one (`{A7}`, `[A7]`).
+#[cfg(feature = "lsp")]
+pub(crate) fn is_symbol_line(input_line: &str) -> bool {
+    let (input_line, _) = split_duration(remove_comments(input_line));
+    parse_chord_line(input_line).is_some() || parse_marker(input_line).is_some()
+}
+
+/// Splits a trailing `@` duration, such as the `@q` of `E4 @q`, off a comment-free line.
+/// Text after the last `@` that is not a duration symbol stays on the line, so the pitch
+/// parser reports it.
+fn split_duration(input_line: &str) -> (&str, Option<Duration>) {
+    input_line
+        .rsplit_once('@')
+        .and_then(|(beat, symbol)| Some((beat, Some(Duration::from_symbol(symbol.trim())?))))
+        .unwrap_or((input_line, None))
+}
+#[cfg(test)]
+mod test_split_duration {
+    use super::*;
+
+    #[test]
+    fn trailing_duration() {
+        assert_eq!(
+            split_duration("E4 G4 @q. "),
+            ("E4 G4 ", Duration::from_symbol("q."))
+        );
+        assert_eq!(split_duration("@h"), ("", Duration::from_symbol("h")));
+    }
+    #[test]
+    fn no_duration() {
+        assert_eq!(split_duration("E4 G4"), ("E4 G4", None));
+        assert_eq!(split_duration("E4 @x"), ("E4 @x", None));
+        assert_eq!(split_duration("E4 @q E5"), ("E4 @q E5", None));
+    }
+}
+
 fn remove_comments(input_line: &str) -> &str {
     input_line.split("//").next().unwrap_or(input_line)
 }
@@ -425,6 +1167,182 @@ mod test_parse_rest {
     }
 }
 
+/// Parses a chord line: a chord symbol in braces (`{Am}`) or after a case-insensitive
+/// `chord:` (`chord: Cmaj7/E`). Brackets are left to [`parse_marker`], so `[A]` stays a
+/// section label.
+fn parse_chord_line(input_line: &str) -> Option<ChordSymbol> {
+    let trimmed = input_line.trim();
+    let symbol = match trimmed.get(..6) {
+        Some(prefix) if prefix.eq_ignore_ascii_case("chord:") => &trimmed[6..],
+        _ => trimmed.strip_prefix('{')?.strip_suffix('}')?,
+    };
+    ChordSymbol::from_symbol(symbol.trim())
+}
+#[cfg(test)]
+mod test_parse_chord_line {
+    use super::*;
+
+    #[test]
+    fn braced_chords() {
+        assert_eq!(parse_chord_line(" {Am} "), ChordSymbol::from_symbol("Am"));
+        assert_eq!(parse_chord_line("{ G/B }"), ChordSymbol::from_symbol("G/B"));
+        assert_eq!(parse_chord_line("{A}"), ChordSymbol::from_symbol("A"));
+    }
+    #[test]
+    fn chord_prefix() {
+        assert_eq!(
+            parse_chord_line("chord: Cmaj7/E"),
+            ChordSymbol::from_symbol("Cmaj7/E")
+        );
+        assert_eq!(
+            parse_chord_line("Chord:F#m"),
+            ChordSymbol::from_symbol("F#m")
+        );
+    }
+    #[test]
+    fn not_a_chord() {
+        assert_eq!(parse_chord_line("[Verse]"), None);
+        assert_eq!(parse_chord_line("[A]"), None);
+        assert_eq!(parse_chord_line("{Verse}"), None);
+        assert_eq!(parse_chord_line("[1]"), None);
+        assert_eq!(parse_chord_line("Am"), None);
+        assert_eq!(parse_chord_line("chord: Verse"), None);
+        assert_eq!(parse_chord_line("A2C3E3"), None);
+        assert_eq!(parse_chord_line(""), None);
+    }
+}
+
+/// Parses a repeat, volta-ending, section or technique marker. Runs before whitespace is
+/// stripped so a section label keeps its inner spaces. A marker must sit on its own line;
+/// technique symbols between pitches are split off by `split_techniques` instead.
+///
+/// `|:` opens a repeat and `:|` closes it. `[1]` or `[1.]` opens a numbered ending. Any
+/// other bracketed text, such as `[Verse]` or `[A]`, is a section label. A lone technique symbol
+/// (`h`, `p`, `/`, `b` or `~`) links the beats around it, and `ring` lets the beat before
+/// it ring.
+fn parse_marker(input_line: &str) -> Option<Line<Vec<Pitch>>> {
+    let trimmed = input_line.trim();
+    match trimmed {
+        "|:" => return Some(Line::RepeatStart),
+        ":|" => return Some(Line::RepeatEnd),
+        _ => {}
+    }
+    if let Some(technique) = Technique::from_symbol(trimmed) {
+        return Some(Line::Technique(technique));
+    }
+    if let Some(let_ring) = parse_let_ring(trimmed) {
+        return Some(let_ring);
+    }
+    let label = trimmed.strip_prefix('[')?.strip_suffix(']')?.trim();
+    if label.is_empty() {
+        return None;
+    }
+    match label.strip_suffix('.').unwrap_or(label).parse::<u8>() {
+        Ok(number) if number > 0 => Some(Line::Ending(number)),
+        _ => Some(Line::Section(label.to_owned())),
+    }
+}
+#[cfg(test)]
+mod test_parse_marker {
+    use super::*;
+
+    #[test]
+    fn repeat_start_and_end() {
+        assert_eq!(parse_marker("|:"), Some(Line::RepeatStart));
+        assert_eq!(parse_marker("  :|  "), Some(Line::RepeatEnd));
+    }
+    #[test]
+    fn numbered_endings() {
+        assert_eq!(parse_marker("[1]"), Some(Line::Ending(1)));
+        assert_eq!(parse_marker("[2.]"), Some(Line::Ending(2)));
+        assert_eq!(parse_marker("[ 3. ]"), Some(Line::Ending(3)));
+    }
+    #[test]
+    fn section_labels() {
+        assert_eq!(
+            parse_marker("[Verse]"),
+            Some(Line::Section("Verse".to_owned()))
+        );
+        assert_eq!(
+            parse_marker("[ Pre-Chorus 2 ]"),
+            Some(Line::Section("Pre-Chorus 2".to_owned()))
+        );
+        assert_eq!(parse_marker("[0]"), Some(Line::Section("0".to_owned())));
+    }
+    #[test]
+    fn techniques() {
+        assert_eq!(
+            parse_marker(" h "),
+            Some(Line::Technique(Technique::HammerOn))
+        );
+        assert_eq!(parse_marker("/"), Some(Line::Technique(Technique::Slide)));
+        assert_eq!(parse_marker("b"), Some(Line::Technique(Technique::Bend)));
+        assert_eq!(parse_marker("~"), Some(Line::Technique(Technique::Vibrato)));
+    }
+    #[test]
+    fn not_a_marker() {
+        assert_eq!(parse_marker(""), None);
+        assert_eq!(parse_marker("[]"), None);
+        assert_eq!(parse_marker("E2"), None);
+        assert_eq!(parse_marker("|: E2"), None);
+        assert_eq!(parse_marker("[Verse"), None);
+    }
+    #[test]
+    fn technique_between_pitches() {
+        assert_eq!(
+            parse_lines("E4 h F#4".to_owned()).unwrap(),
+            vec![
+                Line::Playable(vec![Pitch::E4]),
+                Line::Technique(Technique::HammerOn),
+                Line::Playable(vec![Pitch::FSharpGFlat4]),
+            ]
+        );
+    }
+}
+
+/// Parses a let-ring marker: `ring` or `let ring`, optionally followed by the number of beats
+/// the previous beat rings through. Without a number it rings to the end. Case-insensitive.
+fn parse_let_ring(trimmed_line: &str) -> Option<Line<Vec<Pitch>>> {
+    let lowercase = trimmed_line.to_ascii_lowercase();
+    let without_let = lowercase
+        .strip_prefix("let ")
+        .map_or(lowercase.as_str(), str::trim_start);
+    let beats = without_let.strip_prefix("ring")?.trim();
+    if beats.is_empty() {
+        return Some(Line::LetRing(None));
+    }
+    match beats.parse::<u8>() {
+        Ok(beats) if beats > 0 => Some(Line::LetRing(Some(beats))),
+        _ => None,
+    }
+}
+#[cfg(test)]
+mod test_parse_let_ring {
+    use super::*;
+
+    #[test]
+    fn rings_to_the_end() {
+        assert_eq!(parse_let_ring("ring"), Some(Line::LetRing(None)));
+        assert_eq!(parse_let_ring("Let Ring"), Some(Line::LetRing(None)));
+    }
+    #[test]
+    fn rings_for_a_number_of_beats() {
+        assert_eq!(parse_let_ring("ring 3"), Some(Line::LetRing(Some(3))));
+        assert_eq!(
+            parse_let_ring("let  ring 12"),
+            Some(Line::LetRing(Some(12)))
+        );
+    }
+    #[test]
+    fn rejects_other_text() {
+        assert_eq!(parse_let_ring("rings"), None);
+        assert_eq!(parse_let_ring("ring 0"), None);
+        assert_eq!(parse_let_ring("ring 300"), None);
+        assert_eq!(parse_let_ring("let"), None);
+        assert_eq!(parse_let_ring("E2"), None);
+    }
+}
+
 fn parse_measure_break(input_line: &str) -> Option<Line<Vec<Pitch>>> {
     let unique_chars: HashSet<char> = input_line.chars().collect();
     if unique_chars == HashSet::<char>::from(['-'])
@@ -484,57 +1402,10 @@ fn parse_pitch(
     input_index: usize,
     input_line: &str,
 ) -> Result<Line<Vec<Pitch>>, Vec<crate::error::ParseError>> {
-    let mut matched_mask = vec![false; input_line.len()];
-    let mut matched_pitches: Vec<Pitch> = Vec::new();
-
-    for regex_match in regex.find_iter(input_line) {
-        if let Ok(pitch) = Pitch::from_str(regex_match.as_str()) {
-            matched_pitches.push(pitch);
-            for slot in matched_mask
-                .iter_mut()
-                .take(regex_match.end())
-                .skip(regex_match.start())
-            {
-                *slot = true;
-            }
-        }
-    }
-
-    let unmatched_indices: Vec<usize> = matched_mask
-        .iter()
-        .enumerate()
-        .filter_map(|(idx, matched)| if *matched { None } else { Some(idx) })
-        .collect();
-
-    if !unmatched_indices.is_empty() {
-        let line_number = (input_index + 1) as u32;
-        let consecutive_indices = consecutive_slices(&unmatched_indices);
-        let errors: Vec<crate::error::ParseError> = consecutive_indices
-            .into_iter()
-            .map(|unmatched_input_indices| {
-                let first_idx = *unmatched_input_indices
-                    .first()
-                    .expect("BUG: consecutive_slices never yields an empty group");
-                let last_idx = *unmatched_input_indices
-                    .last()
-                    .expect("BUG: consecutive_slices never yields an empty group");
-                // Collect by char so the unmatched run can never slice across a UTF-8
-                // boundary: `matched_mask` is byte-indexed, so `input_line[first..=last]`
-                // could panic on a non-boundary index.
-                let unmatched_input: String = input_line
-                    .char_indices()
-                    .filter(|(byte_idx, _)| (first_idx..=last_idx).contains(byte_idx))
-                    .map(|(_, ch)| ch)
-                    .collect();
-                crate::error::ParseError {
-                    line: line_number,
-                    text: unmatched_input,
-                }
-            })
-            .collect();
+    let (matched_pitches, errors) = scan_pitches(regex, input_index, input_line);
+    if !errors.is_empty() {
         return Err(errors);
     }
-
     Ok(Line::Playable(matched_pitches))
 }
 #[cfg(test)]
@@ -634,6 +1505,90 @@ mod test_parse_pitch {
     }
 }
 
+/// Scans a whitespace-free input line for pitches. Returns every matched pitch in order,
+/// plus one `ParseError` per run of text that matched no pitch.
+fn scan_pitches(
+    regex: &Regex,
+    input_index: usize,
+    input_line: &str,
+) -> (Vec<Pitch>, Vec<crate::error::ParseError>) {
+    let mut matched_mask = vec![false; input_line.len()];
+    let mut matched_pitches: Vec<Pitch> = Vec::new();
+
+    for regex_match in regex.find_iter(input_line) {
+        if let Ok(pitch) = Pitch::from_str(regex_match.as_str()) {
+            matched_pitches.push(pitch);
+            for slot in matched_mask
+                .iter_mut()
+                .take(regex_match.end())
+                .skip(regex_match.start())
+            {
+                *slot = true;
+            }
+        }
+    }
+
+    let unmatched_indices: Vec<usize> = matched_mask
+        .iter()
+        .enumerate()
+        .filter_map(|(idx, matched)| if *matched { None } else { Some(idx) })
+        .collect();
+
+    if !unmatched_indices.is_empty() {
+        let line_number = (input_index + 1) as u32;
+        let consecutive_indices = consecutive_slices(&unmatched_indices);
+        let errors: Vec<crate::error::ParseError> = consecutive_indices
+            .into_iter()
+            .map(|unmatched_input_indices| {
+                let first_idx = *unmatched_input_indices
+                    .first()
+                    .expect("BUG: consecutive_slices never yields an empty group");
+                let last_idx = *unmatched_input_indices
+                    .last()
+                    .expect("BUG: consecutive_slices never yields an empty group");
+                // Collect by char so the unmatched run can never slice across a UTF-8
+                // boundary: `matched_mask` is byte-indexed, so `input_line[first..=last]`
+                // could panic on a non-boundary index.
+                let unmatched_input: String = input_line
+                    .char_indices()
+                    .filter(|(byte_idx, _)| (first_idx..=last_idx).contains(byte_idx))
+                    .map(|(_, ch)| ch)
+                    .collect();
+                crate::error::ParseError {
+                    line: line_number,
+                    text: unmatched_input,
+                }
+            })
+            .collect();
+        return (matched_pitches, errors);
+    }
+
+    (matched_pitches, Vec::new())
+}
+#[cfg(test)]
+mod test_scan_pitches {
+    use super::*;
+
+    #[test]
+    fn returns_pitches_alongside_errors() {
+        let (pitches, errors) = scan_pitches(&test_pitch_regex(), 2, "A2??E3");
+        assert_eq!(pitches, vec![Pitch::A2, Pitch::E3]);
+        assert_eq!(
+            errors,
+            vec![crate::error::ParseError {
+                line: 3,
+                text: "??".to_owned()
+            }]
+        );
+    }
+    #[test]
+    fn no_match_returns_no_pitches() {
+        let (pitches, errors) = scan_pitches(&test_pitch_regex(), 0, "xyz");
+        assert!(pitches.is_empty());
+        assert_eq!(errors.len(), 1);
+    }
+}
+
 /// Splits `numbers` into runs of consecutive values, preserving input order (no sorting).
 fn consecutive_slices(numbers: &[usize]) -> Vec<&[usize]> {
     let mut slice_start = 0;
@@ -1,10 +1,16 @@
 use crate::{
     arrangement::{BeatVec, Line},
-    guitar::{Guitar, PitchFingering},
+    chord_name::{ROOT_NAMES, name_chord},
+    duration::Duration,
+    guitar::{Guitar, Harmonic, PitchFingering},
+    technique::Technique,
 };
 use itertools::Itertools;
+use serde::{Deserialize, Serialize};
 use std::collections::VecDeque;
 use std::fmt::Write;
+#[cfg(feature = "wasm")]
+use tsify::Tsify;
 
 /// Widest fret column the renderer lays down (two-digit frets such as `12`).
 ///
@@ -32,9 +38,24 @@ pub(crate) const fn min_render_width(padding: u8) -> u16 {
 /// new group when they reach `width`), and `padding` controls the number of dashes between
 /// beats. If `playback` is supplied, an indicator `▼`/`▲` is drawn above and below the
 /// beat column corresponding to the 0-indexed beat (counting `Playable` and `Rest` lines,
-/// skipping `MeasureBreak`s).
+/// skipping `MeasureBreak`s and the other structural markers).
 ///
-/// Returns an empty string if `arrangement_lines` is empty or the guitar has no strings.
+/// `RepeatStart` and `RepeatEnd` draw as `|:` and `:|` columns. `Section` and `Ending` take
+/// no column. They print on a label row above the row group, aligned with the column that
+/// follows them, as `[Verse]` or `1.`. A row group without labels has no label row.
+/// `LetRing` also prints on the label row, as `let ring` or `let ring 3`, but over the beat
+/// before it.
+///
+/// A `Technique` draws its symbol on the strings of the beat before it, flush against that
+/// beat, so a hammer-on reads `5h7` and vibrato reads `7~`. A linking technique also sits
+/// flush against the beat after it.
+///
+/// A natural harmonic draws as `<12>` and an artificial one as `5*`. `min_render_width`
+/// budgets for a two-digit fret, so at the minimum width a harmonic column can run up to two
+/// characters past `width`.
+///
+/// Returns an empty string if `arrangement_lines` has no column to draw or the guitar has
+/// no strings.
 #[must_use]
 pub fn render_tab(
     arrangement_lines: &[Line<BeatVec<PitchFingering>>],
@@ -43,12 +64,727 @@ pub fn render_tab(
     padding: u8,
     playback: Option<u16>,
 ) -> String {
-    if arrangement_lines.is_empty() {
+    render_tab_with_options(
+        arrangement_lines,
+        guitar,
+        &RenderOptions {
+            playback,
+            ..RenderOptions::new(width, padding)
+        },
+    )
+}
+
+/// Renders an `Arrangement`'s lines like [`render_tab`], with a chord-name row above each row
+/// group. A chord beat's name, such as `Am7` or `G/B`, prints above its column whenever the
+/// chord changes.
+#[must_use]
+pub fn render_tab_with_chord_names(
+    arrangement_lines: &[Line<BeatVec<PitchFingering>>],
+    guitar: &Guitar,
+    width: u16,
+    padding: u8,
+    playback: Option<u16>,
+) -> String {
+    render_tab_with_options(
+        arrangement_lines,
+        guitar,
+        &RenderOptions {
+            playback,
+            ..RenderOptions::new(width, padding).with_label_mode(LabelMode::ChordNames)
+        },
+    )
+}
+#[cfg(test)]
+mod test_render_tab_with_chord_names {
+    use super::*;
+    use crate::{pitch::Pitch, string_number::StringNumber};
+
+    fn chord(frets: &[(u8, Pitch, u8)]) -> Line<BeatVec<PitchFingering>> {
+        Line::Playable(
+            frets
+                .iter()
+                .map(|&(string_number, pitch, fret)| PitchFingering {
+                    pitch,
+                    string_number: StringNumber::new(string_number).unwrap(),
+                    fret,
+                    harmonic: None,
+                    voice: None,
+                })
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn names_chord_changes() {
+        let e_minor = chord(&[
+            (6, Pitch::E2, 0),
+            (5, Pitch::B2, 2),
+            (4, Pitch::E3, 2),
+            (3, Pitch::G3, 0),
+        ]);
+        let c_over_e = chord(&[(6, Pitch::E2, 0), (5, Pitch::C3, 3), (4, Pitch::G3, 5)]);
+        let lines = vec![
+            Line::Section("A".to_owned()),
+            e_minor.clone(),
+            e_minor,
+            chord(&[(1, Pitch::E4, 0)]),
+            c_over_e,
+        ];
+        let tab = render_tab_with_chord_names(&lines, &Guitar::default(), 20, 1, None);
+        let tab_lines = tab.lines().collect_vec();
+        assert_eq!(tab_lines[0], " Em    C/E");
+        assert_eq!(tab_lines[1], " [A]");
+        assert_eq!(tab_lines[2], "");
+        assert_eq!(tab_lines[3], "-----0--------------");
+        assert_eq!(
+            tab.lines().skip(2).collect_vec(),
+            render_tab(&lines, &Guitar::default(), 20, 1, None)
+                .lines()
+                .skip(1)
+                .collect_vec()
+        );
+    }
+    #[test]
+    fn no_chords_no_row() {
+        let lines = vec![chord(&[(1, Pitch::E4, 0)])];
+        assert_eq!(
+            render_tab_with_chord_names(&lines, &Guitar::default(), 20, 1, None),
+            render_tab(&lines, &Guitar::default(), 20, 1, None)
+        );
+    }
+}
+
+/// Renders an `Arrangement`'s lines like [`render_tab`], labelled for readers without the
+/// input: every row starts with its string's open-string name from the `guitar` tuning, such
+/// as `e|` or `D|`, and a row above the tab numbers the measures at the start and at each
+/// `MeasureBreak`. The string labels come out of `width`, so rows wrap at the same overall
+/// width.
+#[must_use]
+pub fn render_tab_with_labels(
+    arrangement_lines: &[Line<BeatVec<PitchFingering>>],
+    guitar: &Guitar,
+    width: u16,
+    padding: u8,
+    playback: Option<u16>,
+) -> String {
+    render_tab_with_options(
+        arrangement_lines,
+        guitar,
+        &RenderOptions {
+            playback,
+            ..RenderOptions::new(width, padding).with_label_mode(LabelMode::Strings)
+        },
+    )
+}
+#[cfg(test)]
+mod test_render_tab_with_labels {
+    use super::*;
+    use crate::{guitar::create_string_tuning, pitch::Pitch, string_number::StringNumber};
+
+    fn fingering(string_number: u8, fret: u8) -> PitchFingering {
+        PitchFingering {
+            pitch: Pitch::E4,
+            string_number: StringNumber::new(string_number).unwrap(),
+            fret,
+            harmonic: None,
+            voice: None,
+        }
+    }
+
+    fn lines() -> Vec<Line<BeatVec<PitchFingering>>> {
+        vec![
+            Line::Playable(vec![fingering(1, 0)]),
+            Line::Playable(vec![fingering(6, 3)]),
+            Line::MeasureBreak,
+            Line::Playable(vec![fingering(2, 1)]),
+            Line::MeasureBreak,
+            Line::Playable(vec![fingering(3, 2)]),
+            Line::MeasureBreak,
+        ]
+    }
+
+    #[test]
+    fn labels_strings_and_numbers_measures() {
+        let tab = render_tab_with_labels(&lines(), &Guitar::default(), 40, 1, Some(1));
+        assert_eq!(
+            tab,
+            concat!(
+                "   1   2   3\n",
+                "     ▼\n",
+                "e|-0---|---|---|------------------------\n",
+                "B|-----|-1-|---|------------------------\n",
+                "G|-----|---|-2-|------------------------\n",
+                "D|-----|---|---|------------------------\n",
+                "A|-----|---|---|------------------------\n",
+                "E|---3-|---|---|------------------------\n",
+                "     ▲\n",
+            )
+        );
+    }
+    #[test]
+    fn labels_follow_the_tuning_not_the_capo() {
+        let tuning = create_string_tuning(&[
+            Pitch::D4,
+            Pitch::A3,
+            Pitch::FSharpGFlat3,
+            Pitch::D3,
+            Pitch::A2,
+            Pitch::D2,
+        ])
+        .unwrap();
+        let guitar = Guitar::new(tuning, 18, 2).unwrap();
+        let tab = render_tab_with_labels(&lines(), &guitar, 40, 1, None);
+        let prefixes = tab
+            .lines()
+            .skip(2)
+            .take(6)
+            .map(|row| &row[..3])
+            .collect_vec();
+        assert_eq!(prefixes, ["d |", "a |", "F#|", "d |", "A |", "D |"]);
+    }
+    #[test]
+    fn wraps_within_the_width() {
+        let tab = render_tab_with_labels(&lines(), &Guitar::default(), 10, 1, None);
+        assert!(
+            tab.lines().all(|row| row.chars().count() <= 10),
+            "got:\n{tab}"
+        );
+        // Each row group starts with the labels again.
+        assert_eq!(
+            tab.lines().filter(|row| row.starts_with("e|")).count(),
+            4,
+            "got:\n{tab}"
+        );
+    }
+}
+
+/// Renders a header block for a tab: the guitar's tuning from the lowest string up, the capo
+/// fret, and the arrangement's `difficulty`, one per line.
+#[must_use]
+pub fn render_tab_header(guitar: &Guitar, difficulty: f64) -> String {
+    let tuning = guitar
+        .tuning()
+        .collect_vec()
+        .into_iter()
+        .rev()
+        .map(|pitch| ROOT_NAMES[(pitch.index() % 12) as usize])
+        .join(" ");
+    let capo = match guitar.capo() {
+        0 => "none".to_owned(),
+        capo => capo.to_string(),
+    };
+    format!("Tuning: {tuning}\nCapo: {capo}\nDifficulty: {difficulty:.1}\n")
+}
+#[cfg(test)]
+mod test_render_tab_header {
+    use super::*;
+    use crate::{guitar::create_string_tuning, pitch::Pitch};
+
+    #[test]
+    fn standard_tuning_without_capo() {
+        assert_eq!(
+            render_tab_header(&Guitar::default(), 12.25),
+            "Tuning: E A D G B E\nCapo: none\nDifficulty: 12.2\n"
+        );
+    }
+    #[test]
+    fn drop_d_with_capo() {
+        let tuning = create_string_tuning(&[
+            Pitch::E4,
+            Pitch::B3,
+            Pitch::G3,
+            Pitch::D3,
+            Pitch::A2,
+            Pitch::D2,
+        ])
+        .unwrap();
+        let guitar = Guitar::new(tuning, 18, 3).unwrap();
+        assert_eq!(
+            render_tab_header(&guitar, 0.0),
+            "Tuning: D A D G B E\nCapo: 3\nDifficulty: 0.0\n"
+        );
+    }
+}
+
+/// Which optional label rows a rendered tab carries. `Off` by default, which draws the bare
+/// tab.
+#[derive(Debug, Clone, Copy, Default, Hash, PartialEq, Eq, Deserialize)]
+#[cfg_attr(feature = "wasm", derive(Tsify))]
+#[serde(rename_all = "camelCase")]
+pub enum LabelMode {
+    #[default]
+    Off,
+    /// A chord-name row, as [`render_tab_with_chord_names`] draws it.
+    ChordNames,
+    /// String names and measure numbers, as [`render_tab_with_labels`] draws them.
+    Strings,
+    /// Both of the above.
+    All,
+}
+
+/// The character a tab draws its strings with. `Ascii` by default.
+#[derive(Debug, Clone, Copy, Default, Hash, PartialEq, Eq, Deserialize)]
+#[cfg_attr(feature = "wasm", derive(Tsify))]
+#[serde(rename_all = "camelCase")]
+pub enum DashStyle {
+    /// `-`
+    #[default]
+    Ascii,
+    /// `─` (U+2500), which joins into a continuous line in most monospace fonts.
+    Unicode,
+}
+impl DashStyle {
+    fn dash(self) -> char {
+        match self {
+            DashStyle::Ascii => '-',
+            DashStyle::Unicode => '─',
+        }
+    }
+}
+
+/// Everything [`render_tab_with_options`] needs besides the lines and the guitar.
+///
+/// Crosses the WASM boundary via `tsify`, where only `width` and `padding` are required.
+/// Build one with [`RenderOptions::new`] and the `with_*` methods. The defaults match
+/// [`render_tab`]. Every glyph should be a single character column wide, or the rows lose
+/// their alignment.
+///
+/// `ArrangementSet` renders every format from these options. The text, layout and HTML
+/// renders apply every field. The SVG render draws its strings and bars as lines, so it
+/// ignores `bar_char`, `dash_style` and `empty_string_glyph`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[cfg_attr(feature = "wasm", derive(Tsify), tsify(from_wasm_abi))]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct RenderOptions {
+    /// Character width of each row group. Rows wrap to a new group when they reach it.
+    pub width: u16,
+    /// Number of dashes between beats.
+    pub padding: u8,
+    /// 0-indexed beat to draw the `▼`/`▲` playback indicator around. Omitted means none.
+    #[serde(default)]
+    #[cfg_attr(feature = "wasm", tsify(optional))]
+    pub playback: Option<u16>,
+    /// Character for measure breaks and the bars of repeat signs. Omitted means `|`.
+    #[serde(default = "default_bar_char")]
+    #[cfg_attr(feature = "wasm", tsify(optional))]
+    pub bar_char: char,
+    /// Omitted means `"ascii"`.
+    #[serde(default)]
+    #[cfg_attr(feature = "wasm", tsify(optional))]
+    pub dash_style: DashStyle,
+    /// Omitted means `"off"`.
+    #[serde(default)]
+    #[cfg_attr(feature = "wasm", tsify(optional))]
+    pub label_mode: LabelMode,
+    /// Character drawn in a beat's column on each string the beat leaves silent. Omitted
+    /// means the dash of `dash_style`.
+    #[serde(default)]
+    #[cfg_attr(feature = "wasm", tsify(optional))]
+    pub empty_string_glyph: Option<char>,
+    /// Print each beat's duration on a row right above the strings, when the input gives
+    /// durations. Omitted means `false`.
+    #[serde(default)]
+    #[cfg_attr(feature = "wasm", tsify(optional))]
+    pub rhythm_row: bool,
+}
+
+fn default_bar_char() -> char {
+    '|'
+}
+
+impl RenderOptions {
+    /// Builds `RenderOptions` with no playback indicator, ASCII glyphs and no label rows.
+    #[must_use]
+    pub fn new(width: u16, padding: u8) -> Self {
+        Self {
+            width,
+            padding,
+            playback: None,
+            bar_char: default_bar_char(),
+            dash_style: DashStyle::Ascii,
+            label_mode: LabelMode::Off,
+            empty_string_glyph: None,
+            rhythm_row: false,
+        }
+    }
+
+    /// Sets the 0-indexed beat the playback indicator points at.
+    #[must_use]
+    pub fn with_playback(mut self, playback: u16) -> Self {
+        self.playback = Some(playback);
+        self
+    }
+
+    /// Sets the character for measure breaks and repeat bars.
+    #[must_use]
+    pub fn with_bar_char(mut self, bar_char: char) -> Self {
+        self.bar_char = bar_char;
+        self
+    }
+
+    /// Sets whether the strings are drawn with ASCII or Unicode dashes.
+    #[must_use]
+    pub fn with_dash_style(mut self, dash_style: DashStyle) -> Self {
+        self.dash_style = dash_style;
+        self
+    }
+
+    /// Sets which label rows the tab carries.
+    #[must_use]
+    pub fn with_label_mode(mut self, label_mode: LabelMode) -> Self {
+        self.label_mode = label_mode;
+        self
+    }
+
+    /// Sets the character drawn on the strings a beat leaves silent.
+    #[must_use]
+    pub fn with_empty_string_glyph(mut self, glyph: char) -> Self {
+        self.empty_string_glyph = Some(glyph);
+        self
+    }
+
+    /// Sets whether a row above the strings prints each beat's duration.
+    #[must_use]
+    pub fn with_rhythm_row(mut self, rhythm_row: bool) -> Self {
+        self.rhythm_row = rhythm_row;
+        self
+    }
+
+    /// Whether `label_mode` prints the name of each chord beat on a row above the tab
+    /// whenever the chord changes.
+    pub(crate) fn chord_names(&self) -> bool {
+        matches!(self.label_mode, LabelMode::ChordNames | LabelMode::All)
+    }
+
+    /// Whether `label_mode` starts every row with its string's open-string name, such as
+    /// `e|`, and numbers the measures on a row above the tab.
+    pub(crate) fn string_labels(&self) -> bool {
+        matches!(self.label_mode, LabelMode::Strings | LabelMode::All)
+    }
+
+    fn has_default_glyphs(&self) -> bool {
+        self.bar_char == '|'
+            && self.dash_style == DashStyle::Ascii
+            && self.empty_string_glyph.is_none_or(|glyph| glyph == '-')
+    }
+}
+#[cfg(test)]
+mod test_render_options {
+    use super::*;
+
+    #[test]
+    fn builder_sets_every_field() {
+        let options = RenderOptions::new(40, 2)
+            .with_playback(3)
+            .with_bar_char('‖')
+            .with_dash_style(DashStyle::Unicode)
+            .with_label_mode(LabelMode::All)
+            .with_empty_string_glyph('.')
+            .with_rhythm_row(true);
+        assert_eq!(
+            options,
+            RenderOptions {
+                width: 40,
+                padding: 2,
+                playback: Some(3),
+                bar_char: '‖',
+                dash_style: DashStyle::Unicode,
+                label_mode: LabelMode::All,
+                empty_string_glyph: Some('.'),
+                rhythm_row: true,
+            }
+        );
+        assert!(!options.has_default_glyphs());
+        assert!(RenderOptions::new(40, 2).has_default_glyphs());
+    }
+    #[test]
+    fn label_modes_turn_on_rows() {
+        let rows = |mode| {
+            let options = RenderOptions::new(40, 2).with_label_mode(mode);
+            (options.chord_names(), options.string_labels())
+        };
+        assert_eq!(rows(LabelMode::Off), (false, false));
+        assert_eq!(rows(LabelMode::ChordNames), (true, false));
+        assert_eq!(rows(LabelMode::Strings), (false, true));
+        assert_eq!(rows(LabelMode::All), (true, true));
+    }
+    #[test]
+    fn omitted_fields_take_defaults() {
+        let options: RenderOptions = serde_json::from_str(r#"{"width":30,"padding":1}"#).unwrap();
+        assert_eq!(options, RenderOptions::new(30, 1));
+
+        let options: RenderOptions = serde_json::from_str(
+            r#"{"width":30,"padding":1,"playback":2,"barChar":"!","dashStyle":"unicode","labelMode":"chordNames","emptyStringGlyph":" ","rhythmRow":true}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            options,
+            RenderOptions::new(30, 1)
+                .with_playback(2)
+                .with_bar_char('!')
+                .with_dash_style(DashStyle::Unicode)
+                .with_label_mode(LabelMode::ChordNames)
+                .with_empty_string_glyph(' ')
+                .with_rhythm_row(true)
+        );
+    }
+}
+
+/// Renders an `Arrangement`'s lines as an ASCII guitar tab laid out by `options`.
+///
+/// [`render_tab`], [`render_tab_with_chord_names`] and [`render_tab_with_labels`] are this
+/// function with the matching `label_mode` and the default glyphs. The glyph options only
+/// change the string rows. Labels, chord names and string names print as they are.
+#[must_use]
+pub fn render_tab_with_options(
+    arrangement_lines: &[Line<BeatVec<PitchFingering>>],
+    guitar: &Guitar,
+    options: &RenderOptions,
+) -> String {
+    render_tab_with_rhythm(arrangement_lines, &[], guitar, options)
+}
+#[cfg(test)]
+mod test_render_tab_with_options {
+    use super::*;
+    use crate::{pitch::Pitch, string_number::StringNumber};
+
+    fn fingering(string_number: u8, fret: u8) -> PitchFingering {
+        PitchFingering {
+            pitch: Pitch::E4,
+            string_number: StringNumber::new(string_number).unwrap(),
+            fret,
+            harmonic: None,
+            voice: None,
+        }
+    }
+
+    fn lines() -> Vec<Line<BeatVec<PitchFingering>>> {
+        vec![
+            Line::Playable(vec![fingering(1, 12), fingering(2, 0)]),
+            Line::MeasureBreak,
+            Line::Rest,
+            Line::RepeatEnd,
+        ]
+    }
+
+    #[test]
+    fn defaults_match_render_tab() {
+        let guitar = Guitar::default();
+        assert_eq!(
+            render_tab_with_options(
+                &lines(),
+                &guitar,
+                &RenderOptions::new(20, 1).with_playback(1)
+            ),
+            render_tab(&lines(), &guitar, 20, 1, Some(1))
+        );
+    }
+
+    #[test]
+    fn glyphs_replace_the_string_rows() {
+        let options = RenderOptions::new(16, 1)
+            .with_bar_char('‖')
+            .with_dash_style(DashStyle::Unicode)
+            .with_empty_string_glyph('·');
+        let tab = render_tab_with_options(&lines(), &Guitar::default(), &options);
+        let tab_lines = tab.lines().collect_vec();
+        assert_eq!(tab_lines[1], "─12─‖─·─:‖──────");
+        assert_eq!(tab_lines[2], "──0─‖─·─:‖──────");
+        assert_eq!(tab_lines[3], "─··─‖─·─:‖──────");
+        // Every row keeps its width in characters.
+        assert!(tab_lines[1..7].iter().all(|row| row.chars().count() == 16));
+    }
+    #[test]
+    fn glyphs_skip_labels() {
+        let lines = vec![
+            Line::Section("A-B".to_owned()),
+            Line::Playable(vec![fingering(1, 0)]),
+        ];
+        let options = RenderOptions::new(12, 1)
+            .with_dash_style(DashStyle::Unicode)
+            .with_label_mode(LabelMode::Strings);
+        let tab = render_tab_with_options(&lines, &Guitar::default(), &options);
+        let tab_lines = tab.lines().collect_vec();
+        assert_eq!(tab_lines[0], "   1");
+        assert_eq!(tab_lines[1], "   [A-B]");
+        assert_eq!(tab_lines[3], "e|─0────────");
+        assert_eq!(tab_lines[4], "B|──────────");
+    }
+}
+
+/// Renders an `Arrangement`'s lines like [`render_tab_with_options`], spaced by rhythm.
+///
+/// `durations` holds the duration of each line, indexed like `arrangement_lines`, as
+/// [`crate::ParsedLine::duration`] holds them. A beat without one keeps the duration of the beat
+/// before it, and beats before the first duration keep `padding`. The shortest beat gets
+/// `padding` after a one-character fret, and the others room in proportion to their length,
+/// so a half note takes twice the room of a quarter note. With `options.rhythm_row`, each
+/// beat's duration prints on a row right above the strings, as in `q  e e h`.
+#[must_use]
+pub fn render_tab_with_rhythm(
+    arrangement_lines: &[Line<BeatVec<PitchFingering>>],
+    durations: &[Option<Duration>],
+    guitar: &Guitar,
+    options: &RenderOptions,
+) -> String {
+    let Some(layout) = lay_out_tab(arrangement_lines, guitar, options, durations) else {
         return String::new();
+    };
+    render_string_output(
+        &layout.rows_by_string,
+        layout.playback_indicator_position,
+        &layout.heading_rows().map(|(_, rows)| rows),
+    )
+}
+#[cfg(test)]
+mod test_render_tab_with_rhythm {
+    use super::*;
+    use crate::{pitch::Pitch, string_number::StringNumber};
+
+    fn beat(fret: u8) -> Line<BeatVec<PitchFingering>> {
+        Line::Playable(vec![PitchFingering {
+            pitch: Pitch::E4,
+            string_number: StringNumber::new(1).unwrap(),
+            fret,
+            harmonic: None,
+            voice: None,
+        }])
+    }
+
+    #[test]
+    fn spaces_beats_by_length() {
+        let lines = vec![beat(0), beat(0), beat(0), beat(1)];
+        let durations = ["q", "e", "", "h"].map(Duration::from_symbol);
+        let options = RenderOptions::new(20, 1).with_rhythm_row(true);
+        let tab = render_tab_with_rhythm(&lines, &durations, &Guitar::default(), &options);
+        let tab_lines = tab.lines().collect_vec();
+        // The third beat keeps the eighth before it.
+        assert_eq!(tab_lines[0], " q   e e h");
+        assert_eq!(tab_lines[2], "-0---0-0-1----------");
+        assert_eq!(
+            render_tab_with_rhythm(
+                &lines,
+                &durations,
+                &Guitar::default(),
+                &options.with_rhythm_row(false)
+            )
+            .lines()
+            .collect_vec(),
+            tab_lines[1..]
+        );
+    }
+    #[test]
+    fn no_durations_match_render_tab() {
+        let lines = vec![beat(0), Line::MeasureBreak, beat(3)];
+        let options = RenderOptions::new(20, 1).with_rhythm_row(true);
+        assert_eq!(
+            render_tab_with_rhythm(&lines, &[None, None, None], &Guitar::default(), &options),
+            render_tab(&lines, &Guitar::default(), 20, 1, None)
+        );
+    }
+    #[test]
+    fn long_beats_wrap_at_the_width() {
+        let lines = vec![beat(0), beat(2), beat(3)];
+        let durations = ["s", "w", "s"].map(Duration::from_symbol);
+        let tab = render_tab_with_rhythm(
+            &lines,
+            &durations,
+            &Guitar::default(),
+            &RenderOptions::new(12, 1),
+        );
+        let tab_lines = tab.lines().collect_vec();
+        assert_eq!(tab_lines[1], "-0-2--------");
+        // The whole note's gap stops at the row's end, and the next beat wraps.
+        assert_eq!(tab_lines[10], "-3----------");
+    }
+}
+
+/// One column of a laid-out tab: the line it came from, its beat number when the line is a
+/// beat, its text on each string, and where it landed.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct LaidOutColumn {
+    /// Index of the originating line in the arrangement's lines.
+    pub(crate) line_index: usize,
+    /// 0-indexed beat number, counted like the `playback` argument. `None` for a bar line,
+    /// repeat or technique column.
+    pub(crate) beat_index: Option<usize>,
+    /// The column's text on each string, from string 1 down. All cells share one width.
+    pub(crate) cells: Vec<String>,
+    pub(crate) position: ColumnPosition,
+}
+impl LaidOutColumn {
+    /// Character width of the column in a row.
+    pub(crate) fn width(&self) -> usize {
+        self.cells.first().map_or(0, String::len)
+    }
+}
+
+/// The ASCII tab laid out into wrapped row groups, shared by every output format so they
+/// all wrap at the same beats.
+#[derive(Debug)]
+pub(crate) struct TabLayout {
+    /// One row per row group, for each string.
+    pub(crate) rows_by_string: Vec<Vec<String>>,
+    pub(crate) columns: Vec<LaidOutColumn>,
+    /// One label row per row group. Empty for a row group without labels.
+    pub(crate) label_rows: Vec<String>,
+    /// One chord-name row per row group, drawn above the label row. Empty for a row group
+    /// without chord names.
+    pub(crate) chord_rows: Vec<String>,
+    /// One measure-number row per row group, drawn above the chord-name row. Empty for a row
+    /// group without measure numbers.
+    pub(crate) measure_rows: Vec<String>,
+    /// One rhythm row per row group, drawn below the label row. Empty for a row group without
+    /// durations.
+    pub(crate) rhythm_rows: Vec<String>,
+    /// The label every row of each string starts with, such as `e|`. Empty without labels.
+    pub(crate) string_labels: Vec<String>,
+    pub(crate) playback_indicator_position: Option<PlaybackIndicatorPosition>,
+}
+impl TabLayout {
+    /// The rows printed above each row group's strings, top first, with the class the SVG
+    /// renderer gives each.
+    pub(crate) fn heading_rows(&self) -> [(&'static str, &[String]); 4] {
+        [
+            ("measure-number", &self.measure_rows),
+            ("chord-name", &self.chord_rows),
+            ("label", &self.label_rows),
+            ("rhythm", &self.rhythm_rows),
+        ]
+    }
+
+    pub(crate) fn num_row_groups(&self) -> usize {
+        self.rows_by_string.first().map_or(0, Vec::len)
     }
+
+    /// Character width of every row.
+    pub(crate) fn row_width(&self) -> usize {
+        self.rows_by_string
+            .first()
+            .and_then(|rows| rows.first())
+            .map_or(0, |row| row.chars().count())
+    }
+}
+
+/// Lays `arrangement_lines` out the way [`render_tab_with_rhythm`] draws them, with the rows
+/// and glyphs `options` turns on. `durations` is indexed like the lines, and empty leaves the
+/// tab unspaced. Returns `None` when there is no column to draw or the guitar has no strings.
+pub(crate) fn lay_out_tab(
+    arrangement_lines: &[Line<BeatVec<PitchFingering>>],
+    guitar: &Guitar,
+    options: &RenderOptions,
+    durations: &[Option<Duration>],
+) -> Option<TabLayout> {
+    let RenderOptions {
+        width,
+        padding,
+        playback,
+        ..
+    } = *options;
     let num_strings = guitar.string_ranges.len();
-    if num_strings == 0 {
-        return String::new();
+    if arrangement_lines.is_empty() || num_strings == 0 {
+        return None;
     }
 
     let line_index_of_playback: Option<usize> = match playback {
@@ -58,17 +794,216 @@ pub fn render_tab(
         }
     };
 
-    let columns = arrangement_lines
-        .iter()
-        .map(|line| render_line(line, num_strings))
-        .collect_vec();
+    // Label lines take no column, so column indices drift from line indices after the
+    // first label. Track both the playback column and each label's column here.
+    let mut columns: Vec<Vec<String>> = Vec::with_capacity(arrangement_lines.len());
+    let mut column_line_indices: Vec<usize> = Vec::with_capacity(arrangement_lines.len());
+    let mut column_beat_indices: Vec<Option<usize>> = Vec::with_capacity(arrangement_lines.len());
+    let mut joins_previous: Vec<bool> = Vec::with_capacity(arrangement_lines.len());
+    let mut labels: Vec<(usize, String)> = vec![];
+    let mut chord_names: Vec<(usize, String)> = vec![];
+    let mut measure_numbers: Vec<(usize, String)> = vec![];
+    let mut rhythms: Vec<(usize, String)> = vec![];
+    let mut column_durations: Vec<Option<Duration>> = Vec::with_capacity(arrangement_lines.len());
+    let mut duration: Option<Duration> = None;
+    let mut column_index_of_playback: Option<usize> = None;
+    let mut previous_beat: Option<&Line<BeatVec<PitchFingering>>> = None;
+    let mut join_next = false;
+    let mut num_beats = 0;
+    for (line_index, line) in arrangement_lines.iter().enumerate() {
+        if let Some(label) = render_label(line) {
+            // A let-ring label marks the beat before it rather than the column after it.
+            let column = match line {
+                Line::LetRing(_) => columns.len().saturating_sub(1),
+                _ => columns.len(),
+            };
+            labels.push((column, label));
+            continue;
+        }
+        if line_index_of_playback == Some(line_index) {
+            column_index_of_playback = Some(columns.len());
+        }
+        column_line_indices.push(line_index);
+        if let Line::Technique(technique) = line {
+            columns.push(render_technique(*technique, previous_beat, num_strings));
+            column_beat_indices.push(None);
+            column_durations.push(None);
+            joins_previous.push(true);
+            join_next = technique.links_beats();
+            continue;
+        }
+        if let (true, Line::Playable(fingerings)) = (options.chord_names(), line) {
+            let pitches = fingerings.iter().map(|f| f.pitch).collect_vec();
+            if let Some(name) = name_chord(&pitches) {
+                if chord_names.last().is_none_or(|(_, last)| *last != name) {
+                    chord_names.push((columns.len(), name));
+                }
+            }
+        }
+        if options.string_labels() {
+            if columns.is_empty() {
+                measure_numbers.push((0, "1".to_owned()));
+            } else if let Line::MeasureBreak = line {
+                measure_numbers.push((columns.len(), (measure_numbers.len() + 1).to_string()));
+            }
+        }
+        if line.is_beat() {
+            previous_beat = Some(line);
+            column_beat_indices.push(Some(num_beats));
+            num_beats += 1;
+            if let Some(line_duration) = durations.get(line_index).copied().flatten() {
+                duration = Some(line_duration);
+            }
+            if let (true, Some(duration)) = (options.rhythm_row, duration) {
+                rhythms.push((columns.len(), duration.to_string()));
+            }
+            column_durations.push(duration);
+        } else {
+            column_beat_indices.push(None);
+            column_durations.push(None);
+        }
+        columns.push(render_line(line, num_strings));
+        joins_previous.push(std::mem::take(&mut join_next));
+    }
+    if columns.is_empty() {
+        return None;
+    }
+    labels.sort_by_key(|(column, _)| *column);
+    // A closing measure break starts no measure, so it gets no number.
+    measure_numbers.retain(|(column, _)| *column == 0 || column + 1 < columns.len());
+
+    let column_gaps = rhythm_gaps(&columns, &column_durations, padding);
+    let beat_column_renders = transpose(columns.clone());
 
-    let beat_column_renders = transpose(columns);
+    let string_labels = if options.string_labels() {
+        render_string_labels(guitar)
+    } else {
+        vec![]
+    };
+    let string_label_width = string_labels.first().map_or(0, String::len);
+    // The labels come out of the row width, down to the narrowest width that fits one beat.
+    let content_width = width
+        .saturating_sub(string_label_width as u16)
+        .max(min_render_width(padding));
+
+    let (mut rows_by_string, mut playback_indicator_position, mut column_positions) =
+        render_string_groups(
+            beat_column_renders,
+            content_width,
+            padding,
+            column_index_of_playback,
+            &joins_previous,
+            &column_gaps,
+        );
+    if options.string_labels() {
+        for (single_string_rows, string_label) in rows_by_string.iter_mut().zip(&string_labels) {
+            for row in single_string_rows {
+                row.insert_str(0, string_label);
+            }
+        }
+        for position in &mut column_positions {
+            position.column_index += string_label_width;
+        }
+        if let Some(position) = &mut playback_indicator_position {
+            position.column_index += string_label_width;
+        }
+    }
+
+    let num_row_groups = rows_by_string.first().map_or(0, Vec::len);
+    let label_rows = render_label_rows(&labels, &column_positions, num_row_groups);
+    let chord_rows = render_label_rows(&chord_names, &column_positions, num_row_groups);
+    let measure_rows = render_label_rows(&measure_numbers, &column_positions, num_row_groups);
+    let rhythm_rows = render_label_rows(&rhythms, &column_positions, num_row_groups);
+
+    let columns = columns
+        .into_iter()
+        .zip(column_line_indices)
+        .zip(column_beat_indices)
+        .zip(column_positions)
+        .map(
+            |(((cells, line_index), beat_index), position)| LaidOutColumn {
+                line_index,
+                beat_index,
+                cells,
+                position,
+            },
+        )
+        .collect();
+
+    let mut layout = TabLayout {
+        rows_by_string,
+        columns,
+        label_rows,
+        chord_rows,
+        measure_rows,
+        rhythm_rows,
+        string_labels,
+        playback_indicator_position,
+    };
+    if !options.has_default_glyphs() {
+        apply_glyphs(&mut layout, options);
+    }
+    Some(layout)
+}
+#[cfg(test)]
+mod test_lay_out_tab {
+    use super::*;
+    use crate::{pitch::Pitch, string_number::StringNumber};
 
-    let (rows_by_string, playback_indicator_position) =
-        render_string_groups(beat_column_renders, width, padding, line_index_of_playback);
+    fn fingering(string_number: u8, fret: u8) -> PitchFingering {
+        PitchFingering {
+            pitch: Pitch::E4,
+            string_number: StringNumber::new(string_number).unwrap(),
+            fret,
+            harmonic: None,
+            voice: None,
+        }
+    }
 
-    render_string_output(&rows_by_string, playback_indicator_position)
+    #[test]
+    fn columns_carry_lines_beats_and_positions() {
+        let lines = vec![
+            Line::Section("A".to_owned()),
+            Line::Playable(vec![fingering(1, 0)]),
+            Line::MeasureBreak,
+            Line::Rest,
+            Line::Playable(vec![fingering(2, 12)]),
+        ];
+        let layout =
+            lay_out_tab(&lines, &Guitar::default(), &RenderOptions::new(8, 1), &[]).unwrap();
+
+        assert_eq!(layout.num_row_groups(), 2);
+        assert_eq!(layout.row_width(), 8);
+        let summary = layout
+            .columns
+            .iter()
+            .map(|column| {
+                (
+                    column.line_index,
+                    column.beat_index,
+                    column.width(),
+                    column.position.row_group_index,
+                    column.position.column_index,
+                )
+            })
+            .collect_vec();
+        assert_eq!(
+            summary,
+            vec![
+                (1, Some(0), 1, 0, 1),
+                (2, None, 1, 0, 3),
+                (3, Some(1), 1, 1, 1),
+                (4, Some(2), 2, 1, 3),
+            ]
+        );
+    }
+    #[test]
+    fn nothing_to_draw() {
+        let lines: Vec<Line<BeatVec<PitchFingering>>> = vec![Line::Section("A".to_owned())];
+        let options = RenderOptions::new(20, 1);
+        assert!(lay_out_tab(&lines, &Guitar::default(), &options, &[]).is_none());
+        assert!(lay_out_tab(&[], &Guitar::default(), &options, &[]).is_none());
+    }
 }
 #[cfg(test)]
 mod test_render_tab {
@@ -82,16 +1017,22 @@ mod test_render_tab {
                 pitch: Pitch::E4,
                 string_number: StringNumber::new(1).unwrap(),
                 fret: 0,
+                harmonic: None,
+                voice: None,
             }]),
             Line::Playable(vec![PitchFingering {
                 pitch: Pitch::DSharpEFlat4,
                 string_number: StringNumber::new(2).unwrap(),
                 fret: 4,
+                harmonic: None,
+                voice: None,
             }]),
             Line::Playable(vec![PitchFingering {
                 pitch: Pitch::E4,
                 string_number: StringNumber::new(1).unwrap(),
                 fret: 0,
+                harmonic: None,
+                voice: None,
             }]),
             Line::Rest,
             Line::MeasureBreak,
@@ -99,11 +1040,15 @@ mod test_render_tab {
                 pitch: Pitch::DSharpEFlat4,
                 string_number: StringNumber::new(1).unwrap(),
                 fret: 4,
+                harmonic: None,
+                voice: None,
             }]),
             Line::Playable(vec![PitchFingering {
                 pitch: Pitch::A5,
                 string_number: StringNumber::new(1).unwrap(),
                 fret: 12,
+                harmonic: None,
+                voice: None,
             }]),
         ]
     }
@@ -205,30 +1150,300 @@ mod test_render_tab {
         let lines = vec![Line::Rest, Line::MeasureBreak];
         assert_eq!(render_tab(&lines, &guitar, 20, 1, None), "");
     }
-
+
+    #[test]
+    fn mismatched_guitar_string_count_does_not_panic() {
+        // An arrangement built on a wider guitar, rendered against a narrower one, must drop the
+        // out-of-range fingerings instead of panicking on the per-string slice index.
+        // `get_arrangement_lines` places notes on strings 1 and 2. A 1-string render guitar keeps
+        // string 1 and skips string 2.
+        let arrangement_lines = get_arrangement_lines();
+        let one_string = Guitar::new(
+            crate::guitar::create_string_tuning(&[Pitch::E4]).unwrap(),
+            12,
+            0,
+        )
+        .unwrap();
+        let output = render_tab(&arrangement_lines, &one_string, 20, 1, None);
+        // The render guitar has one string, so string 2's fingering is out of range and dropped:
+        // exactly one string row is laid out, carrying string 1's frets (0, _, 0, |, 4, 12).
+        // `playback` is None, so the indicator rows are blank. Filtering blanks leaves the string
+        // rows only. A regression that kept string 2 would yield two rows and fail this.
+        let string_rows: Vec<&str> = output
+            .lines()
+            .filter(|row| !row.trim().is_empty())
+            .collect();
+        assert_eq!(string_rows, vec!["-0---0---|-4-12-----"]);
+    }
+
+    #[test]
+    fn repeats_and_labels() {
+        let fingering = PitchFingering {
+            pitch: Pitch::E4,
+            string_number: StringNumber::new(1).unwrap(),
+            fret: 0,
+            harmonic: None,
+            voice: None,
+        };
+        let arrangement_lines = vec![
+            Line::Section("Verse".to_owned()),
+            Line::RepeatStart,
+            Line::Playable(vec![fingering]),
+            Line::Ending(1),
+            Line::Playable(vec![fingering]),
+            Line::RepeatEnd,
+            Line::Ending(2),
+            Line::Rest,
+        ];
+        let guitar = Guitar::new(
+            crate::guitar::create_string_tuning(&[Pitch::E4, Pitch::B3]).unwrap(),
+            12,
+            0,
+        )
+        .unwrap();
+
+        // Playback beat 1 is the second `Playable`, past two label lines and a repeat.
+        let output = render_tab(&arrangement_lines, &guitar, 20, 1, Some(1));
+
+        // `1.` attaches to column 6 but `[Verse]` runs to column 8, so it shifts right.
+        let expected_output = concat!(
+            " [Verse] 1. 2.\n",
+            "      ▼\n",
+            "-|:-0-0-:|----------\n",
+            "-|:-----:|----------\n",
+            "      ▲\n",
+        );
+        assert_eq!(output, expected_output);
+    }
+
+    #[test]
+    fn techniques_sit_flush_against_their_beats() {
+        let fingering = |fret: u8| PitchFingering {
+            pitch: Pitch::E4,
+            string_number: StringNumber::new(1).unwrap(),
+            fret,
+            harmonic: None,
+            voice: None,
+        };
+        let arrangement_lines = vec![
+            Line::Playable(vec![fingering(5)]),
+            Line::Technique(Technique::HammerOn),
+            Line::Playable(vec![fingering(7)]),
+            Line::Technique(Technique::Vibrato),
+            Line::Playable(vec![fingering(9)]),
+        ];
+        let guitar = Guitar::new(
+            crate::guitar::create_string_tuning(&[Pitch::E4, Pitch::B3]).unwrap(),
+            12,
+            0,
+        )
+        .unwrap();
+
+        let output = render_tab(&arrangement_lines, &guitar, 14, 1, Some(1));
+
+        let expected_output = concat!("   ▼\n", "-5h7~-9-------\n", "--------------\n", "   ▲\n",);
+        assert_eq!(output, expected_output);
+    }
+
+    #[test]
+    fn only_labels_renders_empty() {
+        let lines: Vec<Line<BeatVec<PitchFingering>>> = vec![Line::Section("Intro".to_owned())];
+        assert_eq!(render_tab(&lines, &Guitar::default(), 20, 1, None), "");
+    }
+}
+
+/// Where one beat landed in a tab from [`render_tab_with_layout`]. The beat's cells run from
+/// `start.column_index` up to, not including, `end_column_index` on every string row of
+/// row group `start.row_group_index`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "wasm", derive(Tsify))]
+#[serde(rename_all = "camelCase")]
+pub struct BeatPosition {
+    /// 0-indexed beat number, counted like the `playback` argument.
+    pub beat_index: usize,
+    pub start: ColumnPosition,
+    pub end_column_index: usize,
+    /// 1-indexed line the beat came from: its line in `arrangement_lines` for
+    /// [`render_tab_with_layout`], and its input line for `ArrangementSet::render_with_layout`.
+    pub line: u32,
+}
+
+/// A rendered ASCII tab with a map of where each beat landed in it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "wasm", derive(Tsify), tsify(into_wasm_abi))]
+#[serde(rename_all = "camelCase")]
+pub struct RenderedTab {
+    /// The tab exactly as [`render_tab`] renders it, or as [`render_tab_with_options`] does
+    /// for `ArrangementSet::render_with_layout`.
+    pub tab: String,
+    /// One entry per beat, in beat order.
+    pub beats: Vec<BeatPosition>,
+    /// 0-indexed line of `tab` holding the first string's row, for each row group.
+    pub row_group_lines: Vec<usize>,
+    pub playback_indicator: Option<PlaybackIndicatorPosition>,
+}
+
+/// Renders the tab like [`render_tab`] and also returns where each beat landed, so a UI can
+/// overlay cursors and selections on the text without re-rendering with `playback`.
+#[must_use]
+pub fn render_tab_with_layout(
+    arrangement_lines: &[Line<BeatVec<PitchFingering>>],
+    guitar: &Guitar,
+    width: u16,
+    padding: u8,
+    playback: Option<u16>,
+) -> RenderedTab {
+    render_tab_with_layout_and_options(
+        arrangement_lines,
+        &[],
+        guitar,
+        &RenderOptions {
+            playback,
+            ..RenderOptions::new(width, padding)
+        },
+    )
+}
+
+/// Renders the tab like [`render_tab_with_rhythm`] and also returns where each beat landed,
+/// like [`render_tab_with_layout`]. Every field of `options` applies. The glyphs replace
+/// characters one for one, so the positions count characters, not bytes.
+pub(crate) fn render_tab_with_layout_and_options(
+    arrangement_lines: &[Line<BeatVec<PitchFingering>>],
+    durations: &[Option<Duration>],
+    guitar: &Guitar,
+    options: &RenderOptions,
+) -> RenderedTab {
+    let Some(layout) = lay_out_tab(arrangement_lines, guitar, options, durations) else {
+        return RenderedTab {
+            tab: String::new(),
+            beats: vec![],
+            row_group_lines: vec![],
+            playback_indicator: None,
+        };
+    };
+
+    let beats = layout
+        .columns
+        .iter()
+        .filter_map(|column| {
+            column.beat_index.map(|beat_index| BeatPosition {
+                beat_index,
+                start: column.position,
+                end_column_index: column.position.column_index + column.width(),
+                line: u32::try_from(column.line_index + 1).unwrap_or(u32::MAX),
+            })
+        })
+        .collect();
+
+    // Each row group is its optional heading rows, the `▼` line, the string rows, the `▲`
+    // line and a blank line, as `render_string_output` writes them.
+    let num_strings = layout.rows_by_string.len();
+    let mut row_group_lines = Vec::with_capacity(layout.num_row_groups());
+    let mut line = 0;
+    for row_group_index in 0..layout.num_row_groups() {
+        for (_, heading_rows) in layout.heading_rows() {
+            if heading_rows
+                .get(row_group_index)
+                .is_some_and(|row| !row.is_empty())
+            {
+                line += 1;
+            }
+        }
+        row_group_lines.push(line + 1);
+        line += num_strings + 3;
+    }
+
+    RenderedTab {
+        tab: render_string_output(
+            &layout.rows_by_string,
+            layout.playback_indicator_position,
+            &layout.heading_rows().map(|(_, rows)| rows),
+        ),
+        beats,
+        row_group_lines,
+        playback_indicator: layout.playback_indicator_position,
+    }
+}
+#[cfg(test)]
+mod test_render_tab_with_layout {
+    use super::*;
+    use crate::{pitch::Pitch, string_number::StringNumber};
+
+    fn fingering(string_number: u8, fret: u8) -> PitchFingering {
+        PitchFingering {
+            pitch: Pitch::E4,
+            string_number: StringNumber::new(string_number).unwrap(),
+            fret,
+            harmonic: None,
+            voice: None,
+        }
+    }
+
+    fn lines() -> Vec<Line<BeatVec<PitchFingering>>> {
+        vec![
+            Line::Playable(vec![fingering(1, 0)]),
+            Line::Section("B".to_owned()),
+            Line::Playable(vec![fingering(2, 12)]),
+            Line::MeasureBreak,
+            Line::Rest,
+        ]
+    }
+
+    #[test]
+    fn tab_matches_render_tab() {
+        for playback in [None, Some(0), Some(2)] {
+            assert_eq!(
+                render_tab_with_layout(&lines(), &Guitar::default(), 8, 1, playback).tab,
+                render_tab(&lines(), &Guitar::default(), 8, 1, playback)
+            );
+        }
+    }
+    #[test]
+    fn maps_each_beat_onto_the_tab_text() {
+        let rendered = render_tab_with_layout(&lines(), &Guitar::default(), 8, 1, Some(1));
+        let tab_lines = rendered.tab.lines().collect_vec();
+
+        assert_eq!(
+            rendered.beats.iter().map(|beat| beat.line).collect_vec(),
+            vec![1, 3, 5]
+        );
+        for beat in &rendered.beats {
+            let first_string_line = rendered.row_group_lines[beat.start.row_group_index];
+            let row = tab_lines[first_string_line];
+            assert!(row.starts_with('-'), "{row:?} is a string row");
+            let cell = &row[beat.start.column_index..beat.end_column_index];
+            assert!(
+                !cell.contains('|'),
+                "beat {} lands on {cell:?}",
+                beat.beat_index
+            );
+        }
+        let second_beat = &rendered.beats[1];
+        let row = tab_lines[rendered.row_group_lines[second_beat.start.row_group_index] + 1];
+        assert_eq!(
+            &row[second_beat.start.column_index..second_beat.end_column_index],
+            "12"
+        );
+    }
     #[test]
-    fn mismatched_guitar_string_count_does_not_panic() {
-        // An arrangement built on a wider guitar, rendered against a narrower one, must drop the
-        // out-of-range fingerings instead of panicking on the per-string slice index.
-        // `get_arrangement_lines` places notes on strings 1 and 2. A 1-string render guitar keeps
-        // string 1 and skips string 2.
-        let arrangement_lines = get_arrangement_lines();
-        let one_string = Guitar::new(
-            crate::guitar::create_string_tuning(&[Pitch::E4]).unwrap(),
-            12,
-            0,
-        )
-        .unwrap();
-        let output = render_tab(&arrangement_lines, &one_string, 20, 1, None);
-        // The render guitar has one string, so string 2's fingering is out of range and dropped:
-        // exactly one string row is laid out, carrying string 1's frets (0, _, 0, |, 4, 12).
-        // `playback` is None, so the indicator rows are blank. Filtering blanks leaves the string
-        // rows only. A regression that kept string 2 would yield two rows and fail this.
-        let string_rows: Vec<&str> = output
-            .lines()
-            .filter(|row| !row.trim().is_empty())
-            .collect();
-        assert_eq!(string_rows, vec!["-0---0---|-4-12-----"]);
+    fn reports_the_playback_indicator() {
+        let rendered = render_tab_with_layout(&lines(), &Guitar::default(), 40, 1, Some(1));
+        let indicator = rendered.playback_indicator.unwrap();
+        let beat = &rendered.beats[1];
+        assert_eq!(indicator.row_group_index, beat.start.row_group_index);
+        assert!((beat.start.column_index..beat.end_column_index).contains(&indicator.column_index));
+        assert!(
+            render_tab_with_layout(&lines(), &Guitar::default(), 40, 1, None)
+                .playback_indicator
+                .is_none()
+        );
+    }
+    #[test]
+    fn nothing_to_draw() {
+        let rendered = render_tab_with_layout(&[], &Guitar::default(), 20, 1, None);
+        assert!(rendered.tab.is_empty());
+        assert!(rendered.beats.is_empty());
+        assert!(rendered.row_group_lines.is_empty());
     }
 }
 
@@ -239,7 +1454,7 @@ fn line_index_of_beat_index(
     lines
         .iter()
         .enumerate()
-        .filter(|(_, line)| matches!(line, Line::Playable(_) | Line::Rest))
+        .filter(|(_, line)| line.is_beat())
         .map(|(index, _)| index)
         .nth(playback_beat_index)
 }
@@ -266,33 +1481,45 @@ mod test_line_index_of_beat_index {
                 string_number: StringNumber::new(1).unwrap(),
                 fret: 6,
                 pitch: Pitch::E4,
+                harmonic: None,
+                voice: None,
             }]),
             Line::Playable(vec![PitchFingering {
                 string_number: StringNumber::new(1).unwrap(),
                 fret: 6,
                 pitch: Pitch::E4,
+                harmonic: None,
+                voice: None,
             }]),
             Line::Rest,
             Line::Playable(vec![PitchFingering {
                 string_number: StringNumber::new(1).unwrap(),
                 fret: 6,
                 pitch: Pitch::E4,
+                harmonic: None,
+                voice: None,
             }]),
             Line::MeasureBreak,
             Line::Playable(vec![PitchFingering {
                 string_number: StringNumber::new(1).unwrap(),
                 fret: 6,
                 pitch: Pitch::E4,
+                harmonic: None,
+                voice: None,
             }]),
             Line::Playable(vec![PitchFingering {
                 string_number: StringNumber::new(1).unwrap(),
                 fret: 6,
                 pitch: Pitch::E4,
+                harmonic: None,
+                voice: None,
             }]),
             Line::Playable(vec![PitchFingering {
                 string_number: StringNumber::new(1).unwrap(),
                 fret: 6,
                 pitch: Pitch::E4,
+                harmonic: None,
+                voice: None,
             }]),
         ]
     }
@@ -311,6 +1538,95 @@ mod test_line_index_of_beat_index {
         let lines = get_lines();
         assert_eq!(line_index_of_beat_index(&lines, 4), Some(5));
     }
+    #[test]
+    fn exclude_markers() {
+        let lines: Vec<Line<BeatVec<PitchFingering>>> = vec![
+            Line::Section("A".to_owned()),
+            Line::RepeatStart,
+            Line::Rest,
+            Line::Ending(1),
+            Line::Rest,
+        ];
+        assert_eq!(line_index_of_beat_index(&lines, 1), Some(4));
+    }
+}
+
+/// Returns the label-row text for a `Section` (`[Verse]`), `Ending` (`1.`) or `LetRing`
+/// (`let ring 3`) line, and `None` for every line that draws a column.
+fn render_label(line: &Line<BeatVec<PitchFingering>>) -> Option<String> {
+    match line {
+        Line::Section(label) => Some(format!("[{label}]")),
+        Line::Ending(number) => Some(format!("{number}.")),
+        Line::LetRing(None) => Some("let ring".to_owned()),
+        Line::LetRing(Some(beats)) => Some(format!("let ring {beats}")),
+        Line::MeasureBreak
+        | Line::RepeatStart
+        | Line::RepeatEnd
+        | Line::Technique(_)
+        | Line::Rest
+        | Line::Playable(_) => None,
+    }
+}
+#[cfg(test)]
+mod test_render_label {
+    use super::*;
+
+    #[test]
+    fn section_and_ending() {
+        assert_eq!(
+            render_label(&Line::Section("Chorus".to_owned())),
+            Some("[Chorus]".to_owned())
+        );
+        assert_eq!(render_label(&Line::Ending(2)), Some("2.".to_owned()));
+    }
+    #[test]
+    fn let_ring() {
+        assert_eq!(
+            render_label(&Line::LetRing(None)),
+            Some("let ring".to_owned())
+        );
+        assert_eq!(
+            render_label(&Line::LetRing(Some(3))),
+            Some("let ring 3".to_owned())
+        );
+    }
+    #[test]
+    fn column_lines_have_no_label() {
+        assert_eq!(render_label(&Line::RepeatStart), None);
+        assert_eq!(render_label(&Line::Rest), None);
+    }
+}
+
+/// Escapes `text` for use in XML or HTML text and attribute values.
+pub(crate) fn escape_markup(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+#[cfg(test)]
+mod test_escape_markup {
+    use super::*;
+
+    #[test]
+    fn escapes_markup_characters() {
+        assert_eq!(
+            escape_markup(r#"<a href="x">Tom & 'Jerry'</a>"#),
+            "&lt;a href=&quot;x&quot;&gt;Tom &amp; &#39;Jerry&#39;&lt;/a&gt;"
+        );
+    }
+    #[test]
+    fn leaves_plain_text() {
+        assert_eq!(escape_markup("[Verse] 1."), "[Verse] 1.");
+    }
 }
 
 /// Renders Line as a vector of strings representing the fret positions on a guitar.
@@ -322,6 +1638,13 @@ mod test_line_index_of_beat_index {
 fn render_line(line: &Line<BeatVec<PitchFingering>>, num_strings: usize) -> Vec<String> {
     let pitch_fingerings = match line {
         Line::MeasureBreak => return vec!["|".to_owned(); num_strings],
+        Line::RepeatStart => return vec!["|:".to_owned(); num_strings],
+        Line::RepeatEnd => return vec![":|".to_owned(); num_strings],
+        // `render_tab` lifts labels onto the label row, so they take no column, and draws
+        // techniques with `render_technique`.
+        Line::Section(_) | Line::Ending(_) | Line::LetRing(_) | Line::Technique(_) => {
+            return vec![String::new(); num_strings];
+        }
         Line::Rest => return vec!["-".to_owned(); num_strings],
         Line::Playable(pitch_fingerings) => pitch_fingerings.iter().sorted().collect_vec(),
     };
@@ -338,7 +1661,7 @@ fn render_line(line: &Line<BeatVec<PitchFingering>>, num_strings: usize) -> Vec<
     for fingering in pitch_fingerings {
         let string_index = fingering.string_number.get() as usize - 1;
         if let Some(slot) = playable_render.get_mut(string_index) {
-            *slot = render_fret(fingering.fret, fret_width_max);
+            *slot = render_fingering(fingering, fret_width_max);
         }
     }
 
@@ -359,6 +1682,17 @@ mod test_render_line {
         );
     }
     #[test]
+    fn repeat_markers() {
+        assert_eq!(
+            render_line(&Line::RepeatStart, NUM_STRINGS),
+            vec!["|:".to_owned(); NUM_STRINGS]
+        );
+        assert_eq!(
+            render_line(&Line::RepeatEnd, NUM_STRINGS),
+            vec![":|".to_owned(); NUM_STRINGS]
+        );
+    }
+    #[test]
     fn rest() {
         assert_eq!(
             render_line(&Line::Rest, NUM_STRINGS),
@@ -372,11 +1706,15 @@ mod test_render_line {
                 string_number: StringNumber::new(2).unwrap(),
                 fret: 2,
                 pitch: Pitch::G4,
+                harmonic: None,
+                voice: None,
             },
             PitchFingering {
                 string_number: StringNumber::new(5).unwrap(),
                 fret: 13,
                 pitch: Pitch::G4,
+                harmonic: None,
+                voice: None,
             },
         ];
         let expected_line_render = vec!["--", "-2", "--", "--", "13", "--"];
@@ -393,36 +1731,50 @@ mod test_render_line {
                 string_number: StringNumber::new(1).unwrap(),
                 fret: 9,
                 pitch: Pitch::G4,
+                harmonic: None,
+                voice: None,
             },
             PitchFingering {
                 string_number: StringNumber::new(2).unwrap(),
                 fret: 0,
                 pitch: Pitch::G4,
+                harmonic: None,
+                voice: None,
             },
             PitchFingering {
                 string_number: StringNumber::new(4).unwrap(),
                 fret: 8,
                 pitch: Pitch::G4,
+                harmonic: None,
+                voice: None,
             },
             PitchFingering {
                 string_number: StringNumber::new(5).unwrap(),
                 fret: 10,
                 pitch: Pitch::G4,
+                harmonic: None,
+                voice: None,
             },
             PitchFingering {
                 string_number: StringNumber::new(6).unwrap(),
                 fret: 0,
                 pitch: Pitch::G4,
+                harmonic: None,
+                voice: None,
             },
             PitchFingering {
                 string_number: StringNumber::new(7).unwrap(),
                 fret: 11,
                 pitch: Pitch::G4,
+                harmonic: None,
+                voice: None,
             },
             PitchFingering {
                 string_number: StringNumber::new(8).unwrap(),
                 fret: 12,
                 pitch: Pitch::G4,
+                harmonic: None,
+                voice: None,
             },
         ];
         let expected_line_render = vec!["-9", "-0", "--", "-8", "10", "-0", "11", "12"];
@@ -441,11 +1793,15 @@ mod test_render_line {
                 string_number: StringNumber::new(1).unwrap(),
                 fret: 9,
                 pitch: Pitch::G4,
+                harmonic: None,
+                voice: None,
             },
             PitchFingering {
                 string_number: StringNumber::new(2).unwrap(),
                 fret: 0,
                 pitch: Pitch::G4,
+                harmonic: None,
+                voice: None,
             },
         ];
         assert_eq!(render_line(&Line::Playable(pitch_fingerings), 1), vec!["9"]);
@@ -461,6 +1817,53 @@ mod test_render_line {
     }
 }
 
+/// Renders a technique column: its symbol on every string `previous_beat` plays, and a dash
+/// on the rest. Without a previous playable beat the column is all dashes.
+fn render_technique(
+    technique: Technique,
+    previous_beat: Option<&Line<BeatVec<PitchFingering>>>,
+    num_strings: usize,
+) -> Vec<String> {
+    let mut technique_render = vec!["-".to_owned(); num_strings];
+    if let Some(Line::Playable(pitch_fingerings)) = previous_beat {
+        for fingering in pitch_fingerings {
+            let string_index = fingering.string_number.get() as usize - 1;
+            if let Some(slot) = technique_render.get_mut(string_index) {
+                *slot = technique.symbol().to_string();
+            }
+        }
+    }
+    technique_render
+}
+#[cfg(test)]
+mod test_render_technique {
+    use super::*;
+    use crate::{pitch::Pitch, string_number::StringNumber};
+
+    #[test]
+    fn marks_the_previous_beat_strings() {
+        let previous_beat = Line::Playable(vec![PitchFingering {
+            pitch: Pitch::B3,
+            string_number: StringNumber::new(2).unwrap(),
+            fret: 0,
+            harmonic: None,
+            voice: None,
+        }]);
+        assert_eq!(
+            render_technique(Technique::HammerOn, Some(&previous_beat), 3),
+            vec!["-", "h", "-"]
+        );
+    }
+    #[test]
+    fn no_previous_beat_draws_dashes() {
+        assert_eq!(render_technique(Technique::Slide, None, 2), vec!["-", "-"]);
+        assert_eq!(
+            render_technique(Technique::Bend, Some(&Line::Rest), 2),
+            vec!["-", "-"]
+        );
+    }
+}
+
 /// Creates a string with the fret number padded with dashes to match the maximum width.
 ///
 /// Through the public render path `fret <= Guitar::MAX_NUM_FRETS`, which the file-level
@@ -514,19 +1917,76 @@ mod test_render_fret {
     }
 }
 
-/// Calculates the maximum width of the string representations of fret numbers in a given array of pitch fingerings.
+/// Renders a fingering padded with dashes to `fret_width_max`. A natural harmonic renders as
+/// its touched fret in angle brackets (`<12>`) and an artificial harmonic as its fretted fret
+/// with a trailing star (`5*`).
+fn render_fingering(fingering: &PitchFingering, fret_width_max: usize) -> String {
+    let label = match fingering.harmonic {
+        None => return render_fret(fingering.fret, fret_width_max),
+        Some(Harmonic::Natural) => format!("<{}>", fingering.fret),
+        Some(Harmonic::Artificial) => format!("{}*", fingering.fret),
+    };
+    format!("{label:->fret_width_max$}")
+}
+#[cfg(test)]
+mod test_render_fingering {
+    use super::*;
+    use crate::{pitch::Pitch, string_number::StringNumber};
+
+    fn fingering(fret: u8, harmonic: Option<Harmonic>) -> PitchFingering {
+        PitchFingering {
+            pitch: Pitch::E5,
+            string_number: StringNumber::new(1).unwrap(),
+            fret,
+            harmonic,
+            voice: None,
+        }
+    }
+
+    #[test]
+    fn fretted() {
+        assert_eq!(render_fingering(&fingering(3, None), 2), "-3");
+    }
+    #[test]
+    fn natural_harmonic() {
+        assert_eq!(
+            render_fingering(&fingering(12, Some(Harmonic::Natural)), 4),
+            "<12>"
+        );
+        assert_eq!(
+            render_fingering(&fingering(7, Some(Harmonic::Natural)), 4),
+            "-<7>"
+        );
+    }
+    #[test]
+    fn artificial_harmonic() {
+        assert_eq!(
+            render_fingering(&fingering(5, Some(Harmonic::Artificial)), 2),
+            "5*"
+        );
+    }
+}
+
+/// Calculates the maximum render width of the given pitch fingerings: the fret digits, plus
+/// the harmonic marks around them.
 fn calc_fret_width_max(pitch_fingerings: &[&PitchFingering]) -> usize {
     pitch_fingerings
         .iter()
         .map(|fingering| {
             let fret = fingering.fret;
-            if fret < 10 {
+            let fret_width = if fret < 10 {
                 1
             } else if fret < 100 {
                 2
             } else {
                 3
-            }
+            };
+            let harmonic_width = match fingering.harmonic {
+                None => 0,
+                Some(Harmonic::Natural) => 2,
+                Some(Harmonic::Artificial) => 1,
+            };
+            fret_width + harmonic_width
         })
         .max()
         .expect("BUG: Playable line pitch fingerings should not be empty")
@@ -543,6 +2003,8 @@ mod test_calc_fret_width_max {
             string_number: StringNumber::new(1).unwrap(),
             fret: 2,
             pitch: Pitch::G4,
+            harmonic: None,
+            voice: None,
         };
         assert_eq!(calc_fret_width_max(&[&fingering]), 1);
     }
@@ -553,16 +2015,22 @@ mod test_calc_fret_width_max {
             string_number: StringNumber::new(1).unwrap(),
             fret: 0,
             pitch: Pitch::G4,
+            harmonic: None,
+            voice: None,
         };
         let fingering2 = PitchFingering {
             string_number: StringNumber::new(2).unwrap(),
             fret: 2,
             pitch: Pitch::G4,
+            harmonic: None,
+            voice: None,
         };
         let fingering3 = PitchFingering {
             string_number: StringNumber::new(5).unwrap(),
             fret: 8,
             pitch: Pitch::G4,
+            harmonic: None,
+            voice: None,
         };
         let fingerings = vec![&fingering1, &fingering2, &fingering3];
         assert_eq!(calc_fret_width_max(&fingerings), 1);
@@ -573,20 +2041,45 @@ mod test_calc_fret_width_max {
             string_number: StringNumber::new(1).unwrap(),
             fret: 2,
             pitch: Pitch::G4,
+            harmonic: None,
+            voice: None,
         };
         let fingering2 = PitchFingering {
             string_number: StringNumber::new(2).unwrap(),
             fret: 11,
             pitch: Pitch::G4,
+            harmonic: None,
+            voice: None,
         };
         let fingering3 = PitchFingering {
             string_number: StringNumber::new(4).unwrap(),
             fret: 3,
             pitch: Pitch::G4,
+            harmonic: None,
+            voice: None,
         };
         let fingerings = vec![&fingering1, &fingering2, &fingering3];
         assert_eq!(calc_fret_width_max(&fingerings), 2);
     }
+    #[test]
+    fn harmonic_marks_widen_the_column() {
+        let natural = PitchFingering {
+            string_number: StringNumber::new(1).unwrap(),
+            fret: 12,
+            pitch: Pitch::E5,
+            harmonic: Some(Harmonic::Natural),
+            voice: None,
+        };
+        let artificial = PitchFingering {
+            string_number: StringNumber::new(2).unwrap(),
+            fret: 5,
+            pitch: Pitch::E4,
+            harmonic: Some(Harmonic::Artificial),
+            voice: None,
+        };
+        assert_eq!(calc_fret_width_max(&[&natural, &artificial]), 4);
+        assert_eq!(calc_fret_width_max(&[&artificial]), 2);
+    }
 
     #[test]
     #[should_panic]
@@ -645,18 +2138,103 @@ mod test_transpose {
     }
 }
 
-#[derive(Debug, PartialEq)]
-struct PlaybackIndicatorPosition {
-    row_group_index: usize,
-    column_index: usize,
+/// Where the playback indicator points in a rendered tab: its row group and the character
+/// offset of the `▼` and `▲` in that group's indicator lines.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "wasm", derive(Tsify))]
+#[serde(rename_all = "camelCase")]
+pub struct PlaybackIndicatorPosition {
+    pub row_group_index: usize,
+    pub column_index: usize,
 }
 
+/// Where one rendered column starts: its row group and its character offset in the row.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "wasm", derive(Tsify))]
+#[serde(rename_all = "camelCase")]
+pub struct ColumnPosition {
+    pub row_group_index: usize,
+    pub column_index: usize,
+}
+
+/// The gap after each column when the beats are spaced by rhythm, or no gaps when no beat
+/// has a duration.
+///
+/// The shortest beat advances as far as a one-character fret and its `padding`, and every
+/// other beat in proportion to its length. A column without a duration keeps `padding`, and
+/// `render_string_groups` raises any gap below `padding` to it.
+fn rhythm_gaps(
+    columns: &[Vec<String>],
+    column_durations: &[Option<Duration>],
+    padding: u8,
+) -> Vec<usize> {
+    let Some(shortest) = column_durations
+        .iter()
+        .flatten()
+        .map(|duration| duration.sixty_fourths())
+        .min()
+    else {
+        return vec![];
+    };
+    let unit = 1 + u32::from(padding);
+    columns
+        .iter()
+        .zip(column_durations)
+        .map(|(cells, duration)| match duration {
+            Some(duration) => {
+                let advance = (unit * duration.sixty_fourths() + shortest / 2) / shortest;
+                let cell_width = cells.first().map_or(0, String::len);
+                (advance as usize).saturating_sub(cell_width)
+            }
+            None => padding as usize,
+        })
+        .collect()
+}
+#[cfg(test)]
+mod test_rhythm_gaps {
+    use super::*;
+
+    #[test]
+    fn gaps_follow_the_lengths() {
+        let cells = |text: &str| vec![text.to_owned()];
+        let columns = vec![cells("0"), cells("12"), cells("|"), cells("3"), cells("5")];
+        let durations = [
+            Duration::from_symbol("e"),
+            Duration::from_symbol("q"),
+            None,
+            Duration::from_symbol("h"),
+            Duration::from_symbol("q."),
+        ];
+        // An eighth advances 3 at padding 2, so a quarter advances 6 and a half 12.
+        assert_eq!(rhythm_gaps(&columns, &durations, 2), vec![2, 4, 2, 11, 8]);
+    }
+    #[test]
+    fn no_durations_no_gaps() {
+        let columns = vec![vec!["0".to_owned()]];
+        assert_eq!(rhythm_gaps(&columns, &[None], 1), Vec::<usize>::new());
+    }
+}
+
+/// Lays the per-string column renders out into wrapped rows.
+///
+/// Returns the rows per string, the playback indicator position, and the start position of
+/// every column. Every string wraps at the same columns, so the positions are read off the
+/// first string. A column flagged in `joins_previous` is laid flush against the column before
+/// it, with no padding between them. Otherwise a column is followed by its gap in
+/// `column_gaps`, or by `padding` when it has none. A gap never drops below `padding` and
+/// never runs past `width`.
 fn render_string_groups(
     beat_column_renders: Vec<Vec<String>>,
     width: u16,
     padding: u8,
     playback_column_index: Option<usize>,
-) -> (Vec<Vec<String>>, Option<PlaybackIndicatorPosition>) {
+    joins_previous: &[bool],
+    column_gaps: &[usize],
+) -> (
+    Vec<Vec<String>>,
+    Option<PlaybackIndicatorPosition>,
+    Vec<ColumnPosition>,
+) {
     let padding_render = "-".repeat(padding as usize);
     let content_cap = (width as usize)
         .saturating_sub(padding as usize)
@@ -666,8 +2244,9 @@ fn render_string_groups(
     let mut rows_by_string: Vec<Vec<String>> = vec![];
 
     let mut playback_indicator_position: Option<PlaybackIndicatorPosition> = None;
+    let mut column_positions: Vec<ColumnPosition> = vec![];
 
-    for string_beat_columns in beat_column_renders {
+    for (string_index, string_beat_columns) in beat_column_renders.into_iter().enumerate() {
         let num_render_columns = string_beat_columns.len();
         let mut remaining_string_beat_columns = VecDeque::from(string_beat_columns);
         let mut single_string_rows: Vec<String> = vec![];
@@ -682,17 +2261,20 @@ fn render_string_groups(
                         break;
                     }
                     Some(string_item) => {
+                        if string_index == 0 {
+                            column_positions.push(ColumnPosition {
+                                row_group_index: single_string_rows.len(),
+                                column_index: row.len(),
+                            });
+                        }
                         match playback_column_index {
                             Some(idx)
                                 if num_render_columns - remaining_string_beat_columns.len() - 1
                                     == idx =>
                             {
-                                // Offset the playback indicator by one
-                                // character if the frets are two characters wide
-                                let wide_fret_playback_offset = match string_item.len() {
-                                    2 => 1,
-                                    _ => 0,
-                                };
+                                // Offset the playback indicator toward the middle of a
+                                // wide column, such as a two-digit fret or a harmonic
+                                let wide_fret_playback_offset = string_item.len() / 2;
 
                                 playback_indicator_position = Some(PlaybackIndicatorPosition {
                                     row_group_index: single_string_rows.len(),
@@ -705,7 +2287,23 @@ fn render_string_groups(
                     }
                 }
 
-                row.push_str(&padding_render);
+                // A technique column sits flush against the beats it joins, as in `5h7`.
+                let next_column_index = num_render_columns - remaining_string_beat_columns.len();
+                if !joins_previous
+                    .get(next_column_index)
+                    .copied()
+                    .unwrap_or(false)
+                {
+                    match column_gaps.get(next_column_index - 1) {
+                        Some(&gap) if gap > padding as usize => {
+                            let gap = gap.min((width as usize).saturating_sub(row.len()));
+                            for _ in 0..gap.max(padding as usize) {
+                                row.push('-');
+                            }
+                        }
+                        _ => row.push_str(&padding_render),
+                    }
+                }
             }
             let remaining_characters = (width as usize).saturating_sub(row.len());
             for _ in 0..remaining_characters {
@@ -718,7 +2316,11 @@ fn render_string_groups(
         rows_by_string.push(single_string_rows);
     }
 
-    (rows_by_string, playback_indicator_position)
+    (
+        rows_by_string,
+        playback_indicator_position,
+        column_positions,
+    )
 }
 #[cfg(test)]
 mod test_render_string_groups {
@@ -795,8 +2397,16 @@ mod test_render_string_groups {
             column_index: 3,
         });
 
+        let (string_groups, playback_indicator_position, _) = render_string_groups(
+            beat_column_renders,
+            width,
+            padding,
+            playback_column_index,
+            &[],
+            &[],
+        );
         assert_eq!(
-            render_string_groups(beat_column_renders, width, padding, playback_column_index),
+            (string_groups, playback_indicator_position),
             (expected_string_groups, expected_playback_indicator_position)
         );
     }
@@ -818,8 +2428,16 @@ mod test_render_string_groups {
             column_index: 8,
         });
 
+        let (string_groups, playback_indicator_position, _) = render_string_groups(
+            beat_column_renders,
+            width,
+            padding,
+            playback_column_index,
+            &[],
+            &[],
+        );
         assert_eq!(
-            render_string_groups(beat_column_renders, width, padding, playback_column_index),
+            (string_groups, playback_indicator_position),
             (expected_string_groups, expected_playback_indicator_position)
         );
     }
@@ -841,25 +2459,271 @@ mod test_render_string_groups {
             column_index: 5,
         });
 
+        let (string_groups, playback_indicator_position, _) = render_string_groups(
+            beat_column_renders,
+            width,
+            padding,
+            playback_column_index,
+            &[],
+            &[],
+        );
         assert_eq!(
-            render_string_groups(beat_column_renders, width, padding, playback_column_index),
+            (string_groups, playback_indicator_position),
             (expected_string_groups, expected_playback_indicator_position)
         );
     }
     #[test]
     fn no_playback_column_index() {
-        let (_, playback_indicator_position) =
-            render_string_groups(get_beat_column_renders(), 20, 1, None);
+        let (_, playback_indicator_position, _) =
+            render_string_groups(get_beat_column_renders(), 20, 1, None, &[], &[]);
 
         assert_eq!(playback_indicator_position, None);
     }
     #[test]
     fn too_large_playback_column_index() {
-        let (_, playback_indicator_position) =
-            render_string_groups(get_beat_column_renders(), 20, 1, Some(100_000));
+        let (_, playback_indicator_position, _) =
+            render_string_groups(get_beat_column_renders(), 20, 1, Some(100_000), &[], &[]);
 
         assert_eq!(playback_indicator_position, None);
     }
+    #[test]
+    fn column_positions_track_every_column() {
+        let (_, _, column_positions) =
+            render_string_groups(get_beat_column_renders(), 25, 1, None, &[], &[]);
+
+        let column_indices = column_positions
+            .iter()
+            .map(|pos| pos.column_index)
+            .collect_vec();
+        assert_eq!(column_indices, vec![1, 3, 5, 7, 10, 12, 14, 16, 18, 21]);
+        assert!(column_positions.iter().all(|pos| pos.row_group_index == 0));
+    }
+    #[test]
+    fn column_gaps_widen_the_padding() {
+        let beat_column_renders = vec![vec!["1".to_owned(), "2".to_owned(), "3".to_owned()]];
+        let (string_groups, _, column_positions) =
+            render_string_groups(beat_column_renders, 12, 1, None, &[], &[3, 0, 20]);
+
+        // A gap below the padding keeps the padding, and one past the width stops at it.
+        assert_eq!(string_groups, vec![vec!["-1---2-3----"]]);
+        assert_eq!(
+            column_positions
+                .iter()
+                .map(|pos| pos.column_index)
+                .collect_vec(),
+            vec![1, 5, 7]
+        );
+    }
+    #[test]
+    fn column_positions_follow_wrapping() {
+        let (_, _, column_positions) =
+            render_string_groups(get_beat_column_renders(), 14, 1, None, &[], &[]);
+
+        assert_eq!(column_positions.len(), 10);
+        assert_eq!(
+            column_positions[5],
+            ColumnPosition {
+                row_group_index: 1,
+                column_index: 1,
+            }
+        );
+    }
+}
+
+/// Builds one label row per row group from `(column, text)` labels. A label prints at its
+/// column's start position. A label that would overlap the one before it shifts right by
+/// one space past it. A row group without labels gets an empty string, which
+/// `render_string_output` skips.
+fn render_label_rows(
+    labels: &[(usize, String)],
+    column_positions: &[ColumnPosition],
+    num_row_groups: usize,
+) -> Vec<String> {
+    let mut label_rows = vec![String::new(); num_row_groups];
+    for (column, text) in labels {
+        // A trailing label has no column after it to attach to. It labels nothing, so drop it.
+        let Some(position) = column_positions.get(*column) else {
+            continue;
+        };
+        let Some(label_row) = label_rows.get_mut(position.row_group_index) else {
+            continue;
+        };
+        let used_width = label_row.chars().count();
+        let start = if used_width == 0 {
+            position.column_index
+        } else {
+            position.column_index.max(used_width + 1)
+        };
+        for _ in used_width..start {
+            label_row.push(' ');
+        }
+        label_row.push_str(text);
+    }
+    label_rows
+}
+#[cfg(test)]
+mod test_render_label_rows {
+    use super::*;
+
+    fn positions() -> Vec<ColumnPosition> {
+        vec![
+            ColumnPosition {
+                row_group_index: 0,
+                column_index: 1,
+            },
+            ColumnPosition {
+                row_group_index: 0,
+                column_index: 4,
+            },
+            ColumnPosition {
+                row_group_index: 1,
+                column_index: 1,
+            },
+        ]
+    }
+
+    #[test]
+    fn places_labels_at_column_starts() {
+        let labels = vec![(0, "1.".to_owned()), (2, "[Bridge]".to_owned())];
+        assert_eq!(
+            render_label_rows(&labels, &positions(), 2),
+            vec![" 1.".to_owned(), " [Bridge]".to_owned()]
+        );
+    }
+    #[test]
+    fn overlapping_labels_shift_right() {
+        let labels = vec![(0, "[Verse]".to_owned()), (1, "1.".to_owned())];
+        assert_eq!(
+            render_label_rows(&labels, &positions(), 2),
+            vec![" [Verse] 1.".to_owned(), String::new()]
+        );
+    }
+    #[test]
+    fn trailing_label_is_dropped() {
+        let labels = vec![(3, "[Outro]".to_owned())];
+        assert_eq!(
+            render_label_rows(&labels, &positions(), 2),
+            vec![String::new(), String::new()]
+        );
+    }
+}
+
+/// The label each string's rows start with: the open-string name as tuned, before the capo,
+/// then `|`. A name that a lower string also has is lowercase, so standard tuning reads
+/// `e B G D A E`. Names are padded to one width so the rows line up.
+fn render_string_labels(guitar: &Guitar) -> Vec<String> {
+    let tuning = guitar.tuning().collect_vec();
+    let names = tuning
+        .iter()
+        .map(|&pitch| {
+            let name = ROOT_NAMES[(pitch.index() % 12) as usize];
+            let has_lower_namesake = tuning
+                .iter()
+                .any(|&other| other < pitch && other.index() % 12 == pitch.index() % 12);
+            if has_lower_namesake {
+                name.to_lowercase()
+            } else {
+                name.to_owned()
+            }
+        })
+        .collect_vec();
+    let name_width = names.iter().map(String::len).max().unwrap_or(0);
+    names
+        .into_iter()
+        .map(|name| format!("{name:<name_width$}|"))
+        .collect()
+}
+#[cfg(test)]
+mod test_render_string_labels {
+    use super::*;
+    use crate::{guitar::create_string_tuning, pitch::Pitch};
+
+    #[test]
+    fn standard_tuning() {
+        assert_eq!(
+            render_string_labels(&Guitar::default()),
+            ["e|", "B|", "G|", "D|", "A|", "E|"]
+        );
+    }
+    #[test]
+    fn pads_accidentals() {
+        let tuning = create_string_tuning(&[Pitch::DSharpEFlat4, Pitch::G3, Pitch::C3]).unwrap();
+        let guitar = Guitar::new(tuning, 12, 0).unwrap();
+        assert_eq!(render_string_labels(&guitar), ["Eb|", "G |", "C |"]);
+    }
+}
+
+/// Redraws the string rows of `layout` with the glyphs in `options`: the dash, the bar
+/// character, and the glyph on each string a beat leaves silent. Every glyph replaces one
+/// character, so the column positions still hold as character offsets. The string labels at
+/// the start of each row keep their ASCII `|`.
+fn apply_glyphs(layout: &mut TabLayout, options: &RenderOptions) {
+    let dash = options.dash_style.dash();
+    let empty_string_glyph = options.empty_string_glyph.unwrap_or(dash);
+    for (string_index, single_string_rows) in layout.rows_by_string.iter_mut().enumerate() {
+        let label_width = layout
+            .string_labels
+            .get(string_index)
+            .map_or(0, String::len);
+        for (row_group_index, row) in single_string_rows.iter_mut().enumerate() {
+            // Rows are still ASCII here, so byte offsets are character offsets.
+            let mut silent = vec![false; row.len()];
+            for column in &layout.columns {
+                let silent_cell = column.beat_index.is_some()
+                    && column.position.row_group_index == row_group_index
+                    && column
+                        .cells
+                        .get(string_index)
+                        .is_some_and(|cell| cell.bytes().all(|byte| byte == b'-'));
+                if silent_cell {
+                    let start = column.position.column_index;
+                    silent[start..start + column.width()].fill(true);
+                }
+            }
+            *row = row
+                .chars()
+                .zip(silent)
+                .enumerate()
+                .map(|(index, (character, silent))| match character {
+                    _ if index < label_width => character,
+                    '-' if silent => empty_string_glyph,
+                    '-' => dash,
+                    '|' => options.bar_char,
+                    _ => character,
+                })
+                .collect();
+        }
+    }
+}
+#[cfg(test)]
+mod test_apply_glyphs {
+    use super::*;
+    use crate::{guitar::create_string_tuning, pitch::Pitch, string_number::StringNumber};
+
+    #[test]
+    fn silent_strings_follow_the_beat_columns() {
+        let lines = vec![
+            Line::Playable(vec![PitchFingering {
+                pitch: Pitch::E4,
+                string_number: StringNumber::new(1).unwrap(),
+                fret: 3,
+                harmonic: None,
+                voice: None,
+            }]),
+            Line::Technique(Technique::Vibrato),
+            Line::MeasureBreak,
+        ];
+        let tuning = create_string_tuning(&[Pitch::E4, Pitch::B3]).unwrap();
+        let guitar = Guitar::new(tuning, 18, 0).unwrap();
+        let options = RenderOptions::new(12, 1)
+            .with_label_mode(LabelMode::Strings)
+            .with_bar_char('!')
+            .with_empty_string_glyph('x');
+        let layout = lay_out_tab(&lines, &guitar, &options, &[]).unwrap();
+        // The technique column is not a beat, so it keeps the dash.
+        assert_eq!(layout.rows_by_string[0], vec!["E|-3~-!-----"]);
+        assert_eq!(layout.rows_by_string[1], vec!["B|-x--!-----"]);
+    }
 }
 
 /// Writes one playback-indicator line into `out`, terminated by `'\n'`.
@@ -867,7 +2731,7 @@ mod test_render_string_groups {
 /// Emits `column_index` spaces followed by `symbol` when the indicator falls on
 /// `row_group_index`, and nothing (just the newline) otherwise. Writing in place
 /// avoids the `" ".repeat(..)` temp string the indent used to allocate.
-fn push_playback_line(
+pub(crate) fn push_playback_line(
     out: &mut String,
     symbol: &str,
     row_group_index: usize,
@@ -884,9 +2748,13 @@ fn push_playback_line(
     out.push('\n');
 }
 
+/// Joins the row groups into the final tab text. Each group gets its heading rows in order
+/// (each one that has a non-empty entry for it), the `▼` line, one row per string, the `▲`
+/// line, and a blank separator line.
 fn render_string_output(
     rows_by_string: &[Vec<String>],
     playback_indicator_position: Option<PlaybackIndicatorPosition>,
+    heading_rows: &[&[String]],
 ) -> String {
     let num_strings = rows_by_string.len();
     let first_string_rows = rows_by_string
@@ -900,6 +2768,15 @@ fn render_string_output(
     let pos = playback_indicator_position.as_ref();
 
     for row_group_index in 0..num_row_groups {
+        for heading_rows in heading_rows {
+            if let Some(heading_row) = heading_rows
+                .get(row_group_index)
+                .filter(|row| !row.is_empty())
+            {
+                out.push_str(heading_row);
+                out.push('\n');
+            }
+        }
         push_playback_line(&mut out, "▼", row_group_index, pos);
 
         for single_string_rows in rows_by_string {
@@ -950,7 +2827,7 @@ mod test_render_string_output {
         .to_owned();
 
         assert_eq!(
-            render_string_output(&string_rows, playback_indicator_position),
+            render_string_output(&string_rows, playback_indicator_position, &[]),
             expected_output
         );
     }
@@ -964,7 +2841,7 @@ mod test_render_string_output {
         ];
         let playback_indicator_position = None;
 
-        let output = render_string_output(&string_rows, playback_indicator_position);
+        let output = render_string_output(&string_rows, playback_indicator_position, &[]);
 
         let expected_output = concat!(
             "\n",
@@ -994,7 +2871,7 @@ mod test_render_string_output {
             column_index: 8,
         });
 
-        let output = render_string_output(&string_rows, playback_indicator_position);
+        let output = render_string_output(&string_rows, playback_indicator_position, &[]);
 
         let expected_output = concat!(
             "\n",
@@ -1017,12 +2894,27 @@ mod test_render_string_output {
         assert_eq!(output, expected_output);
     }
     #[test]
+    fn label_row_prints_above_its_row_group() {
+        let string_rows = vec![
+            vec!["-0---".to_owned(), "-1---".to_owned()],
+            vec!["-----".to_owned(), "-----".to_owned()],
+        ];
+        let label_rows = vec![String::new(), " [B]".to_owned()];
+
+        let output = render_string_output(&string_rows, None, &[&label_rows]);
+
+        let expected_output = concat!(
+            "\n", "-0---\n", "-----\n", "\n\n", " [B]\n", "\n", "-1---\n", "-----\n", "\n"
+        );
+        assert_eq!(output, expected_output);
+    }
+    #[test]
     fn empty_row_groups_returns_empty_string() {
         // One string with zero row groups: the loop body never runs, so the
         // trailing-newline pop() must leave an empty string, matching join over
         // an empty Vec.
         let string_rows: Vec<Vec<String>> = vec![vec![]];
 
-        assert_eq!(render_string_output(&string_rows, None), "");
+        assert_eq!(render_string_output(&string_rows, None, &[]), "");
     }
 }
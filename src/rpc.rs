@@ -0,0 +1,405 @@
+//! The `guitar-tab-rpc` JSON-RPC 2.0 server, built with the `rpc` feature.
+//!
+//! Reads one request per line from stdin and writes one response per line to stdout, so an
+//! editor extension can arrange tabs without embedding the WASM build. Params and results use
+//! the same camelCase shapes as the generated TypeScript types: `generateArrangements` takes a
+//! `TabInput`, and a failed call carries its `TabError` as the error `data`. Each generated
+//! [`ArrangementSet`] stays alive under a numeric handle until `free` releases it.
+//!
+//! | Method                 | Params                                        | Result               |
+//! |------------------------|-----------------------------------------------|----------------------|
+//! | `generateArrangements` | `TabInput`                                    | `GenerateResult`     |
+//! | `render`               | `{ handle, index }` plus the `RenderOptions`  | `string`             |
+//! | `normalizedInput`      | `{ handle }`                                  | `NormalizedBeat[]`   |
+//! | `exportJson`           | `{ handle }`                                  | `string`             |
+//! | `importJson`           | `{ json }`                                    | `GenerateResult`     |
+//! | `free`                 | `{ handle }`                                  | `null`               |
+//! | `getTuningNames`       | none                                          | `TuningName[]`       |
+
+use crate::{ArrangementSet, ParseError, RenderOptions, TabError, TabInput, generate_arrangements};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use serde_json::{Value, json};
+use std::{
+    collections::HashMap,
+    io::{BufRead, Write},
+};
+
+/// JSON-RPC code for a line that is not JSON.
+pub const PARSE_ERROR: i32 = -32700;
+/// JSON-RPC code for JSON that is not a request object.
+pub const INVALID_REQUEST: i32 = -32600;
+/// JSON-RPC code for an unknown method.
+pub const METHOD_NOT_FOUND: i32 = -32601;
+/// JSON-RPC code for params of the wrong shape.
+pub const INVALID_PARAMS: i32 = -32602;
+/// Code for a call that failed with a [`TabError`], which the error `data` carries.
+pub const TAB_ERROR: i32 = -32000;
+/// Code for a handle that was never issued or has been freed.
+pub const UNKNOWN_HANDLE: i32 = -32001;
+
+#[derive(Deserialize)]
+struct Request {
+    jsonrpc: String,
+    #[serde(default)]
+    id: Option<Value>,
+    method: String,
+    #[serde(default)]
+    params: Value,
+}
+
+/// A JSON-RPC error object.
+#[derive(Debug, PartialEq, Serialize)]
+struct RpcError {
+    code: i32,
+    message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    data: Option<TabError>,
+}
+
+impl RpcError {
+    fn new(code: i32, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+            data: None,
+        }
+    }
+}
+
+impl From<TabError> for RpcError {
+    fn from(error: TabError) -> Self {
+        Self {
+            code: TAB_ERROR,
+            message: error.to_string(),
+            data: Some(error),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct HandleParams {
+    handle: u32,
+}
+
+#[derive(Deserialize)]
+struct RenderParams {
+    handle: u32,
+    index: usize,
+    #[serde(flatten)]
+    options: RenderOptions,
+}
+
+#[derive(Deserialize)]
+struct ImportParams {
+    json: String,
+}
+
+/// The result of `generateArrangements` and `importJson`: the new handle and what a client
+/// needs to pick an arrangement without another call.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct GenerateResult {
+    handle: u32,
+    arrangements: Vec<ArrangementSummary>,
+    parse_warnings: Vec<ParseError>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ArrangementSummary {
+    difficulty: f64,
+    max_fret_span: u8,
+}
+
+/// The sets generated so far, by handle.
+#[derive(Debug, Default)]
+pub struct Server {
+    sets: HashMap<u32, ArrangementSet>,
+    next_handle: u32,
+}
+
+impl Server {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Handles one line of input. Returns the response line, or `None` for a notification.
+    pub fn handle_line(&mut self, line: &str) -> Option<String> {
+        let (id, result) = match serde_json::from_str::<Value>(line) {
+            Err(error) => (
+                Value::Null,
+                Err(RpcError::new(PARSE_ERROR, error.to_string())),
+            ),
+            Ok(message) => {
+                let id = message.get("id").cloned().unwrap_or(Value::Null);
+                match serde_json::from_value::<Request>(message) {
+                    Ok(request) if request.jsonrpc == "2.0" => {
+                        let result = self.call(&request.method, request.params);
+                        // A request without an `id` is a notification and gets no response.
+                        request.id?;
+                        (id, result)
+                    }
+                    Ok(_) => (
+                        id,
+                        Err(RpcError::new(INVALID_REQUEST, "jsonrpc must be \"2.0\"")),
+                    ),
+                    Err(error) => (id, Err(RpcError::new(INVALID_REQUEST, error.to_string()))),
+                }
+            }
+        };
+        let response = match result {
+            Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+            Err(error) => json!({ "jsonrpc": "2.0", "id": id, "error": error }),
+        };
+        Some(response.to_string())
+    }
+
+    fn call(&mut self, method: &str, params: Value) -> Result<Value, RpcError> {
+        let result = match method {
+            "generateArrangements" => {
+                let set = generate_arrangements(parse_params::<TabInput>(params)?)?;
+                to_value(self.insert(set)?)
+            }
+            "render" => {
+                let RenderParams {
+                    handle,
+                    index,
+                    options,
+                } = parse_params(params)?;
+                to_value(self.set(handle)?.render_with_options(index, options)?)
+            }
+            "normalizedInput" => {
+                let HandleParams { handle } = parse_params(params)?;
+                to_value(self.set(handle)?.normalized_input())
+            }
+            "exportJson" => {
+                let HandleParams { handle } = parse_params(params)?;
+                to_value(self.set(handle)?.export_json())
+            }
+            "importJson" => {
+                let ImportParams { json } = parse_params(params)?;
+                to_value(self.insert(ArrangementSet::import_json(&json)?)?)
+            }
+            "free" => {
+                let HandleParams { handle } = parse_params(params)?;
+                self.sets
+                    .remove(&handle)
+                    .ok_or_else(|| unknown_handle(handle))?;
+                Value::Null
+            }
+            "getTuningNames" => to_value(crate::get_tuning_names()),
+            _ => {
+                return Err(RpcError::new(
+                    METHOD_NOT_FOUND,
+                    format!("unknown method {method:?}"),
+                ));
+            }
+        };
+        Ok(result)
+    }
+
+    fn insert(&mut self, set: ArrangementSet) -> Result<GenerateResult, RpcError> {
+        let result = GenerateResult {
+            handle: self.next_handle,
+            arrangements: (0..set.len())
+                .map(|index| {
+                    Ok(ArrangementSummary {
+                        difficulty: set.difficulty(index)?,
+                        max_fret_span: set.max_fret_span(index)?,
+                    })
+                })
+                .collect::<Result<_, TabError>>()?,
+            parse_warnings: set.parse_warnings(),
+        };
+        self.sets.insert(self.next_handle, set);
+        self.next_handle += 1;
+        Ok(result)
+    }
+
+    fn set(&self, handle: u32) -> Result<&ArrangementSet, RpcError> {
+        self.sets.get(&handle).ok_or_else(|| unknown_handle(handle))
+    }
+}
+#[cfg(test)]
+mod test_server {
+    use super::*;
+
+    fn call(server: &mut Server, request: Value) -> Value {
+        serde_json::from_str(&server.handle_line(&request.to_string()).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn generate_render_and_free() {
+        let mut server = Server::new();
+        let response = call(
+            &mut server,
+            json!({ "jsonrpc": "2.0", "id": 1, "method": "generateArrangements", "params": {
+                "input": "E2\nA2", "tuningName": "standard", "guitarNumFrets": 18,
+                "guitarCapo": 0, "numArrangements": 2,
+            }}),
+        );
+        assert_eq!(response["id"], 1);
+        let result = &response["result"];
+        assert_eq!(result["handle"], 0);
+        assert_eq!(result["arrangements"].as_array().unwrap().len(), 2);
+        assert_eq!(result["arrangements"][0]["maxFretSpan"], 0);
+
+        let response = call(
+            &mut server,
+            json!({ "jsonrpc": "2.0", "id": "r", "method": "render",
+                "params": { "handle": 0, "index": 0, "width": 20, "padding": 1 }}),
+        );
+        assert_eq!(response["id"], "r");
+        assert!(
+            response["result"]
+                .as_str()
+                .unwrap()
+                .ends_with("\n---0----------------\n-0------------------\n\n")
+        );
+
+        let response = call(
+            &mut server,
+            json!({ "jsonrpc": "2.0", "id": 3, "method": "normalizedInput", "params": { "handle": 0 }}),
+        );
+        assert_eq!(response["result"][1]["pitches"][0], "A2");
+
+        let response = call(
+            &mut server,
+            json!({ "jsonrpc": "2.0", "id": 4, "method": "free", "params": { "handle": 0 }}),
+        );
+        assert_eq!(response["result"], Value::Null);
+        let response = call(
+            &mut server,
+            json!({ "jsonrpc": "2.0", "id": 5, "method": "exportJson", "params": { "handle": 0 }}),
+        );
+        assert_eq!(response["error"]["code"], UNKNOWN_HANDLE);
+    }
+    #[test]
+    fn export_and_import() {
+        let mut server = Server::new();
+        let set = generate_arrangements(TabInput::new("E2", "standard", 18, 0, 1)).unwrap();
+        let json = set.export_json();
+        let response = call(
+            &mut server,
+            json!({ "jsonrpc": "2.0", "id": 1, "method": "importJson", "params": { "json": json }}),
+        );
+        assert_eq!(response["result"]["handle"], 0);
+        let response = call(
+            &mut server,
+            json!({ "jsonrpc": "2.0", "id": 2, "method": "exportJson", "params": { "handle": 0 }}),
+        );
+        assert_eq!(response["result"], json);
+    }
+    #[test]
+    fn tab_errors_carry_their_data() {
+        let mut server = Server::new();
+        let response = call(
+            &mut server,
+            json!({ "jsonrpc": "2.0", "id": 1, "method": "generateArrangements", "params": {
+                "input": "E2", "tuningName": "openZ", "guitarNumFrets": 18,
+                "guitarCapo": 0, "numArrangements": 1,
+            }}),
+        );
+        let error = &response["error"];
+        assert_eq!(error["code"], TAB_ERROR);
+        assert_eq!(error["data"]["kind"], "tuningNameUnknown");
+        assert!(
+            error["message"]
+                .as_str()
+                .unwrap()
+                .starts_with("The tuning name")
+        );
+    }
+    #[test]
+    fn protocol_errors() {
+        let mut server = Server::new();
+        let response: Value = serde_json::from_str(&server.handle_line("{").unwrap()).unwrap();
+        assert_eq!(response["error"]["code"], PARSE_ERROR);
+        assert_eq!(response["id"], Value::Null);
+
+        let response = call(
+            &mut server,
+            json!({ "jsonrpc": "1.0", "id": 1, "method": "free" }),
+        );
+        assert_eq!(response["error"]["code"], INVALID_REQUEST);
+        let response = call(
+            &mut server,
+            json!({ "jsonrpc": "2.0", "id": 2, "method": "arrange" }),
+        );
+        assert_eq!(response["error"]["code"], METHOD_NOT_FOUND);
+        let response = call(
+            &mut server,
+            json!({ "jsonrpc": "2.0", "id": 3, "method": "render", "params": { "handle": 0 }}),
+        );
+        assert_eq!(response["error"]["code"], INVALID_PARAMS);
+
+        let response = call(
+            &mut server,
+            json!({ "jsonrpc": "2.0", "id": 4, "method": "getTuningNames" }),
+        );
+        assert!(
+            response["result"]
+                .as_array()
+                .unwrap()
+                .contains(&json!("dropD"))
+        );
+        assert_eq!(
+            server.handle_line(r#"{"jsonrpc":"2.0","method":"getTuningNames"}"#),
+            None
+        );
+    }
+}
+
+fn parse_params<T: DeserializeOwned>(params: Value) -> Result<T, RpcError> {
+    serde_json::from_value(params).map_err(|error| RpcError::new(INVALID_PARAMS, error.to_string()))
+}
+
+fn to_value(result: impl Serialize) -> Value {
+    serde_json::to_value(result).expect("BUG: every RPC result serializes to JSON")
+}
+
+fn unknown_handle(handle: u32) -> RpcError {
+    RpcError::new(
+        UNKNOWN_HANDLE,
+        format!("no arrangement set has handle {handle}"),
+    )
+}
+
+/// Serves requests from `input` until it ends, writing each response line to `output`.
+///
+/// # Errors
+///
+/// Returns the first error reading `input` or writing `output`.
+pub fn run(input: impl BufRead, output: &mut impl Write) -> std::io::Result<()> {
+    let mut server = Server::new();
+    for line in input.lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        if let Some(response) = server.handle_line(&line) {
+            writeln!(output, "{response}")?;
+            output.flush()?;
+        }
+    }
+    Ok(())
+}
+#[cfg(test)]
+mod test_run {
+    use super::*;
+
+    #[test]
+    fn answers_each_line() {
+        let input = "{\"jsonrpc\":\"2.0\",\"id\":1,\"method\":\"getTuningNames\"}\n\n{\"jsonrpc\":\"2.0\",\"method\":\"free\",\"params\":{\"handle\":0}}\n{\"jsonrpc\":\"2.0\",\"id\":2,\"method\":\"free\",\"params\":{\"handle\":0}}\n";
+        let mut output = Vec::new();
+        run(input.as_bytes(), &mut output).unwrap();
+        let lines: Vec<Value> = String::from_utf8(output)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0]["id"], 1);
+        assert_eq!(lines[1]["error"]["code"], UNKNOWN_HANDLE);
+    }
+}
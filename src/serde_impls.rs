@@ -0,0 +1,393 @@
+//! `Serialize` and `Deserialize` for the core domain types, built with the `serde` feature.
+//!
+//! The encodings are meant to be stored and diffed. A `Pitch` is its sharp spelling, such as
+//! `"C#4"`, and a `StringNumber` is its integer. A `Line` is tagged by `kind` like
+//! `NormalizedBeat`, with the notes of a playable line under `notes`. `PitchFingering`,
+//! `Arrangement`, `Guitar` and `DifficultyWeights` are camelCase objects, and deserializing a
+//! `Guitar` or `DifficultyWeights` runs the same checks as its constructor.
+
+use crate::{
+    Arrangement, CutOffNote, DifficultyWeights, Guitar, Harmonic, HarmonicMode, Line, Pitch,
+    PitchFingering, StringNumber, Technique, Voice, create_string_tuning,
+};
+use serde::{
+    Deserialize, Deserializer, Serialize, Serializer, de::Error as _, ser::SerializeStruct,
+};
+use std::str::FromStr;
+
+/// The sharp spelling of `pitch`, such as `"C#4"` for `CSharpDFlat4`.
+fn sharp_text(pitch: Pitch) -> String {
+    let plain = pitch.plain_text();
+    match plain.strip_suffix(|octave: char| octave.is_ascii_digit()) {
+        Some(name) if name.ends_with('b') => {
+            let sharp_of = match &name[..1] {
+                "D" => 'C',
+                "E" => 'D',
+                "G" => 'F',
+                "A" => 'G',
+                _ => 'A',
+            };
+            format!("{sharp_of}#{}", &plain[name.len()..])
+        }
+        _ => plain.to_owned(),
+    }
+}
+
+impl Serialize for Pitch {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&sharp_text(*self))
+    }
+}
+
+/// Accepts either spelling of an accidental, like `Pitch::from_str`.
+impl<'de> Deserialize<'de> for Pitch {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let text = String::deserialize(deserializer)?;
+        Pitch::from_str(&text).map_err(|_| D::Error::custom(format!("unknown pitch {text:?}")))
+    }
+}
+#[cfg(test)]
+mod test_pitch {
+    use super::*;
+
+    #[test]
+    fn sharp_spelling() {
+        assert_eq!(
+            serde_json::to_string(&Pitch::CSharpDFlat4).unwrap(),
+            "\"C#4\""
+        );
+        assert_eq!(
+            serde_json::to_string(&Pitch::ASharpBFlat2).unwrap(),
+            "\"A#2\""
+        );
+        assert_eq!(serde_json::to_string(&Pitch::E2).unwrap(), "\"E2\"");
+    }
+    #[test]
+    fn round_trips_every_pitch() {
+        use strum::IntoEnumIterator;
+        for pitch in Pitch::iter() {
+            let json = serde_json::to_string(&pitch).unwrap();
+            assert_eq!(serde_json::from_str::<Pitch>(&json).unwrap(), pitch);
+        }
+        assert_eq!(
+            serde_json::from_str::<Pitch>("\"Db4\"").unwrap(),
+            Pitch::CSharpDFlat4
+        );
+        assert!(serde_json::from_str::<Pitch>("\"H2\"").is_err());
+    }
+}
+
+impl Serialize for StringNumber {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u8(self.get())
+    }
+}
+
+impl<'de> Deserialize<'de> for StringNumber {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        StringNumber::new(u8::deserialize(deserializer)?).map_err(D::Error::custom)
+    }
+}
+#[cfg(test)]
+mod test_string_number {
+    use super::*;
+
+    #[test]
+    fn integer() {
+        let string_number = StringNumber::new(6).unwrap();
+        assert_eq!(serde_json::to_string(&string_number).unwrap(), "6");
+        assert_eq!(
+            serde_json::from_str::<StringNumber>("6").unwrap(),
+            string_number
+        );
+        assert!(serde_json::from_str::<StringNumber>("0").is_err());
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PitchFingeringRepr {
+    string: StringNumber,
+    fret: u8,
+    pitch: Pitch,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    harmonic: Option<Harmonic>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    voice: Option<Voice>,
+}
+
+impl Serialize for PitchFingering {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        PitchFingeringRepr {
+            string: self.string_number,
+            fret: self.fret,
+            pitch: self.pitch,
+            harmonic: self.harmonic,
+            voice: self.voice,
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for PitchFingering {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let repr = PitchFingeringRepr::deserialize(deserializer)?;
+        Ok(PitchFingering {
+            string_number: repr.string,
+            fret: repr.fret,
+            pitch: repr.pitch,
+            harmonic: repr.harmonic,
+            voice: repr.voice,
+        })
+    }
+}
+
+/// `Line` with struct variants, so it can be tagged by `kind`.
+#[derive(Serialize, Deserialize)]
+#[serde(
+    tag = "kind",
+    rename_all = "camelCase",
+    rename_all_fields = "camelCase"
+)]
+enum LineRepr<T> {
+    MeasureBreak,
+    RepeatStart,
+    RepeatEnd,
+    Ending { number: u8 },
+    Section { label: String },
+    Technique { technique: Technique },
+    LetRing { beats: Option<u8> },
+    Rest,
+    Playable { notes: T },
+}
+
+impl<T: Serialize> Serialize for Line<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Line::MeasureBreak => LineRepr::MeasureBreak,
+            Line::RepeatStart => LineRepr::RepeatStart,
+            Line::RepeatEnd => LineRepr::RepeatEnd,
+            Line::Ending(number) => LineRepr::Ending { number: *number },
+            Line::Section(label) => LineRepr::Section {
+                label: label.clone(),
+            },
+            Line::Technique(technique) => LineRepr::Technique {
+                technique: *technique,
+            },
+            Line::LetRing(beats) => LineRepr::LetRing { beats: *beats },
+            Line::Rest => LineRepr::Rest,
+            Line::Playable(notes) => LineRepr::Playable { notes },
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for Line<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(match LineRepr::deserialize(deserializer)? {
+            LineRepr::MeasureBreak => Line::MeasureBreak,
+            LineRepr::RepeatStart => Line::RepeatStart,
+            LineRepr::RepeatEnd => Line::RepeatEnd,
+            LineRepr::Ending { number } => Line::Ending(number),
+            LineRepr::Section { label } => Line::Section(label),
+            LineRepr::Technique { technique } => Line::Technique(technique),
+            LineRepr::LetRing { beats } => Line::LetRing(beats),
+            LineRepr::Rest => Line::Rest,
+            LineRepr::Playable { notes } => Line::Playable(notes),
+        })
+    }
+}
+#[cfg(test)]
+mod test_line {
+    use super::*;
+    use crate::BeatVec;
+
+    #[test]
+    fn tagged_by_kind() {
+        let lines: Vec<Line<BeatVec<Pitch>>> = vec![
+            Line::Playable(vec![Pitch::E2, Pitch::GSharpAFlat3]),
+            Line::Rest,
+            Line::MeasureBreak,
+            Line::Ending(2),
+            Line::LetRing(None),
+        ];
+        let json = serde_json::to_string(&lines).unwrap();
+        assert_eq!(
+            json,
+            r#"[{"kind":"playable","notes":["E2","G#3"]},{"kind":"rest"},{"kind":"measureBreak"},{"kind":"ending","number":2},{"kind":"letRing","beats":null}]"#
+        );
+        assert_eq!(
+            serde_json::from_str::<Vec<Line<BeatVec<Pitch>>>>(&json).unwrap(),
+            lines
+        );
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ArrangementRepr {
+    lines: Vec<Line<Vec<PitchFingering>>>,
+    difficulty: f64,
+    max_fret_span: u8,
+    cut_off_notes: Vec<CutOffNote>,
+}
+
+impl Serialize for Arrangement {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("Arrangement", 4)?;
+        state.serialize_field("lines", self.lines())?;
+        state.serialize_field("difficulty", &self.difficulty())?;
+        state.serialize_field("maxFretSpan", &self.max_fret_span())?;
+        state.serialize_field("cutOffNotes", self.cut_off_notes())?;
+        state.end()
+    }
+}
+
+impl<'de> Deserialize<'de> for Arrangement {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let repr = ArrangementRepr::deserialize(deserializer)?;
+        Ok(Arrangement::from_parts(
+            repr.lines,
+            repr.difficulty,
+            repr.max_fret_span,
+            repr.cut_off_notes,
+        ))
+    }
+}
+#[cfg(test)]
+mod test_arrangement {
+    use super::*;
+    use crate::{DifficultyWeights, NumArrangements, create_arrangements, parse_lines};
+
+    #[test]
+    fn round_trips() {
+        let arrangements = create_arrangements(
+            Guitar::default(),
+            parse_lines("E2\n\nA2G3".to_owned()).unwrap(),
+            NumArrangements::try_new(2).unwrap(),
+            DifficultyWeights::standard(),
+            None,
+        )
+        .unwrap();
+        let json = serde_json::to_string(&arrangements).unwrap();
+        assert!(json.starts_with(
+            r#"[{"lines":[{"kind":"playable","notes":[{"string":6,"fret":0,"pitch":"E2"}]},{"kind":"rest"}"#
+        ));
+        assert_eq!(
+            serde_json::from_str::<Vec<Arrangement>>(&json).unwrap(),
+            arrangements
+        );
+    }
+}
+
+/// The guitar as built: open pitches from string 1 down, before the capo.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GuitarRepr {
+    tuning: Vec<Pitch>,
+    num_frets: u8,
+    capo: u8,
+    #[serde(default)]
+    harmonic_mode: HarmonicMode,
+}
+
+impl Serialize for Guitar {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        GuitarRepr {
+            tuning: self.tuning().collect(),
+            num_frets: self.playable_frets + self.capo,
+            capo: self.capo,
+            harmonic_mode: self.harmonic_mode,
+        }
+        .serialize(serializer)
+    }
+}
+
+/// Rebuilt with [`Guitar::new`], so a bad tuning, fret count or capo fails like it does there.
+impl<'de> Deserialize<'de> for Guitar {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let repr = GuitarRepr::deserialize(deserializer)?;
+        let tuning = create_string_tuning(&repr.tuning).map_err(D::Error::custom)?;
+        Ok(Guitar::new(tuning, repr.num_frets, repr.capo)
+            .map_err(D::Error::custom)?
+            .with_harmonic_mode(repr.harmonic_mode))
+    }
+}
+#[cfg(test)]
+mod test_guitar {
+    use super::*;
+
+    #[test]
+    fn round_trips() {
+        let tuning = create_string_tuning(&[
+            Pitch::E4,
+            Pitch::B3,
+            Pitch::G3,
+            Pitch::D3,
+            Pitch::A2,
+            Pitch::D2,
+        ])
+        .unwrap();
+        let guitar = Guitar::new(tuning, 20, 2)
+            .unwrap()
+            .with_harmonic_mode(HarmonicMode::Natural);
+        let json = serde_json::to_string(&guitar).unwrap();
+        assert_eq!(
+            json,
+            r#"{"tuning":["E4","B3","G3","D3","A2","D2"],"numFrets":20,"capo":2,"harmonicMode":"natural"}"#
+        );
+        assert_eq!(serde_json::from_str::<Guitar>(&json).unwrap(), guitar);
+    }
+    #[test]
+    fn rejects_what_the_constructor_rejects() {
+        let error = serde_json::from_str::<Guitar>(r#"{"tuning":["E4"],"numFrets":18,"capo":19}"#)
+            .unwrap_err();
+        assert!(error.to_string().starts_with("The capo"), "{error}");
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct DifficultyWeightsRepr {
+    movement: f64,
+    span: f64,
+    position: f64,
+}
+
+impl Serialize for DifficultyWeights {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        DifficultyWeightsRepr {
+            movement: self.movement(),
+            span: self.span(),
+            position: self.position(),
+        }
+        .serialize(serializer)
+    }
+}
+
+/// Rebuilt with [`DifficultyWeights::try_new`], so a negative or non-finite weight fails.
+impl<'de> Deserialize<'de> for DifficultyWeights {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let repr = DifficultyWeightsRepr::deserialize(deserializer)?;
+        DifficultyWeights::try_new(repr.movement, repr.span, repr.position)
+            .map_err(D::Error::custom)
+    }
+}
+#[cfg(test)]
+mod test_difficulty_weights {
+    use super::*;
+
+    #[test]
+    fn round_trips() {
+        let weights = DifficultyWeights::standard();
+        let json = serde_json::to_string(&weights).unwrap();
+        assert_eq!(json, r#"{"movement":100.0,"span":10.0,"position":1.0}"#);
+        assert_eq!(
+            serde_json::from_str::<DifficultyWeights>(&json).unwrap(),
+            weights
+        );
+        assert!(
+            serde_json::from_str::<DifficultyWeights>(r#"{"movement":-1,"span":0,"position":0}"#)
+                .is_err()
+        );
+    }
+}
@@ -0,0 +1,454 @@
+//! Versioned export and import of a whole `ArrangementSet`.
+//!
+//! A snapshot records everything the set holds, so importing one skips parsing and
+//! pathfinding. The same snapshot is written as JSON for share links and as CBOR where size
+//! matters. Pitches travel as their plain text (`"Db4"`) and strings as their numbers, so the
+//! format does not depend on enum layouts.
+
+use crate::{
+    ArrangementSet, NormalizedBeat, TabInput,
+    arrangement::{Arrangement, BeatVec, CutOffNote, Line},
+    duration::Duration,
+    error::{ParseError, TabError},
+    guitar::{Guitar, Harmonic, HarmonicMode, PitchFingering, create_string_tuning},
+    pitch::Pitch,
+    string_number::StringNumber,
+    technique::Technique,
+    voice::Voice,
+};
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+/// The snapshot format version this build writes. Import rejects any other version.
+pub(crate) const SNAPSHOT_VERSION: u32 = 1;
+
+/// Everything an `ArrangementSet` holds, plus the `TabInput` that generated it.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SetSnapshot {
+    version: u32,
+    parameters: TabInput,
+    guitar: GuitarSnapshot,
+    arrangements: Vec<ArrangementSnapshot>,
+    normalized_input: Vec<NormalizedBeat>,
+    parse_warnings: Vec<ParseError>,
+    input_line_numbers: Vec<u32>,
+    durations: Vec<Option<String>>,
+}
+
+/// Read ahead of the full snapshot, so a newer format fails on its version rather than on
+/// whichever field changed.
+#[derive(Deserialize)]
+struct VersionSnapshot {
+    version: u32,
+}
+
+/// The guitar as built: open pitches from string 1 down, before the capo.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GuitarSnapshot {
+    tuning: Vec<String>,
+    num_frets: u8,
+    capo: u8,
+    harmonic_mode: HarmonicMode,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ArrangementSnapshot {
+    lines: Vec<LineSnapshot>,
+    difficulty: f64,
+    max_fret_span: u8,
+    cut_off_notes: Vec<CutOffNote>,
+}
+
+/// One `Line` of an arrangement, tagged by `kind` like `NormalizedBeat`.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(
+    tag = "kind",
+    rename_all = "camelCase",
+    rename_all_fields = "camelCase"
+)]
+enum LineSnapshot {
+    MeasureBreak,
+    RepeatStart,
+    RepeatEnd,
+    Ending { number: u8 },
+    Section { label: String },
+    Technique { technique: Technique },
+    LetRing { beats: Option<u8> },
+    Rest,
+    Playable { fingerings: Vec<FingeringSnapshot> },
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct FingeringSnapshot {
+    string: u8,
+    fret: u8,
+    pitch: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    harmonic: Option<Harmonic>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    voice: Option<Voice>,
+}
+
+/// Serializes `set` as a snapshot in JSON.
+pub(crate) fn to_json(set: &ArrangementSet) -> String {
+    serde_json::to_string(&export(set)).expect("BUG: a snapshot always serializes to JSON")
+}
+
+/// Rebuilds a set from a JSON snapshot written by [`to_json`].
+pub(crate) fn from_json(json: &str) -> Result<ArrangementSet, TabError> {
+    let VersionSnapshot { version } = serde_json::from_str(json).map_err(invalid)?;
+    check_version(version)?;
+    import(serde_json::from_str(json).map_err(invalid)?)
+}
+#[cfg(test)]
+mod test_json {
+    use super::*;
+    use crate::generate_arrangements;
+
+    fn set() -> ArrangementSet {
+        let input = "[Verse]\nE3\n\n---\nG3B3 @e\nh\nA3B3 @e.\nring 2\n\n{Am}";
+        generate_arrangements(TabInput::new(input, "openG", 18, 2, 3)).unwrap()
+    }
+
+    #[test]
+    fn round_trips() {
+        let set = set();
+        let json = to_json(&set);
+        let imported = from_json(&json).unwrap();
+        assert_eq!(to_json(&imported), json);
+        assert_eq!(
+            imported.render(1, 40, 2, Some(2)),
+            set.render(1, 40, 2, Some(2))
+        );
+    }
+    #[test]
+    fn records_the_version_and_parameters() {
+        let json: serde_json::Value = serde_json::from_str(&to_json(&set())).unwrap();
+        assert_eq!(json["version"], SNAPSHOT_VERSION);
+        assert_eq!(json["parameters"]["tuningName"], "openG");
+        assert_eq!(json["guitar"]["capo"], 2);
+        assert_eq!(json["arrangements"].as_array().unwrap().len(), 3);
+    }
+    #[test]
+    fn rejects_other_versions() {
+        let json = to_json(&set()).replacen("\"version\":1", "\"version\":2", 1);
+        assert_eq!(
+            from_json(&json).unwrap_err(),
+            TabError::SnapshotVersionUnsupported {
+                version: 2,
+                supported: SNAPSHOT_VERSION,
+            }
+        );
+    }
+    #[test]
+    fn rejects_malformed_json() {
+        assert!(matches!(
+            from_json("{\"version\":1}"),
+            Err(TabError::SnapshotInvalid { .. })
+        ));
+        assert!(matches!(
+            from_json("not json"),
+            Err(TabError::SnapshotInvalid { .. })
+        ));
+    }
+}
+
+/// Serializes `set` as a snapshot in CBOR, the compact binary form of the JSON snapshot.
+pub(crate) fn to_binary(set: &ArrangementSet) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    ciborium::into_writer(&export(set), &mut bytes)
+        .expect("BUG: a snapshot always serializes to CBOR");
+    bytes
+}
+
+/// Rebuilds a set from a CBOR snapshot written by [`to_binary`].
+pub(crate) fn from_binary(bytes: &[u8]) -> Result<ArrangementSet, TabError> {
+    let VersionSnapshot { version } = ciborium::from_reader(bytes).map_err(invalid)?;
+    check_version(version)?;
+    import(ciborium::from_reader(bytes).map_err(invalid)?)
+}
+#[cfg(test)]
+mod test_binary {
+    use super::*;
+    use crate::generate_arrangements;
+
+    #[test]
+    fn round_trips_smaller_than_json() {
+        let input = TabInput::new("E2\nA2;D3\n\nG3B3D4", "standard", 18, 0, 2)
+            .with_harmonic_mode(HarmonicMode::Natural);
+        let set = generate_arrangements(input).unwrap();
+        let bytes = to_binary(&set);
+        assert!(bytes.len() < to_json(&set).len());
+        assert_eq!(to_json(&from_binary(&bytes).unwrap()), to_json(&set));
+    }
+    #[test]
+    fn rejects_truncated_bytes() {
+        let set = generate_arrangements(TabInput::new("E2\nA2", "standard", 18, 0, 1)).unwrap();
+        let bytes = to_binary(&set);
+        assert!(matches!(
+            from_binary(&bytes[..bytes.len() / 2]),
+            Err(TabError::SnapshotInvalid { .. })
+        ));
+    }
+}
+
+fn check_version(version: u32) -> Result<(), TabError> {
+    if version == SNAPSHOT_VERSION {
+        Ok(())
+    } else {
+        Err(TabError::SnapshotVersionUnsupported {
+            version,
+            supported: SNAPSHOT_VERSION,
+        })
+    }
+}
+
+fn invalid(error: impl std::fmt::Display) -> TabError {
+    TabError::SnapshotInvalid {
+        reason: error.to_string(),
+    }
+}
+
+fn parse_pitch(text: &str) -> Result<Pitch, TabError> {
+    Pitch::from_str(text).map_err(|_| invalid(format!("unknown pitch {text:?}")))
+}
+
+fn export(set: &ArrangementSet) -> SetSnapshot {
+    SetSnapshot {
+        version: SNAPSHOT_VERSION,
+        parameters: set.parameters.clone(),
+        guitar: GuitarSnapshot {
+            tuning: set
+                .guitar
+                .tuning()
+                .map(|pitch| pitch.plain_text().to_owned())
+                .collect(),
+            num_frets: set.guitar.playable_frets + set.guitar.capo,
+            capo: set.guitar.capo,
+            harmonic_mode: set.guitar.harmonic_mode,
+        },
+        arrangements: set
+            .arrangements
+            .iter()
+            .map(|arrangement| ArrangementSnapshot {
+                lines: arrangement.lines().iter().map(export_line).collect(),
+                difficulty: arrangement.difficulty(),
+                max_fret_span: arrangement.max_fret_span(),
+                cut_off_notes: arrangement.cut_off_notes().to_vec(),
+            })
+            .collect(),
+        normalized_input: set.normalized_input.clone(),
+        parse_warnings: set.parse_warnings.clone(),
+        input_line_numbers: set.input_line_numbers.clone(),
+        durations: set
+            .durations
+            .iter()
+            .map(|duration| duration.map(|duration| duration.to_string()))
+            .collect(),
+    }
+}
+
+fn export_line(line: &Line<BeatVec<PitchFingering>>) -> LineSnapshot {
+    match line {
+        Line::MeasureBreak => LineSnapshot::MeasureBreak,
+        Line::RepeatStart => LineSnapshot::RepeatStart,
+        Line::RepeatEnd => LineSnapshot::RepeatEnd,
+        Line::Ending(number) => LineSnapshot::Ending { number: *number },
+        Line::Section(label) => LineSnapshot::Section {
+            label: label.clone(),
+        },
+        Line::Technique(technique) => LineSnapshot::Technique {
+            technique: *technique,
+        },
+        Line::LetRing(beats) => LineSnapshot::LetRing { beats: *beats },
+        Line::Rest => LineSnapshot::Rest,
+        Line::Playable(fingerings) => LineSnapshot::Playable {
+            fingerings: fingerings
+                .iter()
+                .map(|fingering| FingeringSnapshot {
+                    string: fingering.string_number.get(),
+                    fret: fingering.fret,
+                    pitch: fingering.pitch.plain_text().to_owned(),
+                    harmonic: fingering.harmonic,
+                    voice: fingering.voice,
+                })
+                .collect(),
+        },
+    }
+}
+
+/// Rebuilds the set a snapshot describes. Checks what the renders rely on: every fingering
+/// sits on a string of the guitar within its frets, and every per-line vector matches the
+/// arrangements' line count.
+fn import(snapshot: SetSnapshot) -> Result<ArrangementSet, TabError> {
+    let tuning = snapshot
+        .guitar
+        .tuning
+        .iter()
+        .map(|pitch| parse_pitch(pitch))
+        .collect::<Result<Vec<Pitch>, TabError>>()?;
+    let guitar = Guitar::new(
+        create_string_tuning(&tuning)?,
+        snapshot.guitar.num_frets,
+        snapshot.guitar.capo,
+    )?
+    .with_harmonic_mode(snapshot.guitar.harmonic_mode);
+
+    let arrangements = snapshot
+        .arrangements
+        .into_iter()
+        .map(|arrangement| {
+            let lines = arrangement
+                .lines
+                .into_iter()
+                .map(|line| import_line(line, &guitar))
+                .collect::<Result<Vec<_>, TabError>>()?;
+            Ok(Arrangement::from_parts(
+                lines,
+                arrangement.difficulty,
+                arrangement.max_fret_span,
+                arrangement.cut_off_notes,
+            ))
+        })
+        .collect::<Result<Vec<_>, TabError>>()?;
+
+    let num_lines = snapshot.normalized_input.len();
+    let mismatched = |len: usize, what: &str| -> Result<(), TabError> {
+        if len == num_lines {
+            Ok(())
+        } else {
+            Err(invalid(format!(
+                "{what} covers {len} lines, not the {num_lines} of the normalized input"
+            )))
+        }
+    };
+    for arrangement in &arrangements {
+        mismatched(arrangement.lines().len(), "an arrangement")?;
+    }
+    mismatched(snapshot.input_line_numbers.len(), "inputLineNumbers")?;
+    mismatched(snapshot.durations.len(), "durations")?;
+
+    let durations = snapshot
+        .durations
+        .iter()
+        .map(|duration| {
+            duration
+                .as_deref()
+                .map(|symbol| {
+                    Duration::from_symbol(symbol)
+                        .ok_or_else(|| invalid(format!("unknown duration {symbol:?}")))
+                })
+                .transpose()
+        })
+        .collect::<Result<Vec<_>, TabError>>()?;
+
+    Ok(ArrangementSet {
+        arrangements,
+        guitar,
+        normalized_input: snapshot.normalized_input,
+        parse_warnings: snapshot.parse_warnings,
+        input_line_numbers: snapshot.input_line_numbers,
+        durations,
+        parameters: snapshot.parameters,
+    })
+}
+
+fn import_line(
+    line: LineSnapshot,
+    guitar: &Guitar,
+) -> Result<Line<BeatVec<PitchFingering>>, TabError> {
+    Ok(match line {
+        LineSnapshot::MeasureBreak => Line::MeasureBreak,
+        LineSnapshot::RepeatStart => Line::RepeatStart,
+        LineSnapshot::RepeatEnd => Line::RepeatEnd,
+        LineSnapshot::Ending { number } => Line::Ending(number),
+        LineSnapshot::Section { label } => Line::Section(label),
+        LineSnapshot::Technique { technique } => Line::Technique(technique),
+        LineSnapshot::LetRing { beats } => Line::LetRing(beats),
+        LineSnapshot::Rest => Line::Rest,
+        LineSnapshot::Playable { fingerings } => Line::Playable(
+            fingerings
+                .into_iter()
+                .map(|fingering| {
+                    let string_number = StringNumber::new(fingering.string)?;
+                    if !guitar.string_ranges.contains_key(&string_number) {
+                        return Err(invalid(format!(
+                            "string {} is not on the guitar",
+                            fingering.string
+                        )));
+                    }
+                    if fingering.fret > guitar.playable_frets {
+                        return Err(invalid(format!(
+                            "fret {} is past the {} playable frets",
+                            fingering.fret, guitar.playable_frets
+                        )));
+                    }
+                    Ok(PitchFingering {
+                        string_number,
+                        fret: fingering.fret,
+                        pitch: parse_pitch(&fingering.pitch)?,
+                        harmonic: fingering.harmonic,
+                        voice: fingering.voice,
+                    })
+                })
+                .collect::<Result<_, TabError>>()?,
+        ),
+    })
+}
+#[cfg(test)]
+mod test_import {
+    use super::*;
+    use crate::generate_arrangements;
+
+    fn snapshot() -> SetSnapshot {
+        export(&generate_arrangements(TabInput::new("E2\nA2", "standard", 18, 0, 1)).unwrap())
+    }
+
+    fn first_fingering(snapshot: &mut SetSnapshot) -> &mut FingeringSnapshot {
+        match &mut snapshot.arrangements[0].lines[0] {
+            LineSnapshot::Playable { fingerings } => &mut fingerings[0],
+            line => panic!("expected a playable line, got {line:?}"),
+        }
+    }
+
+    #[test]
+    fn rejects_a_string_off_the_guitar() {
+        let mut snapshot = snapshot();
+        first_fingering(&mut snapshot).string = 7;
+        assert_eq!(
+            import(snapshot).unwrap_err(),
+            invalid("string 7 is not on the guitar")
+        );
+    }
+    #[test]
+    fn rejects_a_fret_past_the_neck() {
+        let mut snapshot = snapshot();
+        first_fingering(&mut snapshot).fret = 19;
+        assert_eq!(
+            import(snapshot).unwrap_err(),
+            invalid("fret 19 is past the 18 playable frets")
+        );
+    }
+    #[test]
+    fn rejects_an_unknown_pitch() {
+        let mut snapshot = snapshot();
+        first_fingering(&mut snapshot).pitch = "H2".to_owned();
+        assert_eq!(
+            import(snapshot).unwrap_err(),
+            invalid("unknown pitch \"H2\"")
+        );
+    }
+    #[test]
+    fn rejects_mismatched_line_counts() {
+        let mut snapshot = snapshot();
+        snapshot.durations.pop();
+        assert_eq!(
+            import(snapshot).unwrap_err(),
+            invalid("durations covers 1 lines, not the 2 of the normalized input")
+        );
+    }
+}
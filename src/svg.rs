@@ -0,0 +1,462 @@
+use crate::{
+    arrangement::{BeatVec, Line},
+    duration::Duration,
+    guitar::{Guitar, PitchFingering},
+    renderer::{DashStyle, LaidOutColumn, RenderOptions, escape_markup, lay_out_tab},
+};
+use std::fmt::Write;
+
+/// Width of one character cell of the ASCII tab, in SVG user units.
+const CELL_WIDTH: f64 = 10.0;
+/// Height of one line of the ASCII tab, in SVG user units.
+const LINE_HEIGHT: f64 = 16.0;
+
+/// Default look for a standalone SVG. Every rule targets a class, so page CSS can override it.
+const STYLE: &str = ".string,.bar{stroke:currentColor;stroke-width:1px}\
+.fret,.technique,.label,.chord-name,.measure-number,.rhythm,.string-label\
+{font-family:monospace;font-size:12px;fill:currentColor}\
+.fret,.technique{stroke:white;stroke-width:4px;paint-order:stroke}\
+.beat-hit{fill:transparent}\
+.repeat-dot,.playback{fill:currentColor}\
+.chord-name{font-weight:bold}\
+.beat.playing .fret{font-weight:bold}";
+
+/// Renders an `Arrangement`'s lines as an SVG guitar tab.
+///
+/// Takes the same parameters as [`render_tab`](crate::render_tab) and wraps at the same
+/// beats. Each character cell of the ASCII tab becomes a fixed-size cell of the drawing, so
+/// `width` still counts characters. Strings and bar lines are drawn as lines and frets as
+/// text. Each beat is a `<g class="beat">` with the stable ID `beat-{n}`, where `n` counts
+/// beats like `playback` does, and the beat at `playback` also gets the class `playing`.
+/// Returns an empty `<svg>` when there is nothing to draw.
+#[must_use]
+pub fn render_tab_svg(
+    arrangement_lines: &[Line<BeatVec<PitchFingering>>],
+    guitar: &Guitar,
+    width: u16,
+    padding: u8,
+    playback: Option<u16>,
+) -> String {
+    render_tab_svg_with_options(
+        arrangement_lines,
+        &[],
+        guitar,
+        &RenderOptions {
+            playback,
+            ..RenderOptions::new(width, padding)
+        },
+    )
+}
+
+/// Renders the SVG tab laid out like [`render_tab_with_rhythm`](crate::render_tab_with_rhythm)
+/// with `options` and `durations`. The drawing has no dash or bar characters, so it ignores
+/// `bar_char`, `dash_style` and `empty_string_glyph`. The other fields apply.
+pub(crate) fn render_tab_svg_with_options(
+    arrangement_lines: &[Line<BeatVec<PitchFingering>>],
+    durations: &[Option<Duration>],
+    guitar: &Guitar,
+    options: &RenderOptions,
+) -> String {
+    // Lay out with the default glyphs, which `push_column` and `push_text_runs` read.
+    let layout_options = RenderOptions {
+        bar_char: '|',
+        dash_style: DashStyle::Ascii,
+        empty_string_glyph: None,
+        ..*options
+    };
+    let Some(layout) = lay_out_tab(arrangement_lines, guitar, &layout_options, durations) else {
+        return svg_element(0.0, 0.0, "");
+    };
+
+    let num_strings = layout.rows_by_string.len();
+    let row_width = layout.row_width() as f64 * CELL_WIDTH;
+    // String labels sit in the cells left of the strings.
+    let strings_left = layout.string_labels.first().map_or(0, String::len) as f64 * CELL_WIDTH;
+    let playing_beat = options.playback.map(usize::from);
+    let mut body = String::new();
+    let mut line = 0;
+    for row_group_index in 0..layout.num_row_groups() {
+        if row_group_index > 0 {
+            line += 1; // blank line between row groups
+        }
+        let _ = write!(
+            body,
+            "<g class=\"row-group\" data-row-group=\"{row_group_index}\">"
+        );
+        for (class, heading_rows) in layout.heading_rows() {
+            if let Some(heading_row) = heading_rows
+                .get(row_group_index)
+                .filter(|row| !row.is_empty())
+            {
+                push_text_runs(&mut body, class, heading_row, 0, line);
+                line += 1;
+            }
+        }
+        let top_indicator_line = line;
+        let strings_top_line = line + 1;
+        line = strings_top_line + num_strings;
+
+        for string_index in 0..num_strings {
+            let y = line_center(strings_top_line + string_index);
+            let _ = write!(
+                body,
+                "<line class=\"string\" x1=\"{strings_left}\" y1=\"{y}\" x2=\"{row_width}\" \
+                 y2=\"{y}\"/>"
+            );
+            if let Some(string_label) = layout.string_labels.get(string_index) {
+                let name = string_label.trim_end_matches('|');
+                push_text_runs(
+                    &mut body,
+                    "string-label",
+                    name,
+                    0,
+                    strings_top_line + string_index,
+                );
+            }
+        }
+        for column in layout
+            .columns
+            .iter()
+            .filter(|column| column.position.row_group_index == row_group_index)
+        {
+            push_column(&mut body, column, strings_top_line, playing_beat);
+        }
+        if let Some(pos) = layout
+            .playback_indicator_position
+            .as_ref()
+            .filter(|pos| pos.row_group_index == row_group_index)
+        {
+            push_playback_indicators(&mut body, pos.column_index, top_indicator_line, line);
+        }
+        line += 1; // the ▲ line
+        body.push_str("</g>");
+    }
+
+    svg_element(row_width, line as f64 * LINE_HEIGHT, &body)
+}
+#[cfg(test)]
+mod test_render_tab_svg {
+    use super::*;
+    use crate::{pitch::Pitch, renderer::LabelMode, string_number::StringNumber};
+
+    fn fingering(string_number: u8, fret: u8) -> PitchFingering {
+        PitchFingering {
+            pitch: Pitch::E4,
+            string_number: StringNumber::new(string_number).unwrap(),
+            fret,
+            harmonic: None,
+            voice: None,
+        }
+    }
+
+    #[test]
+    fn draws_strings_frets_and_bars() {
+        let lines = vec![
+            Line::Playable(vec![fingering(1, 0), fingering(2, 12)]),
+            Line::MeasureBreak,
+        ];
+        let svg = render_tab_svg(&lines, &Guitar::default(), 20, 1, None);
+
+        assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\""));
+        assert!(svg.ends_with("</svg>"));
+        assert_eq!(svg.matches("<line class=\"string\"").count(), 6);
+        assert_eq!(svg.matches("<line class=\"bar\"").count(), 1);
+        assert!(svg.contains("<g class=\"beat\" id=\"beat-0\" data-beat=\"0\" data-line=\"0\">"));
+        assert!(svg.contains(">0</text>"));
+        assert!(svg.contains(">12</text>"));
+        assert!(!svg.contains("class=\"playback\""));
+    }
+    #[test]
+    fn marks_the_playing_beat() {
+        let lines = vec![
+            Line::Playable(vec![fingering(1, 0)]),
+            Line::Rest,
+            Line::Playable(vec![fingering(1, 3)]),
+        ];
+        let svg = render_tab_svg(&lines, &Guitar::default(), 20, 1, Some(1));
+
+        assert!(svg.contains("<g class=\"beat playing\" id=\"beat-1\""));
+        assert_eq!(svg.matches("class=\"beat playing\"").count(), 1);
+        assert_eq!(svg.matches("<polygon class=\"playback\"").count(), 2);
+    }
+    #[test]
+    fn wraps_like_the_ascii_tab() {
+        let lines = vec![Line::Playable(vec![fingering(1, 0)]); 6];
+        let svg = render_tab_svg(&lines, &Guitar::default(), 8, 1, None);
+
+        let num_row_groups =
+            lay_out_tab(&lines, &Guitar::default(), &RenderOptions::new(8, 1), &[])
+                .unwrap()
+                .num_row_groups();
+        assert!(num_row_groups > 1);
+        assert_eq!(
+            svg.matches("<g class=\"row-group\"").count(),
+            num_row_groups
+        );
+        assert!(svg.contains("id=\"beat-5\""));
+    }
+    #[test]
+    fn escapes_labels() {
+        let lines = vec![
+            Line::Section("Verse & <Chorus>".to_owned()),
+            Line::Playable(vec![fingering(1, 0)]),
+        ];
+        let svg = render_tab_svg(&lines, &Guitar::default(), 40, 1, None);
+
+        assert!(svg.contains("&amp;"));
+        assert!(svg.contains("&lt;Chorus&gt;]"));
+        assert!(!svg.contains("<Chorus>"));
+    }
+    #[test]
+    fn draws_repeat_dots() {
+        let lines = vec![
+            Line::RepeatStart,
+            Line::Playable(vec![fingering(1, 0)]),
+            Line::RepeatEnd,
+        ];
+        let svg = render_tab_svg(&lines, &Guitar::default(), 40, 1, None);
+
+        assert_eq!(svg.matches("<line class=\"bar\"").count(), 2);
+        assert_eq!(svg.matches("<circle class=\"repeat-dot\"").count(), 4);
+    }
+    #[test]
+    fn draws_string_labels_and_measure_numbers() {
+        let lines = vec![
+            Line::Playable(vec![fingering(1, 0)]),
+            Line::MeasureBreak,
+            Line::Playable(vec![fingering(1, 3)]),
+        ];
+        let options = RenderOptions::new(40, 1).with_label_mode(LabelMode::Strings);
+        let svg = render_tab_svg_with_options(&lines, &[], &Guitar::default(), &options);
+
+        assert_eq!(svg.matches("class=\"string-label\"").count(), 6);
+        assert!(svg.contains(">e</text>"));
+        assert_eq!(svg.matches("class=\"measure-number\"").count(), 2);
+        // The strings start after the two-cell labels.
+        assert_eq!(svg.matches("<line class=\"string\" x1=\"20\"").count(), 6);
+    }
+    #[test]
+    fn ignores_the_text_glyphs() {
+        let lines = vec![
+            Line::Playable(vec![fingering(1, 0)]),
+            Line::MeasureBreak,
+            Line::Playable(vec![fingering(2, 3)]),
+        ];
+        let options = RenderOptions::new(20, 1)
+            .with_bar_char('‖')
+            .with_dash_style(DashStyle::Unicode)
+            .with_empty_string_glyph('·');
+        assert_eq!(
+            render_tab_svg_with_options(&lines, &[], &Guitar::default(), &options),
+            render_tab_svg(&lines, &Guitar::default(), 20, 1, None)
+        );
+    }
+    #[test]
+    fn nothing_to_draw() {
+        assert_eq!(
+            render_tab_svg(&[], &Guitar::default(), 20, 1, None),
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" class=\"tab\" width=\"0\" height=\"0\" \
+             viewBox=\"0 0 0 0\"><style>"
+                .to_owned()
+                + STYLE
+                + "</style></svg>"
+        );
+    }
+}
+
+/// Wraps `body` in the root `<svg>` element and its default style sheet.
+fn svg_element(width: f64, height: f64, body: &str) -> String {
+    format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" class=\"tab\" width=\"{width}\" \
+         height=\"{height}\" viewBox=\"0 0 {width} {height}\"><style>{STYLE}</style>{body}</svg>"
+    )
+}
+
+/// Vertical center of tab line `line`.
+fn line_center(line: usize) -> f64 {
+    (line as f64 + 0.5) * LINE_HEIGHT
+}
+
+/// Horizontal center of character cell `cell`.
+fn cell_center(cell: usize) -> f64 {
+    (cell as f64 + 0.5) * CELL_WIDTH
+}
+
+/// Draws one laid-out column. A beat is a group with a hit area spanning its strings, so
+/// page CSS and click handlers can target the whole beat. Bar lines and repeats become
+/// lines and dots, and technique symbols become text.
+fn push_column(
+    out: &mut String,
+    column: &LaidOutColumn,
+    strings_top_line: usize,
+    playing_beat: Option<usize>,
+) {
+    let start = column.position.column_index;
+    let num_strings = column.cells.len();
+    let Some(beat_index) = column.beat_index else {
+        if column
+            .cells
+            .iter()
+            .all(|cell| cell.chars().all(|c| c == '|' || c == ':'))
+        {
+            push_bar(out, column, strings_top_line);
+        } else {
+            for (string_index, cell) in column.cells.iter().enumerate() {
+                push_text_runs(
+                    out,
+                    "technique",
+                    cell,
+                    start,
+                    strings_top_line + string_index,
+                );
+            }
+        }
+        return;
+    };
+
+    let class = if playing_beat == Some(beat_index) {
+        "beat playing"
+    } else {
+        "beat"
+    };
+    let _ = write!(
+        out,
+        "<g class=\"{class}\" id=\"beat-{beat_index}\" data-beat=\"{beat_index}\" \
+         data-line=\"{}\">",
+        column.line_index
+    );
+    let _ = write!(
+        out,
+        "<rect class=\"beat-hit\" x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\"/>",
+        start as f64 * CELL_WIDTH,
+        strings_top_line as f64 * LINE_HEIGHT,
+        column.width() as f64 * CELL_WIDTH,
+        num_strings as f64 * LINE_HEIGHT,
+    );
+    for (string_index, cell) in column.cells.iter().enumerate() {
+        push_text_runs(out, "fret", cell, start, strings_top_line + string_index);
+    }
+    out.push_str("</g>");
+}
+
+/// Draws a bar line or repeat column: a line across the strings for each `|`, and two dots
+/// straddling the middle string space for each `:`.
+fn push_bar(out: &mut String, column: &LaidOutColumn, strings_top_line: usize) {
+    let Some(cell) = column.cells.first() else {
+        return;
+    };
+    let num_strings = column.cells.len();
+    let y1 = line_center(strings_top_line);
+    let y2 = line_center(strings_top_line + num_strings - 1);
+    // The dots sit in the string spaces either side of the middle of the staff.
+    let middle = (num_strings as f64 - 1.0) / 2.0;
+    let dot_offset = if num_strings.is_multiple_of(2) {
+        1.0
+    } else {
+        0.5
+    };
+    for (offset, c) in cell.chars().enumerate() {
+        let x = cell_center(column.position.column_index + offset);
+        match c {
+            '|' => {
+                let _ = write!(
+                    out,
+                    "<line class=\"bar\" x1=\"{x}\" y1=\"{y1}\" x2=\"{x}\" y2=\"{y2}\"/>"
+                );
+            }
+            ':' => {
+                for space in [middle - dot_offset, middle + dot_offset] {
+                    let _ = write!(
+                        out,
+                        "<circle class=\"repeat-dot\" cx=\"{x}\" cy=\"{}\" r=\"2\"/>",
+                        (strings_top_line as f64 + space + 0.5) * LINE_HEIGHT
+                    );
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Writes each run of `text` that is neither a dash nor a space as a `<text>` element
+/// stretched over its character cells, so it lines up with the ASCII grid whatever the font.
+fn push_text_runs(out: &mut String, class: &str, text: &str, start: usize, line: usize) {
+    let y = line_center(line);
+    let chars: Vec<char> = text.chars().collect();
+    let mut offset = 0;
+    while offset < chars.len() {
+        if chars[offset] == '-' || chars[offset] == ' ' {
+            offset += 1;
+            continue;
+        }
+        let run_start = offset;
+        while offset < chars.len() && chars[offset] != '-' && chars[offset] != ' ' {
+            offset += 1;
+        }
+        let run: String = chars[run_start..offset].iter().collect();
+        let run_len = offset - run_start;
+        let _ = write!(
+            out,
+            "<text class=\"{class}\" x=\"{}\" y=\"{y}\" textLength=\"{}\" \
+             text-anchor=\"middle\" dominant-baseline=\"central\">{}</text>",
+            (start + run_start) as f64 * CELL_WIDTH + run_len as f64 * CELL_WIDTH / 2.0,
+            run_len as f64 * CELL_WIDTH,
+            escape_markup(&run),
+        );
+    }
+}
+#[cfg(test)]
+mod test_push_text_runs {
+    use super::*;
+
+    #[test]
+    fn splits_on_dashes_and_spaces() {
+        let mut out = String::new();
+        push_text_runs(&mut out, "fret", "-12-h ", 3, 0);
+        assert_eq!(
+            out,
+            "<text class=\"fret\" x=\"50\" y=\"8\" textLength=\"20\" text-anchor=\"middle\" \
+             dominant-baseline=\"central\">12</text>\
+             <text class=\"fret\" x=\"75\" y=\"8\" textLength=\"10\" text-anchor=\"middle\" \
+             dominant-baseline=\"central\">h</text>"
+        );
+    }
+    #[test]
+    fn skips_an_empty_cell() {
+        let mut out = String::new();
+        push_text_runs(&mut out, "fret", "--", 0, 0);
+        assert!(out.is_empty());
+    }
+}
+
+/// Draws the `▼` and `▲` playback triangles above and below the strings at character cell
+/// `column_index`.
+fn push_playback_indicators(
+    out: &mut String,
+    column_index: usize,
+    top_line: usize,
+    bottom_line: usize,
+) {
+    let x = cell_center(column_index);
+    let half_width = CELL_WIDTH * 0.4;
+    let top = top_line as f64 * LINE_HEIGHT;
+    let bottom = bottom_line as f64 * LINE_HEIGHT;
+    let _ = write!(
+        out,
+        "<polygon class=\"playback\" points=\"{},{} {},{} {x},{}\"/>",
+        x - half_width,
+        top + 3.0,
+        x + half_width,
+        top + 3.0,
+        top + LINE_HEIGHT - 3.0,
+    );
+    let _ = write!(
+        out,
+        "<polygon class=\"playback\" points=\"{},{} {},{} {x},{}\"/>",
+        x - half_width,
+        bottom + LINE_HEIGHT - 3.0,
+        x + half_width,
+        bottom + LINE_HEIGHT - 3.0,
+        bottom + 3.0,
+    );
+}
@@ -0,0 +1,89 @@
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "wasm")]
+use tsify::Tsify;
+
+/// A playing technique written on its own line between two beats (or after one, for
+/// vibrato).
+///
+/// `HammerOn`, `PullOff`, `Slide` and `Bend` link the beat before them to the beat after
+/// them: both beats must be played on the same strings, and the rendered tab joins them as
+/// `5h7`, `7p5`, `7/9` or `7b9`. `Vibrato` marks the beat before it and renders as `7~`.
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[cfg_attr(feature = "wasm", derive(Tsify))]
+#[serde(rename_all = "camelCase")]
+pub enum Technique {
+    HammerOn,
+    PullOff,
+    Slide,
+    Bend,
+    Vibrato,
+}
+impl Technique {
+    /// Parses the input symbol for a technique: `h`, `p`, `/`, `b` or `~`. Letters are
+    /// case-insensitive.
+    #[must_use]
+    pub fn from_symbol(symbol: &str) -> Option<Self> {
+        match symbol {
+            "h" | "H" => Some(Technique::HammerOn),
+            "p" | "P" => Some(Technique::PullOff),
+            "/" => Some(Technique::Slide),
+            "b" | "B" => Some(Technique::Bend),
+            "~" => Some(Technique::Vibrato),
+            _ => None,
+        }
+    }
+
+    /// The character drawn in the rendered tab, which is also the lowercase input symbol.
+    #[must_use]
+    pub fn symbol(self) -> char {
+        match self {
+            Technique::HammerOn => 'h',
+            Technique::PullOff => 'p',
+            Technique::Slide => '/',
+            Technique::Bend => 'b',
+            Technique::Vibrato => '~',
+        }
+    }
+
+    /// Returns true when the technique joins the beats on either side of it, which must then
+    /// share strings. Only `Vibrato` stands alone.
+    #[must_use]
+    pub fn links_beats(self) -> bool {
+        !matches!(self, Technique::Vibrato)
+    }
+}
+#[cfg(test)]
+mod test_technique {
+    use super::*;
+
+    #[test]
+    fn symbols_round_trip() {
+        for technique in [
+            Technique::HammerOn,
+            Technique::PullOff,
+            Technique::Slide,
+            Technique::Bend,
+            Technique::Vibrato,
+        ] {
+            let symbol = technique.symbol().to_string();
+            assert_eq!(Technique::from_symbol(&symbol), Some(technique));
+        }
+    }
+    #[test]
+    fn letters_are_case_insensitive() {
+        assert_eq!(Technique::from_symbol("H"), Some(Technique::HammerOn));
+        assert_eq!(Technique::from_symbol("B"), Some(Technique::Bend));
+    }
+    #[test]
+    fn rejects_other_text() {
+        assert_eq!(Technique::from_symbol(""), None);
+        assert_eq!(Technique::from_symbol("hp"), None);
+        assert_eq!(Technique::from_symbol("\\"), None);
+    }
+    #[test]
+    fn only_vibrato_stands_alone() {
+        assert!(Technique::HammerOn.links_beats());
+        assert!(Technique::Bend.links_beats());
+        assert!(!Technique::Vibrato.links_beats());
+    }
+}
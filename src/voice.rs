@@ -0,0 +1,117 @@
+use crate::{arrangement::BeatVec, pitch::Pitch, string_number::StringNumber};
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "wasm")]
+use tsify::Tsify;
+
+/// The part a note plays in multi-voice input.
+///
+/// A line split into voice columns with `;` reads as melody first and bass last, with any
+/// columns between them as inner voices. The arranger steers the melody onto the treble
+/// strings and the bass onto the bass strings. Inner voices go anywhere.
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[cfg_attr(feature = "wasm", derive(Tsify))]
+#[serde(rename_all = "camelCase")]
+pub enum Voice {
+    Melody,
+    Inner,
+    Bass,
+}
+
+/// The highest strings, counted from string 1, that the melody prefers. The rest are bass
+/// strings. On a standard guitar these are the G, B and high E strings.
+const NUM_TREBLE_STRINGS: u8 = 3;
+
+impl Voice {
+    /// The voice of column `column_index` in a line split into `num_columns` columns.
+    pub(crate) fn of_column(column_index: usize, num_columns: usize) -> Self {
+        if column_index == 0 {
+            Voice::Melody
+        } else if column_index + 1 == num_columns {
+            Voice::Bass
+        } else {
+            Voice::Inner
+        }
+    }
+
+    /// Returns true when `string_number` lies outside the strings this voice prefers.
+    pub(crate) fn is_off_its_strings(self, string_number: StringNumber) -> bool {
+        let is_treble_string = string_number.get() <= NUM_TREBLE_STRINGS;
+        match self {
+            Voice::Melody => !is_treble_string,
+            Voice::Bass => is_treble_string,
+            Voice::Inner => false,
+        }
+    }
+}
+#[cfg(test)]
+mod test_voice {
+    use super::*;
+
+    #[test]
+    fn columns_read_melody_to_bass() {
+        assert_eq!(Voice::of_column(0, 2), Voice::Melody);
+        assert_eq!(Voice::of_column(1, 2), Voice::Bass);
+        assert_eq!(Voice::of_column(1, 3), Voice::Inner);
+        assert_eq!(Voice::of_column(2, 3), Voice::Bass);
+    }
+    #[test]
+    fn melody_prefers_treble_and_bass_prefers_bass_strings() {
+        let string = |number| StringNumber::new(number).unwrap();
+        assert!(!Voice::Melody.is_off_its_strings(string(1)));
+        assert!(Voice::Melody.is_off_its_strings(string(4)));
+        assert!(Voice::Bass.is_off_its_strings(string(3)));
+        assert!(!Voice::Bass.is_off_its_strings(string(6)));
+        assert!(!Voice::Inner.is_off_its_strings(string(1)));
+        assert!(!Voice::Inner.is_off_its_strings(string(6)));
+    }
+}
+
+/// One voice column of a multi-voice input line: its voice, its pitches in input order, and
+/// how many beats they last. `beats` is `1` unless the column ends in `:N`.
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct VoiceColumn {
+    pub voice: Voice,
+    pub pitches: BeatVec<Pitch>,
+    pub beats: u8,
+}
+
+/// The voice of each pitch in a voiced line's merged `Playable` beat, in beat order.
+pub(crate) fn pitch_voices(columns: &[VoiceColumn]) -> Vec<Voice> {
+    columns
+        .iter()
+        .flat_map(|column| std::iter::repeat_n(column.voice, column.pitches.len()))
+        .collect()
+}
+#[cfg(test)]
+mod test_pitch_voices {
+    use super::*;
+
+    #[test]
+    fn follows_column_order() {
+        let columns = vec![
+            VoiceColumn {
+                voice: Voice::Melody,
+                pitches: vec![Pitch::E4, Pitch::G4],
+                beats: 1,
+            },
+            VoiceColumn {
+                voice: Voice::Bass,
+                pitches: vec![Pitch::E2],
+                beats: 2,
+            },
+        ];
+        assert_eq!(
+            pitch_voices(&columns),
+            vec![Voice::Melody, Voice::Melody, Voice::Bass]
+        );
+    }
+    #[test]
+    fn empty_columns_add_nothing() {
+        let columns = vec![VoiceColumn {
+            voice: Voice::Melody,
+            pitches: vec![],
+            beats: 1,
+        }];
+        assert!(pitch_voices(&columns).is_empty());
+    }
+}
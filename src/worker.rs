@@ -0,0 +1,117 @@
+//! The Web Worker message protocol, built with the `wasm` feature.
+//!
+//! A worker hands each posted [`WorkerRequest`] to [`handle_worker_request`] and posts the
+//! [`WorkerResponse`] back. A successful response carries the set as a binary snapshot in a
+//! `Uint8Array`, whose buffer the worker can transfer instead of copying. The page rebuilds
+//! the set with `ArrangementSet.importBinary` without pathfinding again, so the arranging
+//! runs off the UI thread. `examples/arranger-worker.js` and `examples/arranger.js` are the
+//! worker script and the promise-based page side.
+
+use crate::{TabError, TabInput, generate_arrangements};
+use serde::{Deserialize, Serialize, Serializer};
+use tsify::Tsify;
+use wasm_bindgen::prelude::*;
+
+/// One arranging job posted to a worker. `id` comes back on the response, so a page can
+/// match responses to the requests it has in flight.
+#[derive(Debug, Clone, Serialize, Deserialize, Tsify)]
+#[tsify(from_wasm_abi)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkerRequest {
+    pub id: u32,
+    pub tab_input: TabInput,
+}
+
+/// The outcome of a [`WorkerRequest`], tagged by `kind`. Never thrown, so a worker can post
+/// failures back like successes.
+#[derive(Debug, Clone, PartialEq, Serialize, Tsify)]
+#[tsify(into_wasm_abi)]
+#[serde(
+    tag = "kind",
+    rename_all = "camelCase",
+    rename_all_fields = "camelCase"
+)]
+pub enum WorkerResponse {
+    /// `snapshot` is the set as `ArrangementSet::export_binary` writes it.
+    Arranged {
+        id: u32,
+        #[serde(serialize_with = "serialize_bytes")]
+        #[tsify(type = "Uint8Array")]
+        snapshot: Vec<u8>,
+    },
+    Failed {
+        id: u32,
+        error: TabError,
+    },
+}
+
+/// Writes `bytes` as a byte string, which reaches JS as a `Uint8Array` rather than an array
+/// of numbers.
+fn serialize_bytes<S: Serializer>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_bytes(bytes)
+}
+
+/// Arranges the input of `request` like [`generate_arrangements`] and returns the set as a
+/// binary snapshot, or the error it failed with.
+#[wasm_bindgen(js_name = "handleWorkerRequest")]
+pub fn handle_worker_request(request: WorkerRequest) -> WorkerResponse {
+    match generate_arrangements(request.tab_input) {
+        Ok(set) => WorkerResponse::Arranged {
+            id: request.id,
+            snapshot: set.export_binary(),
+        },
+        Err(error) => WorkerResponse::Failed {
+            id: request.id,
+            error,
+        },
+    }
+}
+#[cfg(test)]
+mod test_handle_worker_request {
+    use super::*;
+    use crate::ArrangementSet;
+
+    #[test]
+    fn arranged_snapshot_imports() {
+        let tab_input = TabInput::new("E2\nA2", "standard", 18, 0, 2);
+        let response = handle_worker_request(WorkerRequest {
+            id: 7,
+            tab_input: tab_input.clone(),
+        });
+        let WorkerResponse::Arranged { id, snapshot } = response else {
+            panic!("expected an arranged response, got {response:?}");
+        };
+        assert_eq!(id, 7);
+        let set = ArrangementSet::import_binary(&snapshot).unwrap();
+        let expected = generate_arrangements(tab_input).unwrap();
+        assert_eq!(set.export_json(), expected.export_json());
+    }
+    #[test]
+    fn failure_is_a_response() {
+        let response = handle_worker_request(WorkerRequest {
+            id: 8,
+            tab_input: TabInput::new("E2", "openZ", 18, 0, 1),
+        });
+        assert_eq!(
+            response,
+            WorkerResponse::Failed {
+                id: 8,
+                error: TabError::TuningNameUnknown {
+                    value: "openZ".to_owned()
+                },
+            }
+        );
+    }
+    #[test]
+    fn request_and_response_shapes() {
+        let request: WorkerRequest = serde_json::from_str(
+            r#"{"id":1,"tabInput":{"input":"E2","tuningName":"standard","guitarNumFrets":18,"guitarCapo":0,"numArrangements":1}}"#,
+        )
+        .unwrap();
+        assert_eq!(request.id, 1);
+        let response = serde_json::to_value(handle_worker_request(request)).unwrap();
+        assert_eq!(response["kind"], "arranged");
+        assert_eq!(response["id"], 1);
+        assert!(response["snapshot"].is_array());
+    }
+}
@@ -0,0 +1,65 @@
+/* Smoke test of the C API, compiled and run by tests/ffi.rs against the built cdylib. */
+
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+#include "guitar_tab_generator.h"
+
+#define CHECK(condition)                                                       \
+  do {                                                                         \
+    if (!(condition)) {                                                        \
+      fprintf(stderr, "%s:%d: check failed: %s\n", __FILE__, __LINE__,         \
+              #condition);                                                     \
+      return 1;                                                                \
+    }                                                                          \
+  } while (0)
+
+int main(void) {
+  GtgRequest *request = NULL;
+  GtgSet *set = NULL;
+  GtgError *error = NULL;
+
+  CHECK(gtg_request_new("E2\nA2\nD3", "standard", 18, 0, 2, &request, &error) ==
+        GTG_STATUS_OK);
+  CHECK(gtg_generate(request, &set, &error) == GTG_STATUS_OK);
+  gtg_request_free(request);
+  CHECK(gtg_set_len(set) >= 1);
+
+  double difficulty = -1.0;
+  uint8_t span = UINT8_MAX;
+  CHECK(gtg_set_difficulty(set, 0, &difficulty, &error) == GTG_STATUS_OK);
+  CHECK(difficulty >= 0.0);
+  CHECK(gtg_set_max_fret_span(set, 0, &span, &error) == GTG_STATUS_OK);
+  CHECK(span == 0);
+
+  size_t len = 0;
+  CHECK(gtg_set_render(set, 0, 40, 2, NULL, 0, &len, &error) ==
+        GTG_STATUS_BUFFER_TOO_SMALL);
+  CHECK(gtg_error_status(error) == GTG_STATUS_BUFFER_TOO_SMALL);
+  gtg_error_free(error);
+  error = NULL;
+  char *tab = malloc(len + 1);
+  CHECK(tab != NULL);
+  CHECK(gtg_set_render(set, 0, 40, 2, tab, len + 1, &len, &error) ==
+        GTG_STATUS_OK);
+  CHECK(strlen(tab) == len);
+  CHECK(strstr(tab, "-0-") != NULL);
+  printf("%s\n", tab);
+  free(tab);
+
+  CHECK(gtg_set_difficulty(set, 5, &difficulty, &error) ==
+        GTG_STATUS_INDEX_OUT_OF_BOUNDS);
+  CHECK(strstr(gtg_error_message(error), "5") != NULL);
+  gtg_error_free(error);
+  error = NULL;
+  gtg_set_free(set);
+
+  CHECK(gtg_request_new("E2", "openZ", 18, 0, 1, &request, NULL) ==
+        GTG_STATUS_OK);
+  CHECK(gtg_generate(request, &set, &error) == GTG_STATUS_TUNING_NAME_UNKNOWN);
+  CHECK(strstr(gtg_error_message(error), "openZ") != NULL);
+  gtg_error_free(error);
+  gtg_request_free(request);
+  return 0;
+}
@@ -0,0 +1,82 @@
+//! End-to-end tests of the `guitar-tab` binary.
+//!
+//! `src/cli.rs` tests the program through `cli::run`. These spawn the built binary to cover
+//! what only the process shows: reading stdin and files, and the exit status.
+//!
+//! Empty without the `cli` feature, which the binary requires.
+#![cfg(feature = "cli")]
+
+use std::{
+    io::Write,
+    process::{Command, Output, Stdio},
+};
+
+fn guitar_tab(args: &[&str], stdin: &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_guitar-tab"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("the guitar-tab binary runs");
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(stdin.as_bytes())
+        .unwrap();
+    child.wait_with_output().unwrap()
+}
+
+#[test]
+fn renders_stdin() {
+    let output = guitar_tab(&["--width", "20", "--padding", "1"], "E2\nA2");
+    assert!(output.status.success());
+    assert!(
+        String::from_utf8(output.stdout)
+            .unwrap()
+            .ends_with("\n---0----------------\n-0------------------\n\n")
+    );
+}
+
+#[test]
+fn reads_a_file() {
+    let path = std::env::temp_dir().join(format!("guitar-tab-{}.txt", std::process::id()));
+    std::fs::write(&path, "E2").unwrap();
+    let output = guitar_tab(&["--json", path.to_str().unwrap()], "");
+    std::fs::remove_file(&path).unwrap();
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["arrangements"].as_array().unwrap().len(), 1);
+}
+
+#[test]
+fn exits_with_distinct_codes() {
+    assert_eq!(guitar_tab(&["--capo"], "").status.code(), Some(2));
+    assert_eq!(
+        guitar_tab(&["/nonexistent/song.txt"], "").status.code(),
+        Some(3)
+    );
+    assert_eq!(guitar_tab(&[], "H2").status.code(), Some(10));
+    assert_eq!(guitar_tab(&["--capo", "9"], "E2").status.code(), Some(13));
+    assert_eq!(
+        guitar_tab(&["--tuning", "openZ"], "E2").status.code(),
+        Some(21)
+    );
+}
+
+#[test]
+fn batch_exits_on_failed_inputs() {
+    let dir = std::env::temp_dir().join(format!("guitar-tab-batch-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("good.txt"), "E2").unwrap();
+    std::fs::write(dir.join("bad.txt"), "H2").unwrap();
+    let output = guitar_tab(&["batch", dir.to_str().unwrap()], "");
+    std::fs::remove_dir_all(&dir).unwrap();
+    assert_eq!(output.status.code(), Some(4));
+    assert!(
+        String::from_utf8(output.stdout)
+            .unwrap()
+            .ends_with("1 arranged, 1 failed\n")
+    );
+}
@@ -0,0 +1,60 @@
+//! C smoke test of the `ffi` feature.
+//!
+//! `src/ffi.rs` tests the API from Rust. This compiles `tests/c/smoke.c` against
+//! `include/guitar_tab_generator.h`, links it to the `cdylib` cargo built for this run and
+//! runs it, covering the header and the exported symbols a C or C++ caller sees.
+//!
+//! Empty without the `ffi` feature, and off Unix, where the linker flags differ. Skipped
+//! with a note when no C compiler is installed. `CC` picks the compiler, `cc` by default.
+#![cfg(all(feature = "ffi", unix))]
+
+use std::{
+    env::consts::{DLL_PREFIX, DLL_SUFFIX},
+    io::ErrorKind,
+    path::Path,
+    process::Command,
+};
+
+#[test]
+fn c_smoke_test() {
+    let manifest_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+    // Integration tests run from `target/<profile>/deps`, next to the library.
+    let test_exe = std::env::current_exe().unwrap();
+    let lib_dir = test_exe.parent().unwrap();
+    let smoke_exe = lib_dir.join("guitar_tab_generator_c_smoke");
+
+    let compiler = std::env::var("CC").unwrap_or_else(|_| "cc".to_owned());
+    let compiled = Command::new(&compiler)
+        .arg(manifest_dir.join("tests/c/smoke.c"))
+        .arg("-std=c99")
+        .arg("-Wall")
+        .arg("-Werror")
+        .arg("-I")
+        .arg(manifest_dir.join("include"))
+        // By path rather than `-l`: cargo puts `target/<profile>` on the library path, and
+        // the copy there may come from a build without the `ffi` feature.
+        .arg(lib_dir.join(format!("{DLL_PREFIX}guitar_tab_generator{DLL_SUFFIX}")))
+        .arg(format!("-Wl,-rpath,{}", lib_dir.display()))
+        .arg("-o")
+        .arg(&smoke_exe)
+        .status();
+    let compiled = match compiled {
+        Err(error) if error.kind() == ErrorKind::NotFound => {
+            eprintln!("skipping the C smoke test: no C compiler {compiler:?}");
+            return;
+        }
+        compiled => compiled.unwrap(),
+    };
+    assert!(
+        compiled.success(),
+        "{compiler} could not build tests/c/smoke.c"
+    );
+
+    let output = Command::new(&smoke_exe).output().unwrap();
+    assert!(
+        output.status.success(),
+        "tests/c/smoke.c failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(String::from_utf8_lossy(&output.stdout).contains("-0-"));
+}
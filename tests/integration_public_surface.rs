@@ -14,10 +14,18 @@
 #![cfg(not(target_arch = "wasm32"))]
 
 use guitar_tab_generator::{
-    Arrangement, ArrangementSet, BeatVec, DifficultyWeights, Guitar, Line, NormalizedBeat,
-    NumArrangements, ParseError, Pitch, PitchFingering, StringNumber, TabError, TabInput,
-    TuningName, UnplayablePitch, create_arrangements, create_string_tuning, generate_arrangements,
-    get_tuning_names, parse_lines, render_tab,
+    Arrangement, ArrangementLine, ArrangementSet, Barre, BatchEntry, BatchInput, BatchReport,
+    BatchSummaryRow, BeatPosition, BeatVec, ChordShape, ChordSymbol, ColumnPosition, CutOffNote,
+    DashStyle, DifficultyWeights, Duration, Guitar, Harmonic, HarmonicMode, LabelMode, Line,
+    NormalizedBeat, NoteFingering, NumArrangements, ParseError, ParsedLine, Pitch, PitchFingering,
+    PlaybackIndicatorPosition, RenderOptions, RenderedTab, StringNumber, TabError, TabInput,
+    Technique, TuningName, UnplayablePitch, Voice, VoiceColumn, arrange_batch, create_arrangements,
+    create_parsed_arrangements, create_string_tuning, expand_repeats, generate_arrangements,
+    get_tuning_names, name_chord, parse_input, parse_input_lenient, parse_lines,
+    parse_lines_lenient, render_chord_diagram, render_chord_diagram_svg, render_tab,
+    render_tab_header, render_tab_html, render_tab_svg, render_tab_with_chord_names,
+    render_tab_with_labels, render_tab_with_layout, render_tab_with_options,
+    render_tab_with_rhythm,
 };
 
 fn fixture(num: u8) -> TabInput {
@@ -60,6 +68,167 @@ fn render_is_stateless_across_repeated_calls() {
     );
 }
 
+#[test]
+fn render_svg_gives_each_beat_a_stable_id() {
+    let set = generate_arrangements(fixture(1)).unwrap();
+    let svg = set
+        .render_svg(0, RenderOptions::new(30, 2).with_playback(1))
+        .unwrap();
+    assert!(svg.starts_with("<svg"));
+    assert!(svg.contains("id=\"beat-0\""));
+    assert!(svg.contains("<g class=\"beat playing\" id=\"beat-1\""));
+    assert!(svg.contains("id=\"beat-2\""));
+    assert!(matches!(
+        set.render_svg(0, RenderOptions::new(3, 1)),
+        Err(TabError::RenderWidthTooSmall { width: 3, min: 5 })
+    ));
+
+    let arrangements = create_arrangements(
+        Guitar::default(),
+        parse_lines("E2".to_owned()).unwrap(),
+        NumArrangements::try_new(1).unwrap(),
+        DifficultyWeights::standard(),
+        None,
+    )
+    .unwrap();
+    let svg = render_tab_svg(arrangements[0].lines(), &Guitar::default(), 30, 2, None);
+    assert!(svg.contains("data-beat=\"0\""));
+}
+
+#[test]
+fn render_html_wraps_beats_in_data_spans() {
+    let set = generate_arrangements(fixture(1)).unwrap();
+    let html = set
+        .render_html(0, RenderOptions::new(30, 2).with_playback(1))
+        .unwrap();
+    assert!(html.starts_with("<pre class=\"tab\">"));
+    assert!(html.contains("data-beat=\"0\" data-line=\"0\""));
+    assert!(html.contains("<span class=\"beat playing\" data-beat=\"1\""));
+    assert!(matches!(
+        set.render_html(0, RenderOptions::new(3, 1)),
+        Err(TabError::RenderWidthTooSmall { width: 3, min: 5 })
+    ));
+
+    let lines = parse_lines("E2".to_owned()).unwrap();
+    let arrangements = create_arrangements(
+        Guitar::default(),
+        lines,
+        NumArrangements::try_new(1).unwrap(),
+        DifficultyWeights::standard(),
+        None,
+    )
+    .unwrap();
+    let html = render_tab_html(arrangements[0].lines(), &Guitar::default(), 30, 2, None);
+    assert!(html.contains("data-beat=\"0\""));
+}
+
+#[test]
+fn render_with_layout_maps_beats_to_input_lines() {
+    let set = generate_arrangements(fixture(1)).unwrap();
+    let rendered: RenderedTab = set
+        .render_with_layout(0, RenderOptions::new(30, 2).with_playback(1))
+        .unwrap();
+    assert_eq!(rendered.tab, set.render(0, 30, 2, Some(1)).unwrap());
+    assert_eq!(rendered.row_group_lines, vec![1]);
+    let second_beat: &BeatPosition = &rendered.beats[1];
+    assert_eq!(second_beat.line, 2);
+    assert_eq!(
+        second_beat.start,
+        ColumnPosition {
+            row_group_index: 0,
+            column_index: 5,
+        }
+    );
+    assert_eq!(second_beat.end_column_index, 6);
+    assert_eq!(
+        rendered.playback_indicator,
+        Some(PlaybackIndicatorPosition {
+            row_group_index: 0,
+            column_index: 5,
+        })
+    );
+
+    let lines = parse_lines("E2".to_owned()).unwrap();
+    let arrangements = create_arrangements(
+        Guitar::default(),
+        lines,
+        NumArrangements::try_new(1).unwrap(),
+        DifficultyWeights::standard(),
+        None,
+    )
+    .unwrap();
+    let rendered = render_tab_with_layout(arrangements[0].lines(), &Guitar::default(), 30, 2, None);
+    assert_eq!(rendered.beats.len(), 1);
+}
+
+#[test]
+fn chord_shapes_list_distinct_chord_beats() {
+    let input = TabInput::new(
+        "F2 C3 F3 A3 C4 F4\nE4\nF2 C3 F3 A3 C4 F4",
+        "standard",
+        18,
+        0,
+        1,
+    );
+    let set = generate_arrangements(input).unwrap();
+    let shapes: Vec<ChordShape> = set.chord_shapes(0).unwrap();
+    assert_eq!(shapes.len(), 1);
+    assert_eq!(
+        shapes[0].barre,
+        Some(Barre {
+            fret: 1,
+            from_string: 6,
+            to_string: 1,
+        })
+    );
+    assert_eq!(
+        set.chord_diagrams(0).unwrap(),
+        vec![render_chord_diagram(&shapes[0])]
+    );
+    assert_eq!(
+        set.chord_diagrams_svg(0).unwrap(),
+        vec![render_chord_diagram_svg(&shapes[0])]
+    );
+    assert!(matches!(
+        set.chord_shapes(1),
+        Err(TabError::IndexOutOfBounds { index: 1, len: 1 })
+    ));
+}
+
+#[test]
+fn chord_names_reach_normalized_input_and_render() {
+    assert_eq!(
+        name_chord(&[Pitch::B2, Pitch::D3, Pitch::G3]).as_deref(),
+        Some("G/B")
+    );
+
+    let set =
+        generate_arrangements(TabInput::new("A2 E3 A3 C4\nE4", "standard", 18, 0, 1)).unwrap();
+    let beats = set.normalized_input();
+    assert!(matches!(
+        &beats[0],
+        NormalizedBeat::Playable { chord: Some(chord), .. } if chord == "Am"
+    ));
+    assert!(matches!(
+        &beats[1],
+        NormalizedBeat::Playable { chord: None, .. }
+    ));
+    let tab = set.render_with_chord_names(0, 30, 2, None).unwrap();
+    assert!(tab.starts_with("  Am\n"), "got:\n{tab}");
+
+    let lines = parse_lines("A2 E3 A3 C4".to_owned()).unwrap();
+    let arrangements = create_arrangements(
+        Guitar::default(),
+        lines,
+        NumArrangements::try_new(1).unwrap(),
+        DifficultyWeights::standard(),
+        None,
+    )
+    .unwrap();
+    let tab = render_tab_with_chord_names(arrangements[0].lines(), &Guitar::default(), 30, 2, None);
+    assert!(tab.starts_with("  Am\n"), "got:\n{tab}");
+}
+
 #[test]
 fn tab_input_round_trips_from_camel_case_json() {
     let json = r#"{
@@ -131,6 +300,30 @@ fn arrangement_set_is_empty_when_filter_drops_every_candidate() {
     assert_eq!(set.len(), 0);
 }
 
+#[test]
+fn lenient_parse_reports_warnings_instead_of_failing() {
+    let (lines, warnings) = parse_lines_lenient("E2\n???".to_owned()).unwrap();
+    assert_eq!(lines, vec![Line::Playable(vec![Pitch::E2]), Line::Rest]);
+    assert_eq!(warnings.len(), 1);
+
+    let (parsed_lines, warnings) = parse_input_lenient("E2 @q\n???".to_owned()).unwrap();
+    assert_eq!(parsed_lines[1], ParsedLine::new(Line::Rest, 2));
+    assert_eq!(parsed_lines[0].duration, Duration::from_symbol("q"));
+    assert_eq!(warnings.len(), 1);
+
+    let input = TabInput::new("E2\n???\nA2", "standard", 18, 0, 1).with_lenient_parse(true);
+    let set = generate_arrangements(input).expect("lenient parse must not fail on bad text");
+    let warnings: Vec<ParseError> = set.parse_warnings();
+    assert_eq!(
+        warnings,
+        vec![ParseError {
+            line: 2,
+            text: "???".to_owned(),
+        }]
+    );
+    assert!(!set.render(0, 30, 2, None).unwrap().is_empty());
+}
+
 #[test]
 fn invalid_input_errors_are_equal_for_equal_inputs() {
     let err_a = generate_arrangements(fixture(0)).expect_err("0 must be rejected");
@@ -165,9 +358,175 @@ fn normalized_input_variants_are_publicly_constructible() {
     // Construct each variant directly to pin its public shape.
     let _playable = NormalizedBeat::Playable {
         pitches: vec!["E2".to_owned()],
+        chord: None,
     };
     let _rest = NormalizedBeat::Rest;
     let _measure_break = NormalizedBeat::MeasureBreak;
+    let _repeat_start = NormalizedBeat::RepeatStart;
+    let _repeat_end = NormalizedBeat::RepeatEnd;
+    let _ending = NormalizedBeat::Ending { number: 1 };
+    let _section = NormalizedBeat::Section {
+        label: "Verse".to_owned(),
+    };
+    let _technique = NormalizedBeat::Technique {
+        technique: Technique::Slide,
+    };
+}
+
+#[test]
+fn harmonic_fingerings_are_opt_in() {
+    let lines = parse_lines("E6".to_owned()).unwrap();
+    let num = NumArrangements::try_new(1).unwrap();
+    assert!(matches!(
+        create_arrangements(
+            Guitar::default(),
+            lines.clone(),
+            num,
+            DifficultyWeights::standard(),
+            None
+        ),
+        Err(TabError::UnplayablePitches { .. })
+    ));
+
+    let guitar = Guitar::default().with_harmonic_mode(HarmonicMode::Natural);
+    let arrangements =
+        create_arrangements(guitar, lines, num, DifficultyWeights::standard(), None).unwrap();
+    let Line::Playable(fingerings) = &arrangements[0].lines()[0] else {
+        panic!("expected a playable beat");
+    };
+    assert_eq!(fingerings[0].harmonic(), Some(Harmonic::Natural));
+    assert_eq!(fingerings[0].fret(), 5);
+}
+
+#[test]
+fn techniques_parse_and_render_flush() {
+    let lines = parse_lines("E4\n/\nF#4".to_owned()).unwrap();
+    assert_eq!(lines[1], Line::Technique(Technique::Slide));
+    assert_eq!(Technique::from_symbol("/"), Some(Technique::Slide));
+    assert!(Technique::Slide.links_beats());
+
+    let set = generate_arrangements(TabInput::new("E4\n/\nF#4", "standard", 18, 0, 1)).unwrap();
+    let tab = set.render(0, 20, 1, None).unwrap();
+    assert!(tab.contains("-0/2-"), "got:\n{tab}");
+}
+
+#[test]
+fn let_ring_reports_cut_off_notes() {
+    let lines = parse_lines("E2\nlet ring 2\nE2".to_owned()).unwrap();
+    assert_eq!(lines[1], Line::LetRing(Some(2)));
+
+    let num = NumArrangements::try_new(1).unwrap();
+    let arrangements = create_arrangements(
+        Guitar::default(),
+        lines,
+        num,
+        DifficultyWeights::standard(),
+        None,
+    )
+    .unwrap();
+    let expected = CutOffNote {
+        pitch: "E2".to_owned(),
+        string: 6,
+        line: 1,
+        cut_off_line: 3,
+    };
+    assert_eq!(
+        arrangements[0].cut_off_notes(),
+        std::slice::from_ref(&expected)
+    );
+
+    let set = generate_arrangements(TabInput::new("E2\nring\nE2", "standard", 18, 0, 1)).unwrap();
+    assert_eq!(set.cut_off_notes(0).unwrap(), vec![expected]);
+}
+
+#[test]
+fn chord_symbols_expand_to_voicings() {
+    let parsed_lines = parse_input("[Verse]\n{C}\nchord: Cmaj7/E".to_owned()).unwrap();
+    assert_eq!(parsed_lines[0].chord, None);
+    assert_eq!(parsed_lines[1].chord, ChordSymbol::from_symbol("C"));
+    assert_eq!(parsed_lines[2].chord.unwrap().to_string(), "Cmaj7/E");
+
+    let arrangements = create_parsed_arrangements(
+        Guitar::default(),
+        parsed_lines,
+        NumArrangements::try_new(1).unwrap(),
+        DifficultyWeights::standard(),
+        None,
+    )
+    .unwrap();
+    let Line::Playable(fingerings) = &arrangements[0].lines()[1] else {
+        panic!("expected a playable beat");
+    };
+    assert_eq!(fingerings.len(), 5);
+
+    let err = generate_arrangements(TabInput::new("{F#m}", "standard", 0, 0, 1)).unwrap_err();
+    assert_eq!(
+        err,
+        TabError::UnplayableChord {
+            value: "F#m".to_owned(),
+            line: 1,
+        }
+    );
+}
+
+#[test]
+fn voice_columns_tag_fingerings() {
+    let parsed_lines = parse_input("E4 ; A3:2\nG4 ;".to_owned()).unwrap();
+    assert_eq!(
+        parsed_lines[0].line,
+        Line::Playable(vec![Pitch::E4, Pitch::A3])
+    );
+
+    let expected_bass = VoiceColumn {
+        voice: Voice::Bass,
+        pitches: vec![Pitch::A3],
+        beats: 2,
+    };
+    assert_eq!(parsed_lines[0].voices.as_ref().unwrap()[1], expected_bass);
+
+    let num = NumArrangements::try_new(1).unwrap();
+    let arrangements = create_parsed_arrangements(
+        Guitar::default(),
+        parsed_lines,
+        num,
+        DifficultyWeights::standard(),
+        None,
+    )
+    .unwrap();
+    let Line::Playable(fingerings) = &arrangements[0].lines()[0] else {
+        panic!("expected a playable beat");
+    };
+    let bass = fingerings.iter().find(|f| f.pitch() == Pitch::A3).unwrap();
+    assert_eq!(bass.voice(), Some(Voice::Bass));
+}
+
+#[test]
+fn repeat_markers_parse_and_expand() {
+    let lines = parse_lines("[Verse]\n|:\nE2\n:|".to_owned()).unwrap();
+    assert_eq!(
+        lines,
+        vec![
+            Line::Section("Verse".to_owned()),
+            Line::RepeatStart,
+            Line::Playable(vec![Pitch::E2]),
+            Line::RepeatEnd,
+        ]
+    );
+    assert!(!lines[1].is_beat());
+
+    let expanded = expand_repeats(&lines);
+    assert_eq!(
+        expanded,
+        vec![
+            Line::Section("Verse".to_owned()),
+            Line::Playable(vec![Pitch::E2]),
+            Line::Playable(vec![Pitch::E2]),
+        ]
+    );
+
+    let input = TabInput::new("|:\nE2\n:|", "standard", 18, 0, 1).with_expand_repeats(true);
+    let set = generate_arrangements(input).unwrap();
+    assert_eq!(set.normalized_input().len(), 2);
 }
 
 #[test]
@@ -581,3 +940,169 @@ fn invalid_difficulty_weight_is_rejected() {
         TabError::DifficultyWeightOutOfRange { field: "movement" }
     );
 }
+
+#[test]
+fn labels_and_header_are_public() {
+    let guitar = Guitar::new(create_string_tuning(&[Pitch::E4]).unwrap(), 18, 2).unwrap();
+    assert_eq!(guitar.capo(), 2);
+    assert_eq!(
+        render_tab_header(&guitar, 1.0),
+        "Tuning: E\nCapo: 2\nDifficulty: 1.0\n"
+    );
+    let lines = parse_lines("F#4\n\nG#4".to_owned()).unwrap();
+    let arrangements = create_arrangements(
+        guitar.clone(),
+        lines,
+        NumArrangements::try_new(1).unwrap(),
+        DifficultyWeights::standard(),
+        None,
+    )
+    .unwrap();
+    let tab = render_tab_with_labels(arrangements[0].lines(), &guitar, 20, 1, None);
+    assert_eq!(tab, "   1\n\nE|-0---2------------\n\n");
+}
+
+#[test]
+fn render_options_are_public() {
+    let lines = parse_lines("E4\n\nF4".to_owned()).unwrap();
+    let arrangements = create_arrangements(
+        Guitar::default(),
+        lines,
+        NumArrangements::try_new(1).unwrap(),
+        DifficultyWeights::standard(),
+        None,
+    )
+    .unwrap();
+    let options = RenderOptions::new(16, 1)
+        .with_playback(0)
+        .with_bar_char('|')
+        .with_dash_style(DashStyle::Unicode)
+        .with_label_mode(LabelMode::Strings)
+        .with_empty_string_glyph(' ');
+    let tab = render_tab_with_options(arrangements[0].lines(), &Guitar::default(), &options);
+    assert!(tab.starts_with("   1\n   ▼\ne|─0─ ─1────────\nB|─ ─ ─ ────────\n"));
+    assert_eq!(options.playback, Some(0));
+}
+
+#[test]
+fn durations_are_public() {
+    let input = "E4 @h\nF4 @q";
+    let durations = parse_input(input.to_owned())
+        .unwrap()
+        .iter()
+        .map(|parsed| parsed.duration)
+        .collect::<Vec<_>>();
+    assert_eq!(
+        durations,
+        vec![Duration::from_symbol("h"), Duration::from_symbol("q")]
+    );
+    assert_eq!(durations[0].unwrap().to_string(), "h");
+
+    let arrangements = create_arrangements(
+        Guitar::default(),
+        parse_lines(input.to_owned()).unwrap(),
+        NumArrangements::try_new(1).unwrap(),
+        DifficultyWeights::standard(),
+        None,
+    )
+    .unwrap();
+    let tab = render_tab_with_rhythm(
+        arrangements[0].lines(),
+        &durations,
+        &Guitar::default(),
+        &RenderOptions::new(12, 1).with_rhythm_row(true),
+    );
+    assert!(tab.starts_with(" h   q\n\n-0---1------\n"));
+}
+
+#[test]
+fn sets_export_and_import() {
+    let set = generate_arrangements(TabInput::new("E2\nA2\n\nD3G3", "standard", 18, 0, 2)).unwrap();
+
+    let from_json = ArrangementSet::import_json(&set.export_json()).unwrap();
+    let from_binary = ArrangementSet::import_binary(&set.export_binary()).unwrap();
+    for imported in [&from_json, &from_binary] {
+        assert_eq!(imported.len(), set.len());
+        assert_eq!(imported.difficulty(1), set.difficulty(1));
+        assert_eq!(imported.normalized_input(), set.normalized_input());
+        assert_eq!(imported.render(0, 30, 2, None), set.render(0, 30, 2, None));
+    }
+
+    assert!(matches!(
+        ArrangementSet::import_json("{\"version\":99}"),
+        Err(TabError::SnapshotVersionUnsupported { version: 99, .. })
+    ));
+    assert!(matches!(
+        ArrangementSet::import_binary(&[]),
+        Err(TabError::SnapshotInvalid { .. })
+    ));
+}
+
+#[test]
+fn arrangement_lines_are_public() {
+    let set = generate_arrangements(TabInput::new("E2\n\n---\nA2", "standard", 18, 0, 1)).unwrap();
+    let lines: Vec<ArrangementLine> = set.lines(0).unwrap();
+    assert_eq!(lines[1], ArrangementLine::Rest);
+    assert_eq!(lines[2], ArrangementLine::MeasureBreak);
+    let ArrangementLine::Playable { fingerings } = &lines[3] else {
+        panic!("expected a playable line, got {:?}", lines[3]);
+    };
+    let note: &NoteFingering = &fingerings[0];
+    assert_eq!((note.string, note.fret, note.pitch.as_str()), (5, 0, "A2"));
+}
+
+#[test]
+fn batches_are_public() {
+    let report: BatchReport = arrange_batch(
+        &fixture(1),
+        [BatchInput::new("open", "E2"), BatchInput::new("low", "C2")],
+    )
+    .unwrap();
+    let entry: &BatchEntry = &report.entries()[0];
+    assert_eq!(entry.name, "open");
+    assert!(entry.result.is_ok());
+    assert_eq!(report.num_failed(), 1);
+
+    let summary: Vec<BatchSummaryRow> = report.summary();
+    assert_eq!(summary[0].difficulty, Some(0.0));
+    assert_eq!(
+        summary[1].unplayable_pitches,
+        [UnplayablePitch {
+            value: "C2".to_owned(),
+            line: 1
+        }]
+    );
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn core_types_serialize() {
+    let guitar = Guitar::default();
+    let json = serde_json::to_string(&guitar).unwrap();
+    assert_eq!(serde_json::from_str::<Guitar>(&json).unwrap(), guitar);
+    assert_eq!(
+        serde_json::to_string(&Pitch::CSharpDFlat4).unwrap(),
+        "\"C#4\""
+    );
+    let lines: Vec<Line<BeatVec<Pitch>>> = parse_lines("E2\n---".to_owned()).unwrap();
+    assert_eq!(
+        serde_json::to_string(&lines).unwrap(),
+        r#"[{"kind":"playable","notes":["E2"]},{"kind":"measureBreak"}]"#
+    );
+}
+
+#[cfg(feature = "wasm")]
+#[test]
+fn worker_protocol_is_public() {
+    use guitar_tab_generator::{WorkerRequest, WorkerResponse, handle_worker_request};
+
+    let response = handle_worker_request(WorkerRequest {
+        id: 3,
+        tab_input: fixture(1),
+    });
+    let WorkerResponse::Arranged { id, snapshot } = response else {
+        panic!("expected an arranged response, got {response:?}");
+    };
+    assert_eq!(id, 3);
+    assert_eq!(ArrangementSet::import_binary(&snapshot).unwrap().len(), 1);
+}
@@ -0,0 +1,84 @@
+//! End-to-end test of the `guitar-tab-lsp` binary.
+//!
+//! `src/lsp.rs` tests the server over an in-memory connection. This spawns the built binary
+//! to cover the `Content-Length` framing an editor sees.
+//!
+//! Empty without the `lsp` feature, which the binary requires.
+#![cfg(feature = "lsp")]
+
+use serde_json::{Value, json};
+use std::{
+    io::{BufRead, BufReader, Write},
+    process::{Command, Stdio},
+};
+
+fn send(mut stdin: impl Write, message: Value) {
+    let body = message.to_string();
+    write!(stdin, "Content-Length: {}\r\n\r\n{body}", body.len()).unwrap();
+    stdin.flush().unwrap();
+}
+
+fn receive(stdout: &mut impl BufRead) -> Value {
+    let mut length = 0;
+    loop {
+        let mut header = String::new();
+        stdout.read_line(&mut header).unwrap();
+        let header = header.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        if let Some(value) = header.strip_prefix("Content-Length: ") {
+            length = value.parse().unwrap();
+        }
+    }
+    let mut body = vec![0; length];
+    stdout.read_exact(&mut body).unwrap();
+    serde_json::from_slice(&body).unwrap()
+}
+
+#[test]
+fn publishes_diagnostics_and_shuts_down() {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_guitar-tab-lsp"))
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("the guitar-tab-lsp binary runs");
+    let stdin = child.stdin.take().unwrap();
+    let mut stdout = BufReader::new(child.stdout.take().unwrap());
+
+    send(
+        &stdin,
+        json!({ "jsonrpc": "2.0", "id": 1, "method": "initialize", "params": { "capabilities": {} } }),
+    );
+    let response = receive(&mut stdout);
+    assert_eq!(
+        response["result"]["capabilities"]["codeActionProvider"],
+        true
+    );
+    send(
+        &stdin,
+        json!({ "jsonrpc": "2.0", "method": "initialized", "params": {} }),
+    );
+
+    send(
+        &stdin,
+        json!({ "jsonrpc": "2.0", "method": "textDocument/didOpen", "params": { "textDocument": {
+            "uri": "file:///song.txt", "languageId": "guitar-tab", "version": 1, "text": "E2\nH2\n",
+        }}}),
+    );
+    let published = receive(&mut stdout);
+    assert_eq!(published["method"], "textDocument/publishDiagnostics");
+    assert_eq!(
+        published["params"]["diagnostics"][0]["range"]["start"]["line"],
+        1
+    );
+
+    send(
+        &stdin,
+        json!({ "jsonrpc": "2.0", "id": 2, "method": "shutdown" }),
+    );
+    assert_eq!(receive(&mut stdout)["id"], 2);
+    send(&stdin, json!({ "jsonrpc": "2.0", "method": "exit" }));
+    drop(stdin);
+    assert!(child.wait().unwrap().success());
+}
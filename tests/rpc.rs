@@ -0,0 +1,42 @@
+//! End-to-end test of the `guitar-tab-rpc` binary.
+//!
+//! `src/rpc.rs` tests the server through `Server` and `rpc::run`. This spawns the built
+//! binary to cover the stdio framing a client sees.
+//!
+//! Empty without the `rpc` feature, which the binary requires.
+#![cfg(feature = "rpc")]
+
+use std::{
+    io::{BufRead, BufReader, Write},
+    process::{Command, Stdio},
+};
+
+#[test]
+fn keeps_handles_across_requests() {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_guitar-tab-rpc"))
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("the guitar-tab-rpc binary runs");
+    let stdin = child.stdin.take().unwrap();
+    let mut stdout = BufReader::new(child.stdout.take().unwrap());
+    let mut call = |request: &str| {
+        writeln!(&stdin, "{request}").unwrap();
+        let mut line = String::new();
+        stdout.read_line(&mut line).unwrap();
+        serde_json::from_str::<serde_json::Value>(&line).unwrap()
+    };
+
+    let response = call(
+        r#"{"jsonrpc":"2.0","id":1,"method":"generateArrangements","params":{"input":"E2","tuningName":"standard","guitarNumFrets":18,"guitarCapo":0,"numArrangements":1}}"#,
+    );
+    let handle = response["result"]["handle"].clone();
+    let response = call(&format!(
+        r#"{{"jsonrpc":"2.0","id":2,"method":"render","params":{{"handle":{handle},"index":0,"width":20,"padding":1}}}}"#
+    ));
+    assert!(response["result"].as_str().unwrap().contains("-0-"));
+
+    // Closing stdin ends the server.
+    drop(stdin);
+    assert!(child.wait().unwrap().success());
+}
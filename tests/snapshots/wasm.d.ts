@@ -41,8 +41,44 @@ export interface TabInput {
      * ranking. Validated at the boundary by [`generate_arrangements`].
      */
     difficultyWeights?: DifficultyWeightsInput;
+    /**
+     * Recover from unparseable text instead of failing with [`TabError::Parse`]. Bad
+     * substrings are dropped, lines left without a pitch become rests, and the dropped text
+     * is reported on [`ArrangementSet::parse_warnings`]. Omitted means `false`.
+     */
+    lenientParse?: boolean;
+    /**
+     * Unroll repeats and volta endings with [`expand_repeats`] before arranging, so the
+     * arrangement, the normalized input and the playback cursor all follow the order the
+     * piece is played in. Omitted means `false`.
+     */
+    expandRepeats?: boolean;
+    /**
+     * Harmonic fingerings the arranger may use, on top of fretted notes. Omitted means
+     * `\"off\"`. `\"natural\"` adds natural harmonics at frets 12, 7, 5 and 4, and
+     * `\"naturalAndArtificial\"` also adds artificial harmonics an octave above a fretted note.
+     */
+    harmonicMode?: HarmonicMode;
 }
 
+/**
+ * How a harmonic `PitchFingering` is sounded.
+ *
+ * A `Natural` harmonic touches an open string above fret 12, 7, 5 or 4, and its `fret` is
+ * the touched fret. An `Artificial` harmonic frets a note and touches the string 12 frets
+ * higher, sounding an octave up, and its `fret` is the fretted one.
+ */
+export type Harmonic = "natural" | "artificial";
+
+/**
+ * Which harmonic fingerings the arranger may choose, on top of the fretted ones.
+ *
+ * `Off` by default, which keeps every arrangement fretted. Harmonics carry an extra
+ * difficulty cost, so the arranger only reaches for one when it beats the fretted options
+ * or when the pitch is out of fretted range.
+ */
+export type HarmonicMode = "off" | "natural" | "naturalAndArtificial";
+
 /**
  * Named tuning presets. Parsed case-insensitively from strings.
  *
@@ -57,7 +93,208 @@ export type TuningName = "openG" | "openD" | "c6" | "dsus4" | "dropD" | "dropC"
  * Serialized as a discriminated union tagged by `kind`, so JS code can `switch (b.kind)`
  * instead of comparing strings.
  */
-export type NormalizedBeat = { kind: "playable"; pitches: string[] } | { kind: "rest" } | { kind: "measureBreak" };
+export type NormalizedBeat = { kind: "playable"; pitches: string[]; chord?: string } | { kind: "rest" } | { kind: "measureBreak" } | { kind: "repeatStart" } | { kind: "repeatEnd" } | { kind: "ending"; number: number } | { kind: "section"; label: string } | { kind: "technique"; technique: Technique } | { kind: "letRing"; beats: number | null };
+
+/**
+ * One line of an arrangement read back from `ArrangementSet::lines`, at the same index as
+ * its `NormalizedBeat`.
+ *
+ * Tagged by `kind` like `NormalizedBeat`. A `playable` line lists where each of its notes is
+ * played, so a UI can draw its own fretboard instead of reading the rendered tab.
+ */
+export type ArrangementLine = { kind: "playable"; fingerings: NoteFingering[] } | { kind: "rest" } | { kind: "measureBreak" } | { kind: "repeatStart" } | { kind: "repeatEnd" } | { kind: "ending"; number: number } | { kind: "section"; label: string } | { kind: "technique"; technique: Technique } | { kind: "letRing"; beats: number | null };
+
+/**
+ * Where one note of a `playable` [`ArrangementLine`] is played: its string (1 is the
+ * highest), its fret (0 for an open string) and its sounding pitch.
+ *
+ * `harmonic` is set for a note sounded as a harmonic, and `voice` for a note from
+ * multi-voice input.
+ */
+export interface NoteFingering {
+    string: number;
+    fret: number;
+    pitch: string;
+    harmonic?: Harmonic;
+    voice?: Voice;
+}
+
+/**
+ * A playing technique written on its own line between two beats (or after one, for
+ * vibrato).
+ *
+ * `HammerOn`, `PullOff`, `Slide` and `Bend` link the beat before them to the beat after
+ * them: both beats must be played on the same strings, and the rendered tab joins them as
+ * `5h7`, `7p5`, `7/9` or `7b9`. `Vibrato` marks the beat before it and renders as `7~`.
+ */
+export type Technique = "hammerOn" | "pullOff" | "slide" | "bend" | "vibrato";
+
+/**
+ * The part a note plays in multi-voice input.
+ *
+ * A line split into voice columns with `;` reads as melody first and bass last, with any
+ * columns between them as inner voices. The arranger steers the melody onto the treble
+ * strings and the bass onto the bass strings. Inner voices go anywhere.
+ */
+export type Voice = "melody" | "inner" | "bass";
+
+/**
+ * A let-ring note that a later note on the same string cuts off, with 1-indexed line numbers.
+ *
+ * `line` is the ringing note's line and `cut_off_line` the line of the note that silences it.
+ */
+export interface CutOffNote {
+    pitch: string;
+    string: number;
+    line: number;
+    cutOffLine: number;
+}
+
+/**
+ * A barre across the strings from `from_string` down to `to_string` at `fret`.
+ * `from_string` is the higher string number, the lower-pitched end of the barre.
+ */
+export interface Barre {
+    fret: number;
+    fromString: number;
+    toString: number;
+}
+
+/**
+ * The fretboard shape of one chord beat, as drawn in a chord diagram.
+ */
+export interface ChordShape {
+    /**
+     * Fret on each string from string 1 down. `None` marks a muted string and `Some(0)` an
+     * open one.
+     */
+    frets: (number | null)[];
+    /**
+     * Top fret of the diagram window. `1` when the window starts at the nut.
+     */
+    baseFret: number;
+    /**
+     * Number of fret rows in the window.
+     */
+    numFretRows: number;
+    barre: Barre | null;
+}
+
+/**
+ * Where the playback indicator points in a rendered tab: its row group and the character
+ * offset of the `▼` and `▲` in that group's indicator lines.
+ */
+export interface PlaybackIndicatorPosition {
+    rowGroupIndex: number;
+    columnIndex: number;
+}
+
+/**
+ * Where one rendered column starts: its row group and its character offset in the row.
+ */
+export interface ColumnPosition {
+    rowGroupIndex: number;
+    columnIndex: number;
+}
+
+/**
+ * Where one beat landed in a tab from [`render_tab_with_layout`]. The beat's cells run from
+ * `start.column_index` up to, not including, `end_column_index` on every string row of
+ * row group `start.row_group_index`.
+ */
+export interface BeatPosition {
+    /**
+     * 0-indexed beat number, counted like the `playback` argument.
+     */
+    beatIndex: number;
+    start: ColumnPosition;
+    endColumnIndex: number;
+    /**
+     * 1-indexed line the beat came from: its line in `arrangement_lines` for
+     * [`render_tab_with_layout`], and its input line for `ArrangementSet::render_with_layout`.
+     */
+    line: number;
+}
+
+/**
+ * A rendered ASCII tab with a map of where each beat landed in it.
+ */
+export interface RenderedTab {
+    /**
+     * The tab exactly as [`render_tab`] renders it, or as [`render_tab_with_options`] does
+     * for `ArrangementSet::render_with_layout`.
+     */
+    tab: string;
+    /**
+     * One entry per beat, in beat order.
+     */
+    beats: BeatPosition[];
+    /**
+     * 0-indexed line of `tab` holding the first string's row, for each row group.
+     */
+    rowGroupLines: number[];
+    playbackIndicator: PlaybackIndicatorPosition | null;
+}
+
+/**
+ * Which optional label rows a rendered tab carries. `Off` by default, which draws the bare
+ * tab.
+ */
+export type LabelMode = "off" | "chordNames" | "strings" | "all";
+
+/**
+ * The character a tab draws its strings with. `Ascii` by default.
+ */
+export type DashStyle = "ascii" | "unicode";
+
+/**
+ * Everything [`render_tab_with_options`] needs besides the lines and the guitar.
+ *
+ * Crosses the WASM boundary via `tsify`, where only `width` and `padding` are required.
+ * Build one with [`RenderOptions::new`] and the `with_*` methods. The defaults match
+ * [`render_tab`]. Every glyph should be a single character column wide, or the rows lose
+ * their alignment.
+ *
+ * `ArrangementSet` renders every format from these options. The text, layout and HTML
+ * renders apply every field. The SVG render draws its strings and bars as lines, so it
+ * ignores `bar_char`, `dash_style` and `empty_string_glyph`.
+ */
+export interface RenderOptions {
+    /**
+     * Character width of each row group. Rows wrap to a new group when they reach it.
+     */
+    width: number;
+    /**
+     * Number of dashes between beats.
+     */
+    padding: number;
+    /**
+     * 0-indexed beat to draw the `▼`/`▲` playback indicator around. Omitted means none.
+     */
+    playback?: number;
+    /**
+     * Character for measure breaks and the bars of repeat signs. Omitted means `|`.
+     */
+    barChar?: string;
+    /**
+     * Omitted means `\"ascii\"`.
+     */
+    dashStyle?: DashStyle;
+    /**
+     * Omitted means `\"off\"`.
+     */
+    labelMode?: LabelMode;
+    /**
+     * Character drawn in a beat's column on each string the beat leaves silent. Omitted
+     * means the dash of `dash_style`.
+     */
+    emptyStringGlyph?: string;
+    /**
+     * Print each beat's duration on a row right above the strings, when the input gives
+     * durations. Omitted means `false`.
+     */
+    rhythmRow?: boolean;
+}
 
 /**
  * One unparseable substring in the input, with its 1-indexed line number.
@@ -78,6 +315,21 @@ export interface DifficultyWeightsInput {
     position: number;
 }
 
+/**
+ * One arranging job posted to a worker. `id` comes back on the response, so a page can
+ * match responses to the requests it has in flight.
+ */
+export interface WorkerRequest {
+    id: number;
+    tabInput: TabInput;
+}
+
+/**
+ * The outcome of a [`WorkerRequest`], tagged by `kind`. Never thrown, so a worker can post
+ * failures back like successes.
+ */
+export type WorkerResponse = { kind: "arranged"; id: number; snapshot: Uint8Array } | { kind: "failed"; id: number; error: TabError };
+
 /**
  * Top-level error variant for the WASM boundary.
  *
@@ -85,7 +337,7 @@ export interface DifficultyWeightsInput {
  * attribute requires external matches to include a wildcard arm. JS consumers should keep a
  * `default` arm in any `switch (err.kind)`.
  */
-export type TabError = { kind: "parse"; errors: ParseError[] } | { kind: "inputTooManyLines"; max: number } | { kind: "numFretsTooHigh"; numFrets: number; max: number } | { kind: "capoTooHigh"; capo: number; max: number } | { kind: "capoExceedsFrets"; capo: number; numFrets: number } | { kind: "stringNumberOutOfRange"; value: number; max: number } | { kind: "openPitchOutOfRange"; string: number; semitones: number } | { kind: "fretRangeExceedsPitchRange"; openPitch: string; playableFrets: number } | { kind: "unplayablePitches"; pitches: UnplayablePitch[] } | { kind: "noArrangementsFound" } | { kind: "numArrangementsOutOfRange"; value: number; max: number } | { kind: "tuningNameUnknown"; value: string } | { kind: "indexOutOfBounds"; index: number; len: number } | { kind: "renderWidthTooSmall"; width: number; min: number } | { kind: "difficultyWeightOutOfRange"; field: string };
+export type TabError = { kind: "parse"; errors: ParseError[] } | { kind: "inputTooManyLines"; max: number } | { kind: "numFretsTooHigh"; numFrets: number; max: number } | { kind: "capoTooHigh"; capo: number; max: number } | { kind: "capoExceedsFrets"; capo: number; numFrets: number } | { kind: "stringNumberOutOfRange"; value: number; max: number } | { kind: "openPitchOutOfRange"; string: number; semitones: number } | { kind: "fretRangeExceedsPitchRange"; openPitch: string; playableFrets: number } | { kind: "unplayablePitches"; pitches: UnplayablePitch[] } | { kind: "noArrangementsFound" } | { kind: "numArrangementsOutOfRange"; value: number; max: number } | { kind: "tuningNameUnknown"; value: string } | { kind: "indexOutOfBounds"; index: number; len: number } | { kind: "renderWidthTooSmall"; width: number; min: number } | { kind: "difficultyWeightOutOfRange"; field: string } | { kind: "unplayableChord"; value: string; line: number } | { kind: "snapshotInvalid"; reason: string } | { kind: "snapshotVersionUnsupported"; version: number; supported: number };
 
 
 /**
@@ -99,6 +351,39 @@ export class ArrangementSet {
     private constructor();
     free(): void;
     [Symbol.dispose](): void;
+    /**
+     * ASCII chord diagrams of [`ArrangementSet::chord_shapes`], in the same order.
+     *
+     * # Errors
+     *
+     * Returns [`TabError::IndexOutOfBounds`] when `index >= self.len`.
+     */
+    chordDiagrams(index: number): string[];
+    /**
+     * SVG chord diagrams of [`ArrangementSet::chord_shapes`], in the same order.
+     *
+     * # Errors
+     *
+     * Returns [`TabError::IndexOutOfBounds`] when `index >= self.len`.
+     */
+    chordDiagramsSvg(index: number): string[];
+    /**
+     * The distinct chord shapes the arrangement at `index` uses, in order of first
+     * appearance, for a "chords used" legend. A chord is a beat of three or more notes.
+     *
+     * # Errors
+     *
+     * Returns [`TabError::IndexOutOfBounds`] when `index >= self.len`.
+     */
+    chordShapes(index: number): ChordShape[];
+    /**
+     * Let-ring notes that the arrangement at `index` cuts off by replaying their string.
+     *
+     * # Errors
+     *
+     * Returns [`TabError::IndexOutOfBounds`] when `index >= self.len`.
+     */
+    cutOffNotes(index: number): CutOffNote[];
     /**
      * Difficulty score for the arrangement at `index`. Lower is easier.
      *
@@ -107,6 +392,45 @@ export class ArrangementSet {
      * Returns [`TabError::IndexOutOfBounds`] when `index >= self.len`.
      */
     difficulty(index: number): number;
+    /**
+     * Serializes the set like [`ArrangementSet::export_json`], as compact CBOR bytes.
+     * [`ArrangementSet::import_binary`] reloads it.
+     */
+    exportBinary(): Uint8Array;
+    /**
+     * Serializes the whole set as a versioned JSON snapshot: the arrangements with their
+     * fingerings, the guitar, the normalized input and the `TabInput` that generated it.
+     * [`ArrangementSet::import_json`] reloads it without pathfinding.
+     */
+    exportJson(): string;
+    /**
+     * Rebuilds a set from [`ArrangementSet::export_binary`] output, without parsing or
+     * pathfinding.
+     *
+     * # Errors
+     *
+     * Returns the same errors as [`ArrangementSet::import_json`].
+     */
+    static importBinary(bytes: Uint8Array): ArrangementSet;
+    /**
+     * Rebuilds a set from [`ArrangementSet::export_json`] output, without parsing or
+     * pathfinding.
+     *
+     * # Errors
+     *
+     * Returns [`TabError::SnapshotVersionUnsupported`] for a snapshot in another format
+     * version, and [`TabError::SnapshotInvalid`] for malformed or inconsistent data.
+     */
+    static importJson(json: string): ArrangementSet;
+    /**
+     * The lines of the arrangement at `index`, each at the same index as its entry in
+     * [`ArrangementSet::normalized_input`], with the string, fret and pitch of every note.
+     *
+     * # Errors
+     *
+     * Returns [`TabError::IndexOutOfBounds`] when `index >= self.len`.
+     */
+    lines(index: number): ArrangementLine[];
     /**
      * Largest non-zero fret span across any beat in the arrangement at `index`.
      *
@@ -127,6 +451,66 @@ export class ArrangementSet {
      * [`TabError::IndexOutOfBounds`] shared by every indexed accessor.
      */
     render(index: number, width: number, padding: number, playback?: number | null): string;
+    /**
+     * Renders the arrangement at `index` as an HTML tab like [`render_tab_html`]. The text
+     * matches [`ArrangementSet::render_with_options`] for the same `options`, with each beat
+     * cell wrapped in a span carrying `data-beat` and `data-line`.
+     *
+     * # Errors
+     *
+     * Returns the same errors as [`ArrangementSet::render_with_options`].
+     */
+    renderHtml(index: number, options: RenderOptions): string;
+    /**
+     * Renders the arrangement at `index` as an SVG tab like [`render_tab_svg`], laid out by
+     * `options` and wrapped at the same beats as [`ArrangementSet::render_with_options`]. The
+     * drawing has no dash or bar characters, so `bar_char`, `dash_style` and
+     * `empty_string_glyph` do not apply.
+     *
+     * # Errors
+     *
+     * Returns the same errors as [`ArrangementSet::render_with_options`].
+     */
+    renderSvg(index: number, options: RenderOptions): string;
+    /**
+     * Renders the arrangement at `index` like [`ArrangementSet::render`] with a chord-name row
+     * above the tab, using [`render_tab_with_chord_names`].
+     *
+     * # Errors
+     *
+     * Returns the same errors as [`ArrangementSet::render`].
+     */
+    renderWithChordNames(index: number, width: number, padding: number, playback?: number | null): string;
+    /**
+     * Renders the arrangement at `index` like [`ArrangementSet::render`] under a header
+     * listing the tuning, capo and difficulty, with string names before each row and measure
+     * numbers above it. See [`render_tab_header`] and [`render_tab_with_labels`].
+     *
+     * # Errors
+     *
+     * Returns the same errors as [`ArrangementSet::render`].
+     */
+    renderWithLabels(index: number, width: number, padding: number, playback?: number | null): string;
+    /**
+     * Renders the arrangement at `index` like [`ArrangementSet::render_with_options`] and also
+     * returns where each beat landed in the tab, with the input line it came from.
+     *
+     * # Errors
+     *
+     * Returns the same errors as [`ArrangementSet::render_with_options`].
+     */
+    renderWithLayout(index: number, options: RenderOptions): RenderedTab;
+    /**
+     * Renders the arrangement at `index` as laid out by `options`, using
+     * [`render_tab_with_options`]. [`ArrangementSet::render`] and the other text renders are
+     * this method with the matching options.
+     *
+     * # Errors
+     *
+     * Returns the same errors as [`ArrangementSet::render`], for `options.width` and
+     * `options.padding`.
+     */
+    renderWithOptions(index: number, options: RenderOptions): string;
     /**
      * Returns true when `len == 0`.
      */
@@ -145,6 +529,13 @@ export class ArrangementSet {
      * cache in the rerender path; that pattern is the intended consumer shape.
      */
     readonly normalizedInput: NormalizedBeat[];
+    /**
+     * Unparseable substrings that a lenient parse dropped, in input order. Always empty
+     * unless `TabInput::lenient_parse` was set, since a strict parse fails instead.
+     *
+     * Returns a fresh `Vec` on each call.
+     */
+    readonly parseWarnings: ParseError[];
 }
 
 /**
@@ -157,8 +548,8 @@ export class ArrangementSet {
  *
  * - Input-shape validation: [`TabError::NumArrangementsOutOfRange`], [`TabError::TuningNameUnknown`],
  *   [`TabError::NumFretsTooHigh`], [`TabError::CapoTooHigh`], [`TabError::CapoExceedsFrets`].
- * - Parser: [`TabError::Parse`] (carries `Vec<ParseError>` with line/text per unparseable substring),
- *   [`TabError::InputTooManyLines`] (input exceeds the 65,535-line cap).
+ * - Parser: [`TabError::Parse`] (carries `Vec<ParseError>` with line/text per unparseable substring,
+ *   strict mode only), [`TabError::InputTooManyLines`] (input exceeds the 65,535-line cap).
  * - Pathfinding: [`TabError::UnplayablePitches`] (one or more pitches reach no string),
  *   [`TabError::NoArrangementsFound`] (every pitch reaches the guitar but no valid combination exists,
  *   for example duplicate pitches in a single beat that the no-duplicate-strings constraint filters away).
@@ -197,3 +588,8 @@ export function generateArrangements(tab_input: TabInput): ArrangementSet;
  */
 export function getTuningNames(): TuningName[];
 
+/**
+ * Arranges the input of `request` like [`generate_arrangements`] and returns the set as a
+ * binary snapshot, or the error it failed with.
+ */
+export function handleWorkerRequest(request: WorkerRequest): WorkerResponse;
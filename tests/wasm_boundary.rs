@@ -7,8 +7,9 @@
 //! separately by the `tests/snapshots/wasm.d.ts` surface diff. These tests confirm the code
 //! executes correctly under the wasm target.
 //!
-//! Empty on non-wasm targets so the host `cargo test` lane skips it.
-#![cfg(target_arch = "wasm32")]
+//! Empty on non-wasm targets so the host `cargo test` lane skips it, and without the default
+//! `wasm` feature, which the boundary needs.
+#![cfg(all(target_arch = "wasm32", feature = "wasm"))]
 
 use guitar_tab_generator::{TabError, TabInput, generate_arrangements};
 use wasm_bindgen_test::wasm_bindgen_test;